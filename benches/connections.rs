@@ -0,0 +1,55 @@
+//! Construction and traversal of sparse graphs, where most nodes only have a few connections.
+//!
+//! Compare the default `Vec` storage with the inline storage of the `smallvec` feature:
+//! ```sh
+//! cargo bench --bench connections
+//! cargo bench --bench connections --features smallvec
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use fast_graph::algorithms::IterDepthFirst;
+use fast_graph::{Graph, GraphInterface, NodeID};
+
+/// A ring where every node also has an edge to the node `stride` ahead, i.e. every node has degree 4.
+fn sparse_graph(nodes: usize, stride: usize) -> (Graph<u32, ()>, Vec<NodeID>) {
+    let mut graph = Graph::with_capacity(nodes, nodes * 2);
+    let ids: Vec<NodeID> = (0..nodes as u32).map(|i| graph.add_node(i)).collect();
+    for i in 0..nodes {
+        graph.add_edge(ids[i], ids[(i + 1) % nodes], ());
+        graph.add_edge(ids[i], ids[(i + stride) % nodes], ());
+    }
+    (graph, ids)
+}
+
+fn bench_construction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("construction");
+    for nodes in [10_000, 100_000] {
+        group.bench_with_input(BenchmarkId::from_parameter(nodes), &nodes, |b, &nodes| {
+            b.iter(|| sparse_graph(black_box(nodes), 7))
+        });
+    }
+    group.finish();
+}
+
+fn bench_traversal(c: &mut Criterion) {
+    let mut group = c.benchmark_group("traversal");
+    for nodes in [10_000, 100_000] {
+        let (graph, ids) = sparse_graph(nodes, 7);
+        group.bench_with_input(BenchmarkId::new("degree_sum", nodes), &graph, |b, graph| {
+            b.iter(|| {
+                graph
+                    .nodes
+                    .values()
+                    .map(|node| node.connections.len())
+                    .sum::<usize>()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("dfs", nodes), &graph, |b, graph| {
+            b.iter(|| graph.iter_depth_first(black_box(ids[0])).count())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_construction, bench_traversal);
+criterion_main!(benches);
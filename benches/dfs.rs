@@ -0,0 +1,91 @@
+//! Full traversals of a graph whose storage order is unrelated to its structure, before and after [Graph::optimize_layout],
+//! and of a hub node with a very high in-degree.
+//!
+//! ```sh
+//! cargo bench --bench dfs
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use fast_graph::algorithms::{BreadthFirst, IterDepthFirst};
+use fast_graph::locality::LayoutOrder;
+use fast_graph::{Graph, GraphInterface, NodeID};
+use rand::{rngs::SmallRng, seq::SliceRandom, SeedableRng};
+
+/// A 300x300 grid whose nodes and edges were added in random order, like a graph built up over a long time.
+fn scattered_grid(side: usize) -> (Graph<u32, ()>, NodeID) {
+    let mut rng = SmallRng::seed_from_u64(1);
+    let mut graph = Graph::with_capacity(side * side, 2 * side * side);
+    let ids = graph.add_nodes(&(0..(side * side) as u32).collect::<Vec<u32>>());
+    let mut cells = ids.clone();
+    cells.shuffle(&mut rng);
+
+    let mut edges = Vec::with_capacity(2 * side * side);
+    for y in 0..side {
+        for x in 0..side {
+            let i = y * side + x;
+            if x + 1 < side {
+                edges.push((cells[i], cells[i + 1]));
+            }
+            if y + 1 < side {
+                edges.push((cells[i], cells[i + side]));
+            }
+        }
+    }
+    edges.shuffle(&mut rng);
+    graph.add_edges(&edges);
+    (graph, cells[0])
+}
+
+fn bench_layout(c: &mut Criterion) {
+    let (scattered, start) = scattered_grid(300);
+    let mut optimized = scattered.clone();
+    let mapping = optimized.optimize_layout(LayoutOrder::BreadthFirst);
+    let optimized_start = mapping.nodes[&start];
+
+    let mut group = c.benchmark_group("layout");
+    group.sample_size(20);
+    for (name, graph, start) in [
+        ("scattered", &scattered, start),
+        ("optimized", &optimized, optimized_start),
+    ] {
+        group.bench_function(format!("dfs_{}", name), |b| {
+            b.iter(|| graph.iter_depth_first(black_box(start)).count())
+        });
+        group.bench_function(format!("bfs_{}", name), |b| {
+            b.iter(|| BreadthFirst::new(graph, black_box(start)).count())
+        });
+    }
+    group.bench_function("optimize_layout", |b| {
+        b.iter_batched(
+            || scattered.clone(),
+            |mut graph| graph.optimize_layout(LayoutOrder::BreadthFirst),
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+/// A hub that every other node points to, with a single edge back out, so the hub's connections are almost all incoming.
+fn hub(spokes: usize) -> (Graph<u32, ()>, NodeID) {
+    let mut graph = Graph::with_capacity(spokes + 1, spokes + 1);
+    let ids = graph.add_nodes(&(0..=spokes as u32).collect::<Vec<u32>>());
+    let edges: Vec<_> = ids[1..].iter().map(|spoke| (*spoke, ids[0])).collect();
+    graph.add_edges(&edges);
+    graph.add_edge(ids[0], ids[1], ());
+    (graph, ids[1])
+}
+
+fn bench_hub(c: &mut Criterion) {
+    let (graph, start) = hub(50_000);
+    let mut group = c.benchmark_group("hub");
+    group.bench_function("dfs", |b| {
+        b.iter(|| graph.iter_depth_first(black_box(start)).count())
+    });
+    group.bench_function("bfs", |b| {
+        b.iter(|| BreadthFirst::new(&graph, black_box(start)).count())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_layout, bench_hub);
+criterion_main!(benches);
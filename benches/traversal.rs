@@ -0,0 +1,61 @@
+//! Many short traversals over one graph, with a fresh visited-set per search versus one reused [VisitMap].
+//!
+//! ```sh
+//! cargo bench --bench traversal
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use fast_graph::algorithms::{DepthFirstSearch, IterDepthFirst, VisitMap, Visitable};
+use fast_graph::{Graph, GraphInterface, NodeID};
+
+/// Many small disconnected stars, so every search only reaches a handful of nodes of a large graph.
+fn stars(count: usize, size: usize) -> (Graph<(), ()>, Vec<NodeID>) {
+    let mut graph = Graph::with_capacity(count * size, count * (size - 1));
+    let mut centers = Vec::with_capacity(count);
+    for _ in 0..count {
+        let center = graph.add_node(());
+        for _ in 1..size {
+            let leaf = graph.add_node(());
+            graph.add_edge(center, leaf, ());
+        }
+        centers.push(center);
+    }
+    (graph, centers)
+}
+
+fn bench_repeated_dfs(c: &mut Criterion) {
+    let (graph, centers) = stars(5_000, 8);
+    let mut group = c.benchmark_group("repeated_dfs");
+
+    group.bench_function("fresh_map", |b| {
+        b.iter(|| {
+            centers
+                .iter()
+                .map(|center| graph.iter_depth_first(black_box(*center)).count())
+                .sum::<usize>()
+        })
+    });
+
+    group.bench_function("reused_map", |b| {
+        let mut map: VisitMap = graph.visit_map();
+        b.iter(|| {
+            let mut total = 0;
+            for center in &centers {
+                let mut search = DepthFirstSearch::new(&graph, black_box(*center))
+                    .with_visit_map(std::mem::take(&mut map));
+                total += search.by_ref().count();
+                map = search.into_visit_map();
+            }
+            total
+        })
+    });
+
+    group.bench_function("connected_components", |b| {
+        b.iter(|| graph.connected_components().len())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_repeated_dfs);
+criterion_main!(benches);
@@ -0,0 +1,216 @@
+//! # Best first traversal with a priority queue.
+//!
+//! [BestFirst] always continues with the discovered node that has the smallest key, which is the shared machinery of greedy best-first search,
+//! Dijkstra, A* and Prim. The key is computed by a closure from the node and (optionally) a cost that is accumulated along the edges of the search:
+//!
+//! - Greedy best-first search: [BestFirst::new] with a key that only looks at the node.
+//! - Dijkstra: [BestFirst::with_cost] where the step adds the edge weight to the cost, and the key is the cost.
+//! - A*: like Dijkstra, but the key is the cost plus a heuristic of the node.
+//! - Prim: the step returns only the edge weight, and the key is that weight.
+//!
+//! Keys have to implement [Ord]; wrap floating point keys in a type that does. Ties are broken by discovery order.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::algorithms::BestFirst;
+//!
+//! let mut graph: Graph<(), u32> = Graph::new();
+//! let nodes = graph.add_nodes(&[(); 3]);
+//! graph.add_edge(nodes[0], nodes[1], 5);
+//! graph.add_edge(nodes[0], nodes[2], 1);
+//! graph.add_edge(nodes[2], nodes[1], 1);
+//!
+//! // Dijkstra: every node is returned together with the length of its shortest path.
+//! let dijkstra: Vec<(NodeID, u32)> = BestFirst::with_cost(&graph, nodes[0], 0, |cost, edge| cost + edge.data, |_, cost| *cost).collect();
+//! assert_eq!(dijkstra, vec![(nodes[0], 0), (nodes[2], 1), (nodes[1], 2)]);
+//! ```
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use super::{VisitMap, Visitable};
+use crate::{outgoing_edges, Edge, GraphRef, NodeID};
+
+/// The step of a [BestFirst] search without costs.
+pub type NoCost<E> = fn(&(), &Edge<E>);
+
+struct Entry<P, C> {
+    key: P,
+    seq: usize,
+    node: NodeID,
+    cost: C,
+}
+
+impl<P: Ord, C> PartialEq for Entry<P, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<P: Ord, C> Eq for Entry<P, C> {}
+
+impl<P: Ord, C> PartialOrd for Entry<P, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<P: Ord, C> Ord for Entry<P, C> {
+    // Reversed, so the max-heap pops the smallest key (and the earliest discovered node among equal keys) first.
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .key
+            .cmp(&self.key)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+/// Iterator over the nodes reachable from a start node (following outgoing edges), in order of their key, see the [module documentation](self).
+///
+/// Yields every node once together with the cost it was reached with, i.e. the cost of the entry with the smallest key.
+pub struct BestFirst<'a, G: GraphRef, C, S, K, P> {
+    graph: &'a G,
+    heap: BinaryHeap<Entry<P, C>>,
+    visited: VisitMap,
+    seq: usize,
+    step: S,
+    key: K,
+}
+
+impl<'a, G: GraphRef, P: Ord, K: FnMut(NodeID, &()) -> P>
+    BestFirst<'a, G, (), NoCost<G::EdgeData>, K, P>
+{
+    /// Creates a search that continues with the node with the smallest `key(node, &())`, without accumulating costs.
+    pub fn new(graph: &'a G, start: NodeID, key: K) -> Self {
+        Self::with_cost(graph, start, (), |_, _| (), key)
+    }
+}
+
+impl<'a, G, C, S, K, P> BestFirst<'a, G, C, S, K, P>
+where
+    G: GraphRef,
+    S: FnMut(&C, &Edge<G::EdgeData>) -> C,
+    K: FnMut(NodeID, &C) -> P,
+    P: Ord,
+{
+    /// Creates a search that starts with cost `initial`. Following an edge computes the cost of its target with `step(cost, edge)`,
+    /// and the search continues with the node with the smallest `key(node, cost)`.
+    pub fn with_cost(graph: &'a G, start: NodeID, initial: C, step: S, mut key: K) -> Self {
+        let mut heap = BinaryHeap::new();
+        if graph.node(start).is_ok() {
+            heap.push(Entry {
+                key: key(start, &initial),
+                seq: 0,
+                node: start,
+                cost: initial,
+            });
+        }
+        Self {
+            graph,
+            heap,
+            visited: graph.visit_map(),
+            seq: 1,
+            step,
+            key,
+        }
+    }
+}
+
+impl<'a, G, C, S, K, P> Iterator for BestFirst<'a, G, C, S, K, P>
+where
+    G: GraphRef,
+    S: FnMut(&C, &Edge<G::EdgeData>) -> C,
+    K: FnMut(NodeID, &C) -> P,
+    P: Ord,
+{
+    type Item = (NodeID, C);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(Entry { node, cost, .. }) = self.heap.pop() {
+            // Nodes can be queued several times, only the first (best) entry counts.
+            if !self.visited.visit(node) {
+                continue;
+            }
+            for edge in outgoing_edges(self.graph, node) {
+                if self.visited.is_visited(edge.to) {
+                    continue;
+                }
+                let next_cost = (self.step)(&cost, edge);
+                self.heap.push(Entry {
+                    key: (self.key)(edge.to, &next_cost),
+                    seq: self.seq,
+                    node: edge.to,
+                    cost: next_cost,
+                });
+                self.seq += 1;
+            }
+            return Some((node, cost));
+        }
+        None
+    }
+}
+
+impl<'a, G, C, S, K, P> std::iter::FusedIterator for BestFirst<'a, G, C, S, K, P>
+where
+    G: GraphRef,
+    S: FnMut(&C, &Edge<G::EdgeData>) -> C,
+    K: FnMut(NodeID, &C) -> P,
+    P: Ord,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Graph, GraphInterface};
+
+    #[test]
+    fn test_best_first() {
+        let mut graph: Graph<u32, u32> = Graph::new();
+        let nodes = graph.add_nodes(&[0, 30, 10, 20]);
+        graph.add_edges_with_data(&[
+            (nodes[0], nodes[1], 1),
+            (nodes[0], nodes[2], 4),
+            (nodes[1], nodes[3], 1),
+            (nodes[2], nodes[3], 1),
+        ]);
+
+        // Greedy: always the discovered node with the smallest data.
+        let greedy: Vec<NodeID> =
+            BestFirst::new(&graph, nodes[0], |id, _| graph.node(id).unwrap().data)
+                .map(|(id, _)| id)
+                .collect();
+        assert_eq!(greedy, vec![nodes[0], nodes[2], nodes[3], nodes[1]]);
+
+        // Dijkstra
+        let dijkstra: Vec<(NodeID, u32)> = BestFirst::with_cost(
+            &graph,
+            nodes[0],
+            0,
+            |cost, edge| cost + edge.data,
+            |_, cost| *cost,
+        )
+        .collect();
+        assert_eq!(
+            dijkstra,
+            vec![(nodes[0], 0), (nodes[1], 1), (nodes[3], 2), (nodes[2], 4)]
+        );
+
+        // A* towards nodes[3] with a heuristic that prefers nodes[2].
+        let mut a_star = BestFirst::with_cost(
+            &graph,
+            nodes[0],
+            0,
+            |cost, edge| cost + edge.data,
+            |id, cost| cost + if id == nodes[1] { 10 } else { 0 },
+        );
+        assert_eq!(a_star.nth(1), Some((nodes[2], 4)));
+        assert_eq!(a_star.next(), Some((nodes[3], 5)));
+
+        assert_eq!(
+            BestFirst::new(&graph, NodeID::default(), |_, _| 0).count(),
+            0
+        );
+    }
+}
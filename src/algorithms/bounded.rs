@@ -0,0 +1,325 @@
+//! # Traversals with a bounded frontier.
+//!
+//! A [BoundedTraversal] is a breadth or depth first search whose frontier (the queue or stack of nodes still to visit) holds at
+//! most about `max_frontier` nodes in memory. Beyond that, chunks of the frontier are spilled to a [FrontierStore], e.g. one
+//! backed by files, and loaded back when the search gets to them, without changing the visiting order.
+//!
+//! The visited nodes are tracked in a [NodeSet], one bit per slot of the graph. A traversal can also be stopped at any point
+//! with [BoundedTraversal::suspend], which returns its whole state (including the store) as a [SuspendedTraversal] that
+//! [BoundedTraversal::resume] continues from, e.g. to visit an enormous graph in batches.
+//!
+//! Edges are followed from `edge.from` to `edge.to`.
+//!
+//! # Example
+//! ```
+//! use std::collections::VecDeque;
+//! use fast_graph::*;
+//! use fast_graph::algorithms::{BoundedTraversal, TraversalOrder};
+//!
+//! let mut graph: Graph<(), ()> = Graph::new();
+//! let nodes = graph.add_nodes(&[(); 101]);
+//! for node in &nodes[1..] {
+//!     graph.add_edge(nodes[0], *node, ());
+//! }
+//!
+//! // At most 10 queued nodes in memory, the rest is spilled to the store.
+//! let store: VecDeque<Vec<NodeID>> = VecDeque::new();
+//! let mut search = BoundedTraversal::new(&graph, nodes[0], TraversalOrder::BreadthFirst, 10, store);
+//! let first: Vec<NodeID> = search.by_ref().take(50).collect();
+//! assert!(search.spilled_chunks() > 0);
+//!
+//! // Continue later.
+//! let suspended = search.suspend();
+//! let rest: Vec<NodeID> = BoundedTraversal::resume(&graph, suspended).collect();
+//! assert_eq!([first, rest].concat(), nodes);
+//! ```
+
+use std::collections::VecDeque;
+
+use super::NodeSet;
+use crate::{outgoing_edges, GraphRef, NodeID};
+
+/// The order in which a [BoundedTraversal] visits nodes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraversalOrder {
+    /// Closest nodes first, like [BreadthFirst](super::BreadthFirst).
+    BreadthFirst,
+    /// Deepest nodes first, like [DepthFirstSearch](super::DepthFirstSearch).
+    DepthFirst,
+}
+
+/// Where a [BoundedTraversal] spills chunks of its frontier.
+///
+/// The traversal keeps track of how many chunks it has spilled, and only takes chunks it spilled itself.
+pub trait FrontierStore {
+    /// Stores a chunk of the frontier.
+    fn spill(&mut self, chunk: Vec<NodeID>);
+
+    /// Takes the chunk that was spilled first (used by breadth first traversals).
+    fn take_oldest(&mut self) -> Option<Vec<NodeID>>;
+
+    /// Takes the chunk that was spilled last (used by depth first traversals).
+    fn take_newest(&mut self) -> Option<Vec<NodeID>>;
+}
+
+/// An in-memory store, mostly useful for testing a traversal before moving its frontier out of memory.
+impl FrontierStore for VecDeque<Vec<NodeID>> {
+    fn spill(&mut self, chunk: Vec<NodeID>) {
+        self.push_back(chunk);
+    }
+
+    fn take_oldest(&mut self) -> Option<Vec<NodeID>> {
+        self.pop_front()
+    }
+
+    fn take_newest(&mut self) -> Option<Vec<NodeID>> {
+        self.pop_back()
+    }
+}
+
+/// The state of a suspended [BoundedTraversal], see [BoundedTraversal::suspend].
+#[derive(Clone, Debug)]
+pub struct SuspendedTraversal<S> {
+    order: TraversalOrder,
+    max_frontier: usize,
+    /// The front of the queue (breadth first) or the stack (depth first).
+    head: VecDeque<NodeID>,
+    /// The nodes queued after the spilled chunks (breadth first only).
+    tail: Vec<NodeID>,
+    spilled: usize,
+    visited: NodeSet,
+    store: S,
+}
+
+impl<S> SuspendedTraversal<S> {
+    /// Returns true if the traversal had no nodes left to visit.
+    pub fn is_finished(&self) -> bool {
+        self.head.is_empty() && self.tail.is_empty() && self.spilled == 0
+    }
+
+    /// Returns the nodes visited so far.
+    pub fn visited(&self) -> &NodeSet {
+        &self.visited
+    }
+
+    /// Returns the store, e.g. to flush it before the suspended traversal is kept for later.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+}
+
+/// A breadth or depth first search with a bounded in-memory frontier, see the [module documentation](self).
+pub struct BoundedTraversal<'a, G: GraphRef, S = VecDeque<Vec<NodeID>>> {
+    graph: &'a G,
+    state: SuspendedTraversal<S>,
+    successors: Vec<NodeID>,
+}
+
+impl<'a, G: GraphRef, S: FrontierStore> BoundedTraversal<'a, G, S> {
+    /// Creates a traversal from `start` that keeps about `max_frontier` (at least 2) frontier nodes in memory and spills
+    /// chunks of half that size to `store`. Nothing is visited if `start` doesn't exist.
+    pub fn new(
+        graph: &'a G,
+        start: NodeID,
+        order: TraversalOrder,
+        max_frontier: usize,
+        store: S,
+    ) -> Self {
+        let mut visited = NodeSet::with_capacity(graph.node_count());
+        let mut head = VecDeque::new();
+        if graph.node(start).is_ok() {
+            if order == TraversalOrder::BreadthFirst {
+                visited.insert(start);
+            }
+            head.push_back(start);
+        }
+        BoundedTraversal {
+            graph,
+            state: SuspendedTraversal {
+                order,
+                max_frontier: max_frontier.max(2),
+                head,
+                tail: Vec::new(),
+                spilled: 0,
+                visited,
+                store,
+            },
+            successors: Vec::new(),
+        }
+    }
+
+    /// Continues a suspended traversal. The graph should be the one it was started on; nodes removed in between are skipped.
+    pub fn resume(graph: &'a G, state: SuspendedTraversal<S>) -> Self {
+        BoundedTraversal {
+            graph,
+            state,
+            successors: Vec::new(),
+        }
+    }
+
+    /// Stops the traversal, returning everything needed to [resume](BoundedTraversal::resume) it.
+    pub fn suspend(self) -> SuspendedTraversal<S> {
+        self.state
+    }
+
+    /// The number of chunks currently spilled to the store.
+    pub fn spilled_chunks(&self) -> usize {
+        self.state.spilled
+    }
+
+    fn chunk_size(&self) -> usize {
+        self.state.max_frontier / 2
+    }
+
+    fn push(&mut self, id: NodeID) {
+        let chunk = self.chunk_size();
+        let state = &mut self.state;
+        match state.order {
+            TraversalOrder::BreadthFirst => {
+                // The queue is `head`, then the spilled chunks (oldest first), then `tail`.
+                state.tail.push(id);
+                if state.tail.len() >= chunk {
+                    if state.spilled == 0 && state.head.len() + chunk <= state.max_frontier {
+                        state.head.extend(state.tail.drain(..));
+                    } else {
+                        state.store.spill(std::mem::take(&mut state.tail));
+                        state.spilled += 1;
+                    }
+                }
+            }
+            TraversalOrder::DepthFirst => {
+                // The stack is the spilled chunks (oldest at the bottom), then `head`.
+                state.head.push_back(id);
+                if state.head.len() > state.max_frontier {
+                    state.store.spill(state.head.drain(..chunk).collect());
+                    state.spilled += 1;
+                }
+            }
+        }
+    }
+
+    fn pop(&mut self) -> Option<NodeID> {
+        let state = &mut self.state;
+        match state.order {
+            TraversalOrder::BreadthFirst => {
+                if state.head.is_empty() {
+                    match state.spilled {
+                        0 => state.head.extend(state.tail.drain(..)),
+                        _ => {
+                            state.spilled -= 1;
+                            state.head.extend(state.store.take_oldest()?);
+                        }
+                    }
+                }
+                state.head.pop_front()
+            }
+            TraversalOrder::DepthFirst => {
+                if state.head.is_empty() && state.spilled > 0 {
+                    state.spilled -= 1;
+                    state.head.extend(state.store.take_newest()?);
+                }
+                state.head.pop_back()
+            }
+        }
+    }
+}
+
+impl<'a, G: GraphRef, S: FrontierStore> Iterator for BoundedTraversal<'a, G, S> {
+    type Item = NodeID;
+
+    fn next(&mut self) -> Option<NodeID> {
+        loop {
+            let id = self.pop()?;
+            let depth_first = self.state.order == TraversalOrder::DepthFirst;
+            if depth_first && !self.state.visited.insert(id) {
+                continue;
+            }
+            for edge in outgoing_edges(self.graph, id) {
+                let unvisited = if depth_first {
+                    !self.state.visited.contains(edge.to)
+                } else {
+                    self.state.visited.insert(edge.to)
+                };
+                if unvisited {
+                    self.successors.push(edge.to);
+                }
+            }
+            if depth_first {
+                // The first successor ends up on top of the stack.
+                self.successors.reverse();
+            }
+            let mut successors = std::mem::take(&mut self.successors);
+            for next in successors.drain(..) {
+                self.push(next);
+            }
+            self.successors = successors;
+            return Some(id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::BreadthFirst;
+    use crate::{Graph, GraphInterface};
+
+    #[test]
+    fn test_bounded_traversal_matches_unbounded() {
+        // A complete binary tree with some cross edges.
+        let mut graph: Graph<(), ()> = Graph::new();
+        let nodes = graph.add_nodes(&[(); 255]);
+        for i in 1..nodes.len() {
+            graph.add_edge(nodes[(i - 1) / 2], nodes[i], ());
+        }
+        for i in (0..nodes.len()).step_by(7) {
+            graph.add_edge(nodes[i], nodes[(i * 3) % nodes.len()], ());
+        }
+
+        let unbounded: Vec<NodeID> = BreadthFirst::new(&graph, nodes[0]).collect();
+        let roomy: Vec<NodeID> = BoundedTraversal::new(
+            &graph,
+            nodes[0],
+            TraversalOrder::DepthFirst,
+            usize::MAX,
+            VecDeque::new(),
+        )
+        .collect();
+        assert_eq!(roomy.len(), nodes.len());
+
+        for max_frontier in [2, 5, 16] {
+            let mut search = BoundedTraversal::new(
+                &graph,
+                nodes[0],
+                TraversalOrder::BreadthFirst,
+                max_frontier,
+                VecDeque::new(),
+            );
+            let mut visited: Vec<NodeID> = search.by_ref().take(100).collect();
+            assert!(search.spilled_chunks() > 0);
+            let suspended = search.suspend();
+            assert!(!suspended.is_finished());
+            visited.extend(BoundedTraversal::resume(&graph, suspended));
+            assert_eq!(visited, unbounded);
+
+            let search = BoundedTraversal::new(
+                &graph,
+                nodes[0],
+                TraversalOrder::DepthFirst,
+                max_frontier,
+                VecDeque::new(),
+            );
+            assert_eq!(search.collect::<Vec<NodeID>>(), roomy);
+        }
+
+        let mut search = BoundedTraversal::new(
+            &graph,
+            nodes[0],
+            TraversalOrder::DepthFirst,
+            4,
+            VecDeque::new(),
+        );
+        search.by_ref().for_each(drop);
+        assert!(search.suspend().is_finished());
+    }
+}
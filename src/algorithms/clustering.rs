@@ -0,0 +1,192 @@
+//! # Single-linkage clustering on the minimum spanning tree.
+//!
+//! [single_linkage] runs Kruskal's algorithm over the edges (ignoring their direction) and records every merge of two clusters
+//! in a [Dendrogram]. Cutting the `k - 1` heaviest edges of the minimum spanning tree leaves `k` clusters, which is
+//! what [Dendrogram::clusters] does; [Dendrogram::clusters_below] cuts at a weight instead.
+//!
+//! The merges are tracked with a [DisjointSet]. Weights have to implement [Ord]; wrap floating point weights in a type that does. Ties are broken by [EdgeID].
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::algorithms::single_linkage;
+//!
+//! let mut graph: Graph<(), u32> = Graph::new();
+//! let nodes = graph.add_nodes(&[(); 4]);
+//! graph.add_edge(nodes[0], nodes[1], 1);
+//! graph.add_edge(nodes[1], nodes[2], 10);
+//! graph.add_edge(nodes[2], nodes[3], 2);
+//!
+//! let dendrogram = single_linkage(&graph, |_, edge| edge.data);
+//! let clusters = dendrogram.clusters(2);
+//! assert_eq!(clusters[&nodes[0]], clusters[&nodes[1]]);
+//! assert_ne!(clusters[&nodes[1]], clusters[&nodes[2]]);
+//! assert_eq!(dendrogram.merges.last().unwrap().height, 10);
+//! ```
+
+use super::DisjointSet;
+use crate::hash::HashMap;
+use crate::{outgoing_edges, Edge, EdgeID, GraphRef, NodeID};
+
+/// Two clusters joined by [single_linkage], see [Dendrogram].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Merge<W> {
+    /// The joined clusters: `i < leaves.len()` is the leaf `leaves[i]`, otherwise the cluster created by `merges[i - leaves.len()]`.
+    pub left: usize,
+    pub right: usize,
+    /// The weight of the spanning tree edge that joined them.
+    pub height: W,
+    pub edge: EdgeID,
+    /// The number of nodes in the joined cluster.
+    pub size: usize,
+}
+
+/// The merge history of [single_linkage], in the layout of SciPy's linkage matrix.
+///
+/// The edges of the merges form a minimum spanning forest. A graph with `c` (weakly) connected components has `leaves.len() - c` merges.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Dendrogram<W> {
+    /// The nodes of the graph, in slot order.
+    pub leaves: Vec<NodeID>,
+    /// The merges in order of increasing height.
+    pub merges: Vec<Merge<W>>,
+}
+
+impl<W> Dendrogram<W> {
+    /// Assigns every node to one of `k` clusters, by undoing the `k - 1` highest merges.
+    ///
+    /// There are more than `k` clusters if the graph has more than `k` connected components, and fewer if it has fewer than `k` nodes.
+    /// Clusters are numbered from 0 in slot order of their first node.
+    pub fn clusters(&self, k: usize) -> HashMap<NodeID, usize> {
+        let applied = self.leaves.len().saturating_sub(k.max(1));
+        self.assign(&self.merges[..applied.min(self.merges.len())])
+    }
+
+    /// Assigns every node to a cluster, keeping only the merges with a height of at most `height`.
+    pub fn clusters_below(&self, height: &W) -> HashMap<NodeID, usize>
+    where
+        W: Ord,
+    {
+        let applied = self.merges.partition_point(|merge| merge.height <= *height);
+        self.assign(&self.merges[..applied])
+    }
+
+    fn assign(&self, merges: &[Merge<W>]) -> HashMap<NodeID, usize> {
+        let mut sets: DisjointSet = self.leaves.iter().copied().collect();
+        // A leaf of every cluster created so far.
+        let mut representative = self.leaves.clone();
+        for merge in merges {
+            let (left, right) = (representative[merge.left], representative[merge.right]);
+            sets.union(left, right);
+            representative.push(left);
+        }
+
+        let mut clusters = HashMap::with_capacity_and_hasher(self.leaves.len(), Default::default());
+        // The sets are ordered by their first leaf.
+        for (label, set) in sets.sets().into_iter().enumerate() {
+            for leaf in set {
+                clusters.insert(leaf, label);
+            }
+        }
+        clusters
+    }
+}
+
+/// Builds the single-linkage [Dendrogram] of the graph with Kruskal's algorithm, see the [module documentation](self).
+///
+/// Edge directions are ignored. Self-loops, connections to removed edges and edges to nodes that don't exist are skipped.
+pub fn single_linkage<G, W, F>(graph: &G, mut weight: F) -> Dendrogram<W>
+where
+    G: GraphRef,
+    W: Ord,
+    F: FnMut(EdgeID, &Edge<G::EdgeData>) -> W,
+{
+    let leaves: Vec<NodeID> = graph.nodes().collect();
+
+    let mut edges = Vec::new();
+    for id in &leaves {
+        for edge in outgoing_edges(graph, *id) {
+            if edge.to != *id && graph.node(edge.to).is_ok() {
+                edges.push((weight(edge.id, edge), edge.id, edge.from, edge.to));
+            }
+        }
+    }
+    edges.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+
+    let mut sets: DisjointSet = leaves.iter().copied().collect();
+    // The dendrogram index of the cluster of every set, keyed by the set's representative.
+    let mut cluster: HashMap<NodeID, usize> =
+        leaves.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+    let mut merges = Vec::with_capacity(leaves.len().saturating_sub(1));
+    for (height, edge, from, to) in edges {
+        let a = sets.find(from).expect("every node is in a set");
+        let b = sets.find(to).expect("every node is in a set");
+        if a == b {
+            continue;
+        }
+        let (left, right) = (cluster[&a].min(cluster[&b]), cluster[&a].max(cluster[&b]));
+        sets.union(a, b);
+        // Either of the old representatives is the new one.
+        let id = leaves.len() + merges.len();
+        cluster.insert(a, id);
+        cluster.insert(b, id);
+        merges.push(Merge {
+            left,
+            right,
+            height,
+            edge,
+            size: sets.set_size(a).expect("every node is in a set"),
+        });
+    }
+    Dendrogram { leaves, merges }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Graph, GraphInterface};
+
+    #[test]
+    fn test_single_linkage() {
+        let mut graph: Graph<(), u32> = Graph::new();
+        let nodes = graph.add_nodes(&[(); 6]);
+        graph.add_edges_with_data(&[
+            (nodes[0], nodes[1], 1),
+            (nodes[1], nodes[2], 2),
+            (nodes[2], nodes[0], 3),
+            (nodes[3], nodes[2], 7),
+            (nodes[3], nodes[4], 1),
+            (nodes[4], nodes[4], 0),
+        ]);
+
+        let dendrogram = single_linkage(&graph, |_, edge| edge.data);
+        // `nodes[5]` is unconnected and the cycle edge of weight 3 isn't in the spanning tree.
+        let heights: Vec<u32> = dendrogram.merges.iter().map(|merge| merge.height).collect();
+        assert_eq!(heights, vec![1, 1, 2, 7]);
+        assert_eq!(
+            dendrogram.merges[2],
+            Merge {
+                left: 2,
+                right: 6,
+                height: 2,
+                edge: graph.node(nodes[1]).unwrap().connections[1],
+                size: 3,
+            }
+        );
+        assert_eq!(dendrogram.merges[3].size, 5);
+
+        let labels = |clusters: HashMap<NodeID, usize>| -> Vec<usize> {
+            nodes.iter().map(|id| clusters[id]).collect()
+        };
+        assert_eq!(labels(dendrogram.clusters(1)), vec![0, 0, 0, 0, 0, 1]);
+        assert_eq!(labels(dendrogram.clusters(3)), vec![0, 0, 0, 1, 1, 2]);
+        assert_eq!(labels(dendrogram.clusters(4)), vec![0, 0, 1, 2, 2, 3]);
+        assert_eq!(labels(dendrogram.clusters(10)), vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(
+            labels(dendrogram.clusters_below(&1)),
+            vec![0, 0, 1, 2, 2, 3]
+        );
+    }
+}
@@ -0,0 +1,404 @@
+//! # Contraction hierarchies.
+//!
+//! A [ContractionHierarchy] preprocesses a weighted graph so that shortest path queries only explore a small part of it.
+//! Nodes are contracted one by one, least important first (ordered by edge difference: the shortcuts contracting a node needs
+//! minus the edges it removes, plus its already contracted neighbors). Contracting a node adds a *shortcut* edge `u -> x` for
+//! every path `u -> node -> x` that is the only shortest path between its ends, found with a bounded local search.
+//!
+//! A query then runs two Dijkstra searches that only go *up* in the order: forward from the start and backward from the
+//! target. They meet at the most important node of a shortest path, and the shortcuts on it are unpacked into original edges.
+//! This pays off for many queries on large, sparse graphs such as road networks; for a few queries, plain Dijkstra is faster.
+//!
+//! Edges are followed from `edge.from` to `edge.to` and weights must not be negative. Parallel edges keep the lightest one, and
+//! self-loops are ignored. The hierarchy is a snapshot: rebuild it after changing the graph.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::algorithms::ContractionHierarchy;
+//!
+//! let mut graph: Graph<(), u32> = Graph::new();
+//! let nodes = graph.add_nodes(&[(); 4]);
+//! let edges = graph.add_edges_with_data(&[
+//!     (nodes[0], nodes[1], 1),
+//!     (nodes[1], nodes[2], 1),
+//!     (nodes[2], nodes[3], 1),
+//!     (nodes[0], nodes[3], 5),
+//! ]);
+//!
+//! let hierarchy = ContractionHierarchy::build(&graph, |edge: &Edge<u32>| edge.data);
+//! let path = hierarchy.shortest_path(nodes[0], nodes[3]).unwrap();
+//! assert_eq!(path.cost, 3);
+//! assert_eq!(path.edges, edges[..3]);
+//! assert_eq!(hierarchy.distance(nodes[3], nodes[0]), None);
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::ops::Add;
+
+use crate::hash::HashMap;
+use crate::{outgoing_edges, Edge, EdgeID, GraphRef, NodeID};
+
+/// How many nodes a witness search settles before giving up (and adding the shortcut).
+const WITNESS_LIMIT: usize = 128;
+
+/// What an edge of the hierarchy stands for.
+#[derive(Clone, Copy, Debug)]
+enum Via {
+    /// An edge of the graph.
+    Edge(EdgeID),
+    /// A shortcut over a contracted node.
+    Node(usize),
+}
+
+/// A shortest path found by [ContractionHierarchy::shortest_path].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChPath<W> {
+    /// The nodes of the path, from the start to the end (both included).
+    pub nodes: Vec<NodeID>,
+    /// The edges of the graph on the path, one less than the nodes.
+    pub edges: Vec<EdgeID>,
+    /// The sum of the weights of the edges.
+    pub cost: W,
+}
+
+/// A graph preprocessed for fast shortest path queries, see the [module documentation](self).
+#[derive(Clone, Debug)]
+pub struct ContractionHierarchy<W> {
+    nodes: Vec<NodeID>,
+    index: HashMap<NodeID, usize>,
+    /// The edges to more important nodes, per node.
+    up: Vec<Vec<(usize, W)>>,
+    /// The edges from more important nodes, per node (followed backwards).
+    down: Vec<Vec<(usize, W)>>,
+    edges: HashMap<(usize, usize), (W, Via)>,
+    shortcuts: usize,
+}
+
+/// The state of the contraction while building a hierarchy.
+struct Builder<W> {
+    out: Vec<HashMap<usize, (W, Via)>>,
+    into: Vec<HashMap<usize, (W, Via)>>,
+    contracted: Vec<bool>,
+    contracted_neighbors: Vec<i64>,
+}
+
+impl<W: Ord + Copy + Default + Add<Output = W>> Builder<W> {
+    /// Adds the edge, or replaces a heavier one. Returns true if the edge changed.
+    fn relax(&mut self, from: usize, to: usize, weight: W, via: Via) -> bool {
+        if matches!(self.out[from].get(&to), Some((current, _)) if *current <= weight) {
+            return false;
+        }
+        self.out[from].insert(to, (weight, via));
+        self.into[to].insert(from, (weight, via));
+        true
+    }
+
+    /// The edges of a node's map that lead to nodes that aren't contracted yet.
+    fn remaining<'a>(
+        &'a self,
+        edges: &'a HashMap<usize, (W, Via)>,
+    ) -> impl Iterator<Item = (usize, W)> + 'a {
+        edges
+            .iter()
+            .filter(|(id, _)| !self.contracted[**id])
+            .map(|(id, (weight, _))| (*id, *weight))
+    }
+
+    /// Returns the shortcuts needed to contract `node`.
+    fn shortcuts(&self, node: usize) -> Vec<(usize, usize, W)> {
+        let outgoing: Vec<(usize, W)> = self.remaining(&self.out[node]).collect();
+        let mut shortcuts = Vec::new();
+        if outgoing.is_empty() {
+            return shortcuts;
+        }
+        for (from, first) in self.remaining(&self.into[node]) {
+            let limit = outgoing
+                .iter()
+                .filter(|(to, _)| *to != from)
+                .map(|(_, second)| first + *second)
+                .max();
+            let Some(limit) = limit else {
+                continue;
+            };
+            let witnesses = self.witness_search(from, node, limit);
+            for (to, second) in &outgoing {
+                let through = first + *second;
+                if *to != from
+                    && !matches!(witnesses.get(to), Some(distance) if *distance <= through)
+                {
+                    shortcuts.push((from, *to, through));
+                }
+            }
+        }
+        shortcuts
+    }
+
+    /// A Dijkstra search from `from` that avoids `node` and contracted nodes, returning tentative distances up to `limit`.
+    fn witness_search(&self, from: usize, node: usize, limit: W) -> HashMap<usize, W> {
+        let mut distances: HashMap<usize, W> = HashMap::default();
+        distances.insert(from, W::default());
+        let mut heap = BinaryHeap::from([Reverse((W::default(), from))]);
+        let mut settled = 0;
+        while let Some(Reverse((distance, id))) = heap.pop() {
+            if distances[&id] < distance {
+                continue;
+            }
+            settled += 1;
+            if distance > limit || settled > WITNESS_LIMIT {
+                break;
+            }
+            for (to, (weight, _)) in &self.out[id] {
+                if *to == node || self.contracted[*to] {
+                    continue;
+                }
+                let through = distance + *weight;
+                if through <= limit
+                    && !matches!(distances.get(to), Some(current) if *current <= through)
+                {
+                    distances.insert(*to, through);
+                    heap.push(Reverse((through, *to)));
+                }
+            }
+        }
+        distances
+    }
+
+    fn priority(&self, node: usize) -> i64 {
+        let removed =
+            self.remaining(&self.out[node]).count() + self.remaining(&self.into[node]).count();
+        self.shortcuts(node).len() as i64 - removed as i64 + self.contracted_neighbors[node]
+    }
+}
+
+impl<W: Ord + Copy + Default + Add<Output = W>> ContractionHierarchy<W> {
+    /// Contracts every node of the graph, with `weight` returning the weight of an edge.
+    ///
+    /// Connections to removed edges and edges to nodes that don't exist are skipped.
+    pub fn build<G: GraphRef>(graph: &G, mut weight: impl FnMut(&Edge<G::EdgeData>) -> W) -> Self {
+        let nodes: Vec<NodeID> = graph.nodes().collect();
+        let index: HashMap<NodeID, usize> =
+            nodes.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        let mut builder = Builder {
+            out: vec![HashMap::default(); nodes.len()],
+            into: vec![HashMap::default(); nodes.len()],
+            contracted: vec![false; nodes.len()],
+            contracted_neighbors: vec![0; nodes.len()],
+        };
+        for (i, id) in nodes.iter().enumerate() {
+            for edge in outgoing_edges(graph, *id) {
+                match index.get(&edge.to) {
+                    Some(to) if *to != i => {
+                        builder.relax(i, *to, weight(edge), Via::Edge(edge.id));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // Contract the least important node first, updating priorities lazily when a node comes up.
+        let mut rank = vec![0; nodes.len()];
+        let mut queue: BinaryHeap<Reverse<(i64, usize)>> = (0..nodes.len())
+            .map(|i| Reverse((builder.priority(i), i)))
+            .collect();
+        let mut shortcuts = 0;
+        let mut next_rank = 0;
+        while let Some(Reverse((_, node))) = queue.pop() {
+            let priority = builder.priority(node);
+            if matches!(queue.peek(), Some(Reverse((next, _))) if priority > *next) {
+                queue.push(Reverse((priority, node)));
+                continue;
+            }
+            for (from, to, weight) in builder.shortcuts(node) {
+                if builder.relax(from, to, weight, Via::Node(node)) {
+                    shortcuts += 1;
+                }
+            }
+            builder.contracted[node] = true;
+            rank[node] = next_rank;
+            next_rank += 1;
+            let neighbors: Vec<usize> = builder
+                .remaining(&builder.out[node])
+                .chain(builder.remaining(&builder.into[node]))
+                .map(|(id, _)| id)
+                .collect();
+            for neighbor in neighbors {
+                builder.contracted_neighbors[neighbor] += 1;
+            }
+        }
+
+        let mut hierarchy = ContractionHierarchy {
+            up: vec![Vec::new(); nodes.len()],
+            down: vec![Vec::new(); nodes.len()],
+            edges: HashMap::default(),
+            nodes,
+            index,
+            shortcuts,
+        };
+        for (from, edges) in builder.out.into_iter().enumerate() {
+            for (to, (weight, via)) in edges {
+                if rank[from] < rank[to] {
+                    hierarchy.up[from].push((to, weight));
+                } else {
+                    hierarchy.down[to].push((from, weight));
+                }
+                hierarchy.edges.insert((from, to), (weight, via));
+            }
+        }
+        hierarchy
+    }
+
+    /// The number of shortcut edges added while contracting (some may have been replaced by lighter ones later).
+    pub fn shortcut_count(&self) -> usize {
+        self.shortcuts
+    }
+
+    /// Returns the cost of a shortest path from `from` to `to`, or `None` if there is none (or either node wasn't in the graph).
+    pub fn distance(&self, from: NodeID, to: NodeID) -> Option<W> {
+        self.meet(from, to).map(|(_, cost, _, _)| cost)
+    }
+
+    /// Returns a shortest path from `from` to `to` with the shortcuts unpacked into edges of the graph, or `None` if there is none.
+    pub fn shortest_path(&self, from: NodeID, to: NodeID) -> Option<ChPath<W>> {
+        let (meeting, cost, forward, backward) = self.meet(from, to)?;
+
+        // The hierarchy edges from the start up to the meeting node, and from there down to the target.
+        let mut hops = Vec::new();
+        let mut current = meeting;
+        while let Some((_, Some(previous))) = forward.get(&current) {
+            hops.push((*previous, current));
+            current = *previous;
+        }
+        hops.reverse();
+        let mut current = meeting;
+        while let Some((_, Some(next))) = backward.get(&current) {
+            hops.push((current, *next));
+            current = *next;
+        }
+
+        let mut path = ChPath {
+            nodes: vec![self.nodes[self.index[&from]]],
+            edges: Vec::new(),
+            cost,
+        };
+        for hop in hops {
+            let mut stack = vec![hop];
+            while let Some((a, b)) = stack.pop() {
+                match self.edges[&(a, b)].1 {
+                    Via::Edge(edge) => {
+                        path.edges.push(edge);
+                        path.nodes.push(self.nodes[b]);
+                    }
+                    Via::Node(middle) => {
+                        stack.push((middle, b));
+                        stack.push((a, middle));
+                    }
+                }
+            }
+        }
+        Some(path)
+    }
+
+    /// Runs both upward searches and returns the best meeting node with the cost through it and both search trees.
+    #[allow(clippy::type_complexity)]
+    fn meet(
+        &self,
+        from: NodeID,
+        to: NodeID,
+    ) -> Option<(
+        usize,
+        W,
+        HashMap<usize, (W, Option<usize>)>,
+        HashMap<usize, (W, Option<usize>)>,
+    )> {
+        let (from, to) = (*self.index.get(&from)?, *self.index.get(&to)?);
+        let forward = upward_search(&self.up, from);
+        let backward = upward_search(&self.down, to);
+        let (meeting, cost) = forward
+            .iter()
+            .filter_map(|(id, (distance, _))| Some((*id, *distance + backward.get(id)?.0)))
+            .min_by_key(|(id, cost)| (*cost, *id))?;
+        Some((meeting, cost, forward, backward))
+    }
+}
+
+/// A Dijkstra search over `edges` from `start`, returning the distance and previous node of every reached node.
+fn upward_search<W: Ord + Copy + Default + Add<Output = W>>(
+    edges: &[Vec<(usize, W)>],
+    start: usize,
+) -> HashMap<usize, (W, Option<usize>)> {
+    let mut reached: HashMap<usize, (W, Option<usize>)> = HashMap::default();
+    reached.insert(start, (W::default(), None));
+    let mut heap = BinaryHeap::from([Reverse((W::default(), start))]);
+    while let Some(Reverse((distance, id))) = heap.pop() {
+        if reached[&id].0 < distance {
+            continue;
+        }
+        for (next, weight) in &edges[id] {
+            let through = distance + *weight;
+            if !matches!(reached.get(next), Some((current, _)) if *current <= through) {
+                reached.insert(*next, (through, Some(id)));
+                heap.push(Reverse((through, *next)));
+            }
+        }
+    }
+    reached
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path_cache::ShortestPathCache;
+    use crate::{Graph, GraphInterface};
+
+    #[test]
+    fn test_contraction_hierarchy_matches_dijkstra() {
+        // A 6x6 grid with one-way streets and varying weights.
+        let mut graph: Graph<(), u32> = Graph::new();
+        let nodes = graph.add_nodes(&[(); 36]);
+        for y in 0..6 {
+            for x in 0..6 {
+                let i = y * 6 + x;
+                let weight = (i * 7 % 5 + 1) as u32;
+                if x < 5 {
+                    graph.add_edge(nodes[i], nodes[i + 1], weight);
+                    if y % 2 == 0 {
+                        graph.add_edge(nodes[i + 1], nodes[i], weight + 1);
+                    }
+                }
+                if y < 5 {
+                    graph.add_edge(nodes[i], nodes[i + 6], 6 - weight);
+                    graph.add_edge(nodes[i + 6], nodes[i], weight);
+                }
+            }
+        }
+        graph.add_edge(nodes[3], nodes[3], 0);
+        graph.add_edge(nodes[0], nodes[35], 100);
+
+        let hierarchy = ContractionHierarchy::build(&graph, |edge: &Edge<u32>| edge.data);
+        let mut dijkstra = ShortestPathCache::new(graph, |edge: &Edge<u32>| edge.data);
+        for from in &nodes {
+            for to in &nodes {
+                let expected = dijkstra.shortest_path(*from, *to).map(|path| path.cost);
+                assert_eq!(hierarchy.distance(*from, *to), expected);
+                let Some(path) = hierarchy.shortest_path(*from, *to) else {
+                    continue;
+                };
+                // The unpacked path is made of original edges and adds up to the cost.
+                assert_eq!(path.nodes.first(), Some(from));
+                assert_eq!(path.nodes.last(), Some(to));
+                let mut cost = 0;
+                for (i, edge) in path.edges.iter().enumerate() {
+                    let edge = dijkstra.graph().edge(*edge).unwrap();
+                    assert_eq!((edge.from, edge.to), (path.nodes[i], path.nodes[i + 1]));
+                    cost += edge.data;
+                }
+                assert_eq!(Some(cost), expected);
+            }
+        }
+
+        let added = dijkstra.add_node(());
+        assert_eq!(hierarchy.distance(nodes[0], added), None);
+    }
+}
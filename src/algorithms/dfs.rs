@@ -1,50 +1,75 @@
 //! # Under development
-#[cfg(feature = "hashbrown")]
-use hashbrown::HashSet;
-#[cfg(not(feature = "hashbrown"))]
-use std::collections::HashSet;
+use crate::hash::{HashMap, HashSet};
 
-use crate::{Edge, Graph, GraphInterface, NodeID};
+use super::{NodeSet, VisitMap, Visitable};
+use crate::{outgoing_edges, EdgeID, GraphRef, NodeID};
+
+/// The node filter of an unfiltered [DepthFirstSearch], which accepts every node.
+pub type AllNodes = fn(NodeID) -> bool;
 
 /// Under development
+///
+/// Only nodes accepted by the node filter `F` are visited, the search doesn't continue through nodes that are filtered out.
 #[derive(Clone)]
-pub struct DepthFirstSearch<'a, G: GraphInterface> {
+pub struct DepthFirstSearch<'a, G: GraphRef, F = AllNodes> {
     graph: &'a G,
     start: NodeID,
-    visited: HashSet<NodeID>,
+    visited: VisitMap,
     stack: Vec<NodeID>,
     cyclic: bool,
     visited_edges: Vec<(NodeID, NodeID)>,
+    filter: F,
 }
 
-impl<'a, G: GraphInterface> DepthFirstSearch<'a, G> {
+impl<'a, G: GraphRef> DepthFirstSearch<'a, G> {
     pub fn new(graph: &'a G, start: NodeID) -> Self {
+        Self::with_filter(graph, start, |_| true)
+    }
+}
+
+impl<'a, G: GraphRef, F: FnMut(NodeID) -> bool> DepthFirstSearch<'a, G, F> {
+    /// Creates a search that only visits nodes for which `filter` returns true. If `start` is filtered out nothing is visited.
+    pub fn with_filter(graph: &'a G, start: NodeID, mut filter: F) -> Self {
+        let stack = if filter(start) { vec![start] } else { vec![] };
         Self {
             graph,
             start,
-            visited: HashSet::new(),
-            stack: vec![start],
+            visited: VisitMap::new(),
+            stack,
             cyclic: false,
             visited_edges: Vec::new(),
+            filter,
         }
     }
+
+    /// Uses `map` (after resetting it) to track visited nodes, so its allocation can be reused across searches.
+    pub fn with_visit_map(mut self, mut map: VisitMap) -> Self {
+        map.reset();
+        self.visited = map;
+        self
+    }
+
+    /// Returns the map of visited nodes, e.g. to reuse it for the next search with [DepthFirstSearch::with_visit_map].
+    pub fn into_visit_map(self) -> VisitMap {
+        self.visited
+    }
 }
 
-impl<'a, G: GraphInterface> Iterator for DepthFirstSearch<'a, G> {
+impl<'a, G: GraphRef, F: FnMut(NodeID) -> bool> Iterator for DepthFirstSearch<'a, G, F> {
     type Item = NodeID;
 
     fn next(&mut self) -> Option<Self::Item> {
         if let Some(node) = self.stack.pop() {
-            if self.visited.contains(&node) {
+            if !self.visited.visit(node) {
                 self.cyclic = true;
                 return self.next();
             }
-            self.visited.insert(node);
 
-            let node = self.graph.node(node).unwrap();
-            for edge in &node.connections {
-                let edge = self.graph.edge(*edge).unwrap();
-                if (edge.to != self.start) && !self.visited.contains(&edge.to) {
+            for edge in outgoing_edges(self.graph, node) {
+                if (edge.to != self.start)
+                    && !self.visited.is_visited(edge.to)
+                    && (self.filter)(edge.to)
+                {
                     self.stack.push(edge.to);
                     self.visited_edges.push((edge.from, edge.to));
                 }
@@ -53,51 +78,353 @@ impl<'a, G: GraphInterface> Iterator for DepthFirstSearch<'a, G> {
                 // }
             }
 
-            return Some(node.id);
+            return Some(node);
+        }
+        None
+    }
+}
+
+impl<'a, G: GraphRef, F: FnMut(NodeID) -> bool> std::iter::FusedIterator
+    for DepthFirstSearch<'a, G, F>
+{
+}
+
+/// A depth first search over the whole graph, returned by [IterDepthFirst::iter_depth_first_all].
+///
+/// Starts at the first node in slot order and, whenever the search runs out of nodes, restarts from the next node that hasn't
+/// been visited yet. Every node is returned once, as `(search index, node)`, where the search index counts the restarts (from 0).
+/// Edges are followed from `edge.from` to `edge.to`, so a later search can't reach into an earlier one: with edges in both
+/// directions the searches are the connected components, otherwise nodes that are only reached from a later start end up in an
+/// earlier search.
+#[derive(Clone)]
+pub struct DepthFirstAll<'a, G: GraphRef> {
+    graph: &'a G,
+    /// The nodes that haven't been tried as a start yet, in reverse slot order.
+    roots: Vec<NodeID>,
+    visited: VisitMap,
+    stack: Vec<NodeID>,
+    searches: usize,
+}
+
+impl<'a, G: GraphRef> DepthFirstAll<'a, G> {
+    pub fn new(graph: &'a G) -> Self {
+        let mut roots: Vec<NodeID> = graph.nodes().collect();
+        roots.reverse();
+        Self {
+            graph,
+            roots,
+            visited: graph.visit_map(),
+            stack: Vec::new(),
+            searches: 0,
+        }
+    }
+}
+
+impl<'a, G: GraphRef> Iterator for DepthFirstAll<'a, G> {
+    type Item = (usize, NodeID);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            while let Some(id) = self.stack.pop() {
+                if !self.visited.visit(id) {
+                    continue;
+                }
+                if !self.graph.contains_node(id) {
+                    continue;
+                }
+                for edge in outgoing_edges(self.graph, id) {
+                    if !self.visited.is_visited(edge.to) && self.graph.contains_node(edge.to) {
+                        self.stack.push(edge.to);
+                    }
+                }
+                return Some((self.searches - 1, id));
+            }
+
+            let root = self.roots.pop()?;
+            if !self.visited.is_visited(root) {
+                self.stack.push(root);
+                self.searches += 1;
+            }
+        }
+    }
+}
+
+impl<'a, G: GraphRef> std::iter::FusedIterator for DepthFirstAll<'a, G> {}
+
+/// A depth first search that returns every node after all nodes reachable from it (post-order), returned by
+/// [IterDepthFirst::iter_depth_first_post_order].
+///
+/// Unlike [DepthFirstSearch], which returns a node when it's first reached (pre-order), a node is returned once all of its
+/// successors have been returned or were already on the path to it. In a graph without cycles every node therefore comes
+/// after all of its successors, so [IterDepthFirst::reverse_post_order] is a topological order of the reachable nodes.
+/// Edges are followed from `edge.from` to `edge.to`.
+#[derive(Clone)]
+pub struct DepthFirstPostOrder<'a, G: GraphRef> {
+    graph: &'a G,
+    visited: VisitMap,
+    /// The path from the start, with the position in the connections of the next edge to follow for every node.
+    stack: Vec<(NodeID, usize)>,
+}
+
+impl<'a, G: GraphRef> DepthFirstPostOrder<'a, G> {
+    /// Creates a search from `start`. Nothing is visited if `start` doesn't exist.
+    pub fn new(graph: &'a G, start: NodeID) -> Self {
+        let mut visited = graph.visit_map();
+        let mut stack = Vec::new();
+        if graph.contains_node(start) {
+            visited.visit(start);
+            stack.push((start, 0));
+        }
+        Self {
+            graph,
+            visited,
+            stack,
+        }
+    }
+}
+
+impl<'a, G: GraphRef> Iterator for DepthFirstPostOrder<'a, G> {
+    type Item = NodeID;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((id, next)) = self.stack.last_mut() {
+            let id = *id;
+            let Ok(node) = self.graph.node(id) else {
+                self.stack.pop();
+                continue;
+            };
+            let mut successor = None;
+            while let Some(edge) = node.connections.get(*next) {
+                *next += 1;
+                match self.graph.edge(*edge) {
+                    Ok(edge)
+                        if edge.from == id
+                            && !self.visited.is_visited(edge.to)
+                            && self.graph.contains_node(edge.to) =>
+                    {
+                        successor = Some(edge.to);
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            match successor {
+                Some(successor) => {
+                    self.visited.visit(successor);
+                    self.stack.push((successor, 0));
+                }
+                None => {
+                    self.stack.pop();
+                    return Some(id);
+                }
+            }
         }
         None
     }
 }
 
-impl<'a, G: GraphInterface> std::iter::FusedIterator for DepthFirstSearch<'a, G> {}
+impl<'a, G: GraphRef> std::iter::FusedIterator for DepthFirstPostOrder<'a, G> {}
+
+/// The kind of an edge in a depth first search, returned by [DepthFirstEdges].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    /// The edge that first reached its target, part of the search tree.
+    Tree,
+    /// An edge to a node on the current path (including self-loops). The graph has a cycle if and only if there is one.
+    Back,
+    /// An edge to a finished descendant that was already reached over another path.
+    Forward,
+    /// An edge to a finished node that isn't a descendant, in an earlier branch or an earlier search.
+    Cross,
+}
+
+/// A depth first search that returns every edge it follows together with its [EdgeKind], returned by
+/// [IterDepthFirst::iter_depth_first_edges].
+///
+/// Edges are followed from `edge.from` to `edge.to` in connection order, and every outgoing edge of a reached node is returned
+/// once, when it's followed. Connections to removed edges and edges to nodes that don't exist are skipped.
+///
+/// [DepthFirstEdges::all] restarts from every node that hasn't been reached yet, in slot order, so every edge of the graph is classified.
+#[derive(Clone)]
+pub struct DepthFirstEdges<'a, G: GraphRef> {
+    graph: &'a G,
+    /// The nodes that haven't been tried as a start yet, in reverse order.
+    roots: Vec<NodeID>,
+    /// The order in which every reached node was reached.
+    discovered: HashMap<NodeID, usize>,
+    finished: NodeSet,
+    /// The path from the start, with the position in the connections of the next edge to follow for every node.
+    stack: Vec<(NodeID, usize)>,
+    /// Self-loops are listed twice in the connections, but only returned once.
+    self_loops: HashSet<EdgeID>,
+}
+
+impl<'a, G: GraphRef> DepthFirstEdges<'a, G> {
+    /// Creates a search from `start`. Nothing is returned if `start` doesn't exist.
+    pub fn new(graph: &'a G, start: NodeID) -> Self {
+        Self::with_roots(graph, vec![start])
+    }
+
+    /// Creates a search over the whole graph, see [DepthFirstEdges].
+    pub fn all(graph: &'a G) -> Self {
+        let mut roots: Vec<NodeID> = graph.nodes().collect();
+        roots.reverse();
+        Self::with_roots(graph, roots)
+    }
+
+    fn with_roots(graph: &'a G, roots: Vec<NodeID>) -> Self {
+        Self {
+            graph,
+            roots,
+            discovered: HashMap::default(),
+            finished: NodeSet::with_capacity(graph.node_count()),
+            stack: Vec::new(),
+            self_loops: HashSet::default(),
+        }
+    }
+
+    fn discover(&mut self, id: NodeID) {
+        self.discovered.insert(id, self.discovered.len());
+        self.stack.push((id, 0));
+    }
+}
+
+impl<'a, G: GraphRef> Iterator for DepthFirstEdges<'a, G> {
+    type Item = (EdgeID, EdgeKind);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            while let Some(&(id, position)) = self.stack.last() {
+                let Some(edge) = self
+                    .graph
+                    .node(id)
+                    .ok()
+                    .and_then(|node| node.connections.get(position))
+                else {
+                    self.finished.insert(id);
+                    self.stack.pop();
+                    continue;
+                };
+                let top = self.stack.len() - 1;
+                self.stack[top].1 += 1;
+
+                let Ok(edge) = self.graph.edge(*edge) else {
+                    continue;
+                };
+                if edge.from != id || !self.graph.contains_node(edge.to) {
+                    continue;
+                }
+                if edge.to == id && !self.self_loops.insert(edge.id) {
+                    continue;
+                }
+                let kind = match self.discovered.get(&edge.to) {
+                    None => {
+                        self.discover(edge.to);
+                        EdgeKind::Tree
+                    }
+                    Some(_) if !self.finished.contains(edge.to) => EdgeKind::Back,
+                    Some(target) if *target > self.discovered[&id] => EdgeKind::Forward,
+                    Some(_) => EdgeKind::Cross,
+                };
+                return Some((edge.id, kind));
+            }
+
+            let root = self.roots.pop()?;
+            if self.graph.contains_node(root) && !self.discovered.contains_key(&root) {
+                self.discover(root);
+            }
+        }
+    }
+}
+
+impl<'a, G: GraphRef> std::iter::FusedIterator for DepthFirstEdges<'a, G> {}
 
 /// Under development
-pub trait IterDepthFirst<'a, G: GraphInterface> {
+pub trait IterDepthFirst<'a, G: GraphRef> {
     /// Returns a *depth first search* iterator starting from a given node
     fn iter_depth_first(&'a self, start: NodeID) -> DepthFirstSearch<'a, G>;
 
+    /// Returns a *depth first search* iterator starting from a given node that only visits nodes for which `filter` returns true.
+    fn iter_depth_first_filtered<F: FnMut(NodeID) -> bool>(
+        &'a self,
+        start: NodeID,
+        filter: F,
+    ) -> DepthFirstSearch<'a, G, F>;
+
+    /// Returns a *depth first search* iterator over every node of the graph, restarting from unvisited nodes, see [DepthFirstAll].
+    fn iter_depth_first_all(&'a self) -> DepthFirstAll<'a, G>;
+
+    /// Returns a *depth first search* iterator starting from a given node that returns the nodes in post-order, see [DepthFirstPostOrder].
+    fn iter_depth_first_post_order(&'a self, start: NodeID) -> DepthFirstPostOrder<'a, G>;
+
+    /// Returns the nodes reachable from a given node in reverse post-order, a topological order if there are no cycles (see [DepthFirstPostOrder]).
+    fn reverse_post_order(&'a self, start: NodeID) -> std::iter::Rev<std::vec::IntoIter<NodeID>>;
+
+    /// Returns a *depth first search* iterator starting from a given node that returns every followed edge with its [EdgeKind],
+    /// see [DepthFirstEdges].
+    fn iter_depth_first_edges(&'a self, start: NodeID) -> DepthFirstEdges<'a, G>;
+
     /// Returns a vector of sets of node IDs, where each set is a connected component. \
     /// Starts a DFS at every node (except if it's already been visited) and marks all reachable nodes as being part of the same component.
     fn connected_components(&'a self) -> Vec<HashSet<NodeID>>;
 }
 
-impl<'a, G: GraphInterface> IterDepthFirst<'a, G> for G {
+impl<'a, G: GraphRef> IterDepthFirst<'a, G> for G {
     fn iter_depth_first(&'a self, start: NodeID) -> DepthFirstSearch<'a, G> {
         DepthFirstSearch::new(self, start)
     }
 
+    fn iter_depth_first_filtered<F: FnMut(NodeID) -> bool>(
+        &'a self,
+        start: NodeID,
+        filter: F,
+    ) -> DepthFirstSearch<'a, G, F> {
+        DepthFirstSearch::with_filter(self, start, filter)
+    }
+
+    fn iter_depth_first_all(&'a self) -> DepthFirstAll<'a, G> {
+        DepthFirstAll::new(self)
+    }
+
+    fn iter_depth_first_post_order(&'a self, start: NodeID) -> DepthFirstPostOrder<'a, G> {
+        DepthFirstPostOrder::new(self, start)
+    }
+
+    fn reverse_post_order(&'a self, start: NodeID) -> std::iter::Rev<std::vec::IntoIter<NodeID>> {
+        let post_order: Vec<NodeID> = self.iter_depth_first_post_order(start).collect();
+        post_order.into_iter().rev()
+    }
+
+    fn iter_depth_first_edges(&'a self, start: NodeID) -> DepthFirstEdges<'a, G> {
+        DepthFirstEdges::new(self, start)
+    }
+
     /// Returns a vector of sets of node IDs, where each set is a connected component. \
     /// Starts a DFS at every node (except if it's already been visited) and marks all reachable nodes as being part of the same component.
     fn connected_components(&'a self) -> Vec<HashSet<NodeID>> {
-        let mut visited = HashSet::new();
+        let mut visited = NodeSet::with_capacity(self.node_count());
+        // One map is reused by all the searches instead of allocating a new one per component.
+        let mut search_map = self.visit_map();
         let mut components = Vec::new();
         let mut current_component = 0usize;
 
         // Starts a DFS at every node
         for node_id in self.nodes() {
             // (except if it's already been visited)
-            if visited.contains(&node_id) {
+            if visited.contains(node_id) {
                 continue;
             }
-            for node in self.iter_depth_first(node_id) {
+            let mut search = self.iter_depth_first(node_id).with_visit_map(search_map);
+            for node in search.by_ref() {
                 visited.insert(node);
 
                 // and marks all reachable nodes as being part of the same component.
                 if current_component >= components.len() {
-                    components.push(HashSet::new());
+                    components.push(HashSet::default());
                 }
                 components[current_component].insert(node);
             }
+            search_map = search.into_visit_map();
             current_component += 1;
         }
 
@@ -108,7 +435,7 @@ impl<'a, G: GraphInterface> IterDepthFirst<'a, G> for G {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::GraphInterface;
+    use crate::{Graph, GraphInterface};
 
     #[derive(Clone, Debug)]
     enum NodeData {
@@ -255,4 +582,108 @@ mod tests {
 
         assert_eq!(visited.len(), visited2.len());
     }
+
+    #[test]
+    fn test_dfs_filtered() {
+        let mut graph: Graph<NodeData, ()> = Graph::new();
+        let [node0, node1, node2, node3, node4] = get_graph!(graph, 5);
+        graph.add_edges(&[
+            (node0, node1),
+            (node1, node2),
+            (node0, node3),
+            (node3, node4),
+        ]);
+
+        // Node 3 is filtered out, so node 4 can't be reached either.
+        let visited: Vec<NodeID> = graph
+            .iter_depth_first_filtered(node0, |id| id != node3)
+            .collect();
+        assert_eq!(visited.len(), 3);
+        assert!(!visited.contains(&node3));
+        assert!(!visited.contains(&node4));
+
+        assert_eq!(graph.iter_depth_first_filtered(node0, |_| false).count(), 0);
+    }
+
+    #[test]
+    fn test_dfs_all() {
+        let mut graph: Graph<NodeData, ()> = Graph::new();
+        let [node0, node1, node2, node3, node4] = get_graph!(graph, 5);
+        // 3 → 0 is only reached from the later start 3, and 4 is isolated.
+        graph.add_edges(&[
+            (node0, node1),
+            (node1, node0),
+            (node3, node0),
+            (node2, node2),
+        ]);
+
+        let visited: Vec<(usize, NodeID)> = graph.iter_depth_first_all().collect();
+        assert_eq!(
+            visited,
+            vec![(0, node0), (0, node1), (1, node2), (2, node3), (3, node4)]
+        );
+        assert_eq!(
+            Graph::<NodeData, ()>::new().iter_depth_first_all().count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_dfs_post_order() {
+        let mut graph: Graph<NodeData, ()> = Graph::new();
+        let [node0, node1, node2, node3, node4] = get_graph!(graph, 5);
+        // A diamond 0 → {1, 2} → 3 with a back edge 3 → 0, and 4 unreachable.
+        graph.add_edges(&[
+            (node0, node1),
+            (node0, node2),
+            (node1, node3),
+            (node2, node3),
+            (node3, node0),
+            (node4, node0),
+        ]);
+
+        let post_order: Vec<NodeID> = graph.iter_depth_first_post_order(node0).collect();
+        assert_eq!(post_order, vec![node3, node1, node2, node0]);
+        let reverse: Vec<NodeID> = graph.reverse_post_order(node0).collect();
+        assert_eq!(reverse, vec![node0, node2, node1, node3]);
+
+        let removed = graph.add_node(NodeData::Int64(5));
+        graph.remove_node(removed).unwrap();
+        assert_eq!(graph.iter_depth_first_post_order(removed).count(), 0);
+    }
+
+    #[test]
+    fn test_dfs_edge_kinds() {
+        let mut graph: Graph<NodeData, ()> = Graph::new();
+        let [node0, node1, node2, node3, node4] = get_graph!(graph, 5);
+        let edges = graph.add_edges(&[
+            (node0, node1),
+            (node1, node2),
+            (node2, node0),
+            (node0, node2),
+            (node0, node3),
+            (node3, node1),
+            (node3, node3),
+            (node4, node3),
+        ]);
+
+        let kinds: Vec<(EdgeID, EdgeKind)> = graph.iter_depth_first_edges(node0).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (edges[0], EdgeKind::Tree),
+                (edges[1], EdgeKind::Tree),
+                (edges[2], EdgeKind::Back),
+                (edges[3], EdgeKind::Forward),
+                (edges[4], EdgeKind::Tree),
+                (edges[5], EdgeKind::Cross),
+                (edges[6], EdgeKind::Back),
+            ]
+        );
+
+        // Over the whole graph, the edge from the last start is a cross edge into the first search.
+        let all: Vec<(EdgeID, EdgeKind)> = DepthFirstEdges::all(&graph).collect();
+        assert_eq!(all.len(), 8);
+        assert_eq!(all[7], (edges[7], EdgeKind::Cross));
+    }
 }
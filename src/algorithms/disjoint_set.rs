@@ -0,0 +1,248 @@
+//! # Union-find.
+//!
+//! [DisjointSet] keeps track of a partition of keys (usually [NodeID]s) into disjoint sets, with path compression and union by rank,
+//! so [DisjointSet::union] and [DisjointSet::find] take effectively constant time. It is what Kruskal's algorithm
+//! ([single_linkage](super::single_linkage)) uses to tell whether an edge joins two trees, and can be used for any incremental grouping.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::algorithms::DisjointSet;
+//!
+//! let mut graph: Graph<(), ()> = Graph::new();
+//! let nodes = graph.add_nodes(&[(); 4]);
+//!
+//! let mut sets: DisjointSet = nodes.iter().copied().collect();
+//! assert!(sets.union(nodes[0], nodes[1]));
+//! assert!(sets.union(nodes[2], nodes[1]));
+//! assert!(!sets.union(nodes[0], nodes[2]));
+//!
+//! assert!(sets.same_set(nodes[0], nodes[2]));
+//! assert_eq!(sets.set_count(), 2);
+//! assert_eq!(sets.set_size(nodes[3]), Some(1));
+//! ```
+
+use std::hash::Hash;
+
+use crate::hash::HashMap;
+use crate::NodeID;
+
+/// A partition of keys into disjoint sets, see the [module documentation](self).
+#[derive(Clone, Debug)]
+pub struct DisjointSet<K = NodeID> {
+    index: HashMap<K, usize>,
+    keys: Vec<K>,
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    size: Vec<usize>,
+    sets: usize,
+}
+
+impl<K> Default for DisjointSet<K> {
+    fn default() -> Self {
+        DisjointSet {
+            index: HashMap::default(),
+            keys: Vec::new(),
+            parent: Vec::new(),
+            rank: Vec::new(),
+            size: Vec::new(),
+            sets: 0,
+        }
+    }
+}
+
+impl<K: Copy + Eq + Hash> DisjointSet<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        DisjointSet {
+            index: HashMap::with_capacity_and_hasher(capacity, Default::default()),
+            keys: Vec::with_capacity(capacity),
+            parent: Vec::with_capacity(capacity),
+            rank: Vec::with_capacity(capacity),
+            size: Vec::with_capacity(capacity),
+            sets: 0,
+        }
+    }
+
+    /// Adds `key` as a set of its own. Returns false (and changes nothing) if it is already in a set.
+    pub fn insert(&mut self, key: K) -> bool {
+        self.position(key).1
+    }
+
+    pub fn contains(&self, key: K) -> bool {
+        self.index.contains_key(&key)
+    }
+
+    /// The number of keys in all sets.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// The number of disjoint sets.
+    pub fn set_count(&self) -> usize {
+        self.sets
+    }
+
+    /// Returns the representative of the set containing `key`, or `None` if `key` was never inserted.
+    ///
+    /// All keys of a set have the same representative until the set is joined with another one.
+    pub fn find(&mut self, key: K) -> Option<K> {
+        let i = *self.index.get(&key)?;
+        let root = self.root(i);
+        Some(self.keys[root])
+    }
+
+    /// Joins the sets containing `a` and `b`, inserting them first if needed. Returns false if they were already in the same set.
+    pub fn union(&mut self, a: K, b: K) -> bool {
+        let (a, b) = (self.position(a).0, self.position(b).0);
+        let (a, b) = (self.root(a), self.root(b));
+        if a == b {
+            return false;
+        }
+        let (root, child) = if self.rank[a] < self.rank[b] {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        self.parent[child] = root;
+        self.size[root] += self.size[child];
+        if self.rank[a] == self.rank[b] {
+            self.rank[root] += 1;
+        }
+        self.sets -= 1;
+        true
+    }
+
+    /// Whether `a` and `b` are in the same set. Keys that were never inserted are in no set.
+    pub fn same_set(&mut self, a: K, b: K) -> bool {
+        match (self.index.get(&a), self.index.get(&b)) {
+            (Some(a), Some(b)) => {
+                let (a, b) = (*a, *b);
+                self.root(a) == self.root(b)
+            }
+            _ => false,
+        }
+    }
+
+    /// The number of keys in the set containing `key`, or `None` if `key` was never inserted.
+    pub fn set_size(&mut self, key: K) -> Option<usize> {
+        let i = *self.index.get(&key)?;
+        let root = self.root(i);
+        Some(self.size[root])
+    }
+
+    /// Returns all sets, each in insertion order of its keys, ordered by their first inserted key.
+    pub fn sets(&mut self) -> Vec<Vec<K>> {
+        let mut sets: Vec<Vec<K>> = Vec::with_capacity(self.sets);
+        let mut set_of_root: HashMap<usize, usize> = HashMap::default();
+        for i in 0..self.keys.len() {
+            let root = self.root(i);
+            let next = sets.len();
+            let set = *set_of_root.entry(root).or_insert(next);
+            if set == next {
+                sets.push(Vec::with_capacity(self.size[root]));
+            }
+            sets[set].push(self.keys[i]);
+        }
+        sets
+    }
+
+    /// Returns the index of `key`, inserting it if needed, and whether it was inserted.
+    fn position(&mut self, key: K) -> (usize, bool) {
+        if let Some(i) = self.index.get(&key) {
+            return (*i, false);
+        }
+        let i = self.keys.len();
+        self.index.insert(key, i);
+        self.keys.push(key);
+        self.parent.push(i);
+        self.rank.push(0);
+        self.size.push(1);
+        self.sets += 1;
+        (i, true)
+    }
+
+    fn root(&mut self, mut i: usize) -> usize {
+        let mut root = i;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        // Path compression.
+        while self.parent[i] != root {
+            i = std::mem::replace(&mut self.parent[i], root);
+        }
+        root
+    }
+}
+
+impl<K: Copy + Eq + Hash> FromIterator<K> for DisjointSet<K> {
+    /// Creates a set for every key.
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        let mut sets = Self::new();
+        sets.extend(iter);
+        sets
+    }
+}
+
+impl<K: Copy + Eq + Hash> Extend<K> for DisjointSet<K> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        for key in iter {
+            self.insert(key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Graph, GraphInterface, GraphRef};
+
+    #[test]
+    fn test_disjoint_set() {
+        let mut graph: Graph<(), ()> = Graph::new();
+        let nodes = graph.add_nodes(&[(); 6]);
+        graph.add_edges(&[
+            (nodes[0], nodes[1]),
+            (nodes[2], nodes[1]),
+            (nodes[3], nodes[4]),
+            (nodes[4], nodes[3]),
+        ]);
+
+        // Weakly connected components.
+        let mut sets: DisjointSet = graph.nodes().collect();
+        let mut joined = 0;
+        for edge in graph.edges.values() {
+            joined += sets.union(edge.from, edge.to) as usize;
+        }
+        assert_eq!(joined, 3);
+        assert_eq!(sets.len(), 6);
+        assert_eq!(sets.set_count(), 3);
+        assert_eq!(
+            sets.sets(),
+            vec![
+                vec![nodes[0], nodes[1], nodes[2]],
+                vec![nodes[3], nodes[4]],
+                vec![nodes[5]]
+            ]
+        );
+        assert_eq!(sets.find(nodes[2]), sets.find(nodes[0]));
+        assert_eq!(sets.set_size(nodes[1]), Some(3));
+        assert!(!sets.same_set(nodes[0], nodes[5]));
+        assert!(!sets.insert(nodes[5]));
+
+        // Unknown keys are in no set until they are inserted (or joined).
+        let mut sets: DisjointSet<u32> = DisjointSet::new();
+        assert_eq!(sets.find(1), None);
+        assert!(!sets.same_set(1, 1));
+        assert!(sets.union(1, 2));
+        assert_eq!(sets.set_count(), 1);
+        assert!(!sets.is_empty() && sets.contains(2));
+    }
+}
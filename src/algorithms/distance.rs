@@ -0,0 +1,154 @@
+//! # Breadth first distances and layers.
+//!
+//! Both functions, and the [BreadthFirst] iterator, follow outgoing edges (from `edge.from` to `edge.to`), like [DepthFirstSearch](super::DepthFirstSearch).
+//! Dangling edges, whose target doesn't exist, are skipped.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::algorithms::{bfs_distances, bfs_layers};
+//!
+//! let mut graph: Graph<(), ()> = Graph::new();
+//! let nodes = graph.add_nodes(&[(); 4]);
+//! graph.add_edges(&[(nodes[0], nodes[1]), (nodes[0], nodes[2]), (nodes[2], nodes[3])]);
+//!
+//! assert_eq!(bfs_layers(&graph, nodes[0]), vec![vec![nodes[0]], vec![nodes[1], nodes[2]], vec![nodes[3]]]);
+//! assert_eq!(bfs_distances(&graph, nodes[0])[&nodes[3]], 2);
+//! ```
+
+use std::collections::VecDeque;
+
+use super::{VisitMap, Visitable};
+use crate::hash::HashMap;
+use crate::{outgoing_edges, GraphRef, NodeID};
+
+/// Returns the number of edges on a shortest path from `start` to every node reachable from it (`start` itself has distance 0).
+pub fn bfs_distances<G: GraphRef>(graph: &G, start: NodeID) -> HashMap<NodeID, u32> {
+    let layers = bfs_layers(graph, start);
+    let mut distances =
+        HashMap::with_capacity_and_hasher(layers.iter().map(Vec::len).sum(), Default::default());
+    for (distance, layer) in layers.into_iter().enumerate() {
+        for node in layer {
+            distances.insert(node, distance as u32);
+        }
+    }
+    distances
+}
+
+/// Returns the nodes reachable from `start` grouped by their distance from it: layer `i` contains the nodes `i` edges away.
+///
+/// Within a layer the nodes are in the order they were discovered. If `start` doesn't exist the result is empty.
+pub fn bfs_layers<G: GraphRef>(graph: &G, start: NodeID) -> Vec<Vec<NodeID>> {
+    if graph.node(start).is_err() {
+        return Vec::new();
+    }
+    let mut visited = graph.visit_map();
+    visited.visit(start);
+    let mut layers = vec![vec![start]];
+
+    loop {
+        let mut next = Vec::new();
+        for id in layers.last().unwrap() {
+            for edge in outgoing_edges(graph, *id) {
+                if graph.node(edge.to).is_ok() && visited.visit(edge.to) {
+                    next.push(edge.to);
+                }
+            }
+        }
+        if next.is_empty() {
+            return layers;
+        }
+        layers.push(next);
+    }
+}
+
+/// Iterator over the nodes reachable from a start node over outgoing edges, in breadth first order (the order of [bfs_layers], flattened).
+pub struct BreadthFirst<'a, G: GraphRef> {
+    graph: &'a G,
+    queue: VecDeque<NodeID>,
+    visited: VisitMap,
+}
+
+impl<'a, G: GraphRef> BreadthFirst<'a, G> {
+    pub fn new(graph: &'a G, start: NodeID) -> Self {
+        let mut visited = graph.visit_map();
+        let mut queue = VecDeque::new();
+        if graph.node(start).is_ok() {
+            visited.visit(start);
+            queue.push_back(start);
+        }
+        BreadthFirst {
+            graph,
+            queue,
+            visited,
+        }
+    }
+}
+
+impl<'a, G: GraphRef> Iterator for BreadthFirst<'a, G> {
+    type Item = NodeID;
+
+    fn next(&mut self) -> Option<NodeID> {
+        let id = self.queue.pop_front()?;
+        for edge in outgoing_edges(self.graph, id) {
+            if self.graph.node(edge.to).is_ok() && self.visited.visit(edge.to) {
+                self.queue.push_back(edge.to);
+            }
+        }
+        Some(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Graph, GraphInterface};
+
+    #[test]
+    fn test_bfs_distances_and_layers() {
+        let mut graph: Graph<(), ()> = Graph::new();
+        let nodes = graph.add_nodes(&[(); 6]);
+        graph.add_edges(&[
+            (nodes[0], nodes[1]),
+            (nodes[1], nodes[2]),
+            (nodes[0], nodes[2]),
+            (nodes[2], nodes[3]),
+            (nodes[3], nodes[0]),
+            (nodes[4], nodes[0]),
+        ]);
+
+        assert_eq!(
+            bfs_layers(&graph, nodes[0]),
+            vec![vec![nodes[0]], vec![nodes[1], nodes[2]], vec![nodes[3]]]
+        );
+
+        let distances = bfs_distances(&graph, nodes[0]);
+        assert_eq!(distances.len(), 4);
+        assert_eq!(distances[&nodes[2]], 1);
+        assert_eq!(distances[&nodes[3]], 2);
+        // Incoming edges aren't followed, and unconnected nodes aren't reachable.
+        assert!(!distances.contains_key(&nodes[4]));
+        assert!(!distances.contains_key(&nodes[5]));
+
+        assert_eq!(bfs_layers(&graph, nodes[5]), vec![vec![nodes[5]]]);
+        graph.remove_node(nodes[5]).unwrap();
+        assert!(bfs_layers(&graph, nodes[5]).is_empty());
+    }
+
+    #[test]
+    fn test_dangling_edges_are_skipped() {
+        let mut graph: Graph<(), ()> = Graph::new();
+        let nodes = graph.add_nodes(&[(); 3]);
+        graph.add_edges(&[(nodes[0], nodes[1]), (nodes[0], nodes[2])]);
+        graph.nodes.remove(nodes[1]);
+
+        assert_eq!(
+            bfs_layers(&graph, nodes[0]),
+            vec![vec![nodes[0]], vec![nodes[2]]]
+        );
+        assert_eq!(
+            BreadthFirst::new(&graph, nodes[0]).collect::<Vec<_>>(),
+            vec![nodes[0], nodes[2]]
+        );
+    }
+}
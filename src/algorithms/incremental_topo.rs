@@ -0,0 +1,302 @@
+//! # Topological order maintained under edge insertions.
+//!
+//! [IncrementalTopo] owns a [Graph] without cycles together with a topological order of its nodes, and keeps the order valid as
+//! edges are added, using the Pearce–Kelly algorithm: an edge that already agrees with the order is just added, otherwise only the
+//! nodes between its two ends in the order are searched and renumbered. An edge that would close a cycle is rejected and the graph
+//! is left unchanged, so e.g. a dependency manager never needs to re-sort the whole graph (see [toposort](super::toposort)).
+//!
+//! Removing nodes and edges can't create a cycle, so the order stays valid without any work.
+//!
+//! Edges are followed from `edge.from` to `edge.to`, i.e. every node comes after the nodes with edges into it.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::algorithms::{IncrementalTopo, InsertEdgeError};
+//!
+//! let mut topo: IncrementalTopo<&str, ()> = IncrementalTopo::default();
+//! let app = topo.add_node("app");
+//! let http = topo.add_node("http");
+//! let tls = topo.add_node("tls");
+//!
+//! // `http` needs `tls` and `app` needs `http`, so both are moved in front of `app`.
+//! topo.add_edge(tls, http, ()).unwrap();
+//! topo.add_edge(http, app, ()).unwrap();
+//! assert_eq!(topo.order().collect::<Vec<_>>(), vec![tls, http, app]);
+//!
+//! // `tls` can't depend on `app`.
+//! let Err(InsertEdgeError::Cycle { path }) = topo.add_edge(app, tls, ()) else { panic!() };
+//! assert_eq!(path, vec![tls, http, app]);
+//! assert_eq!(topo.graph().edges.len(), 2);
+//! ```
+
+use thiserror::Error;
+
+use super::{toposort, CycleError};
+use crate::hash::HashMap;
+use crate::*;
+
+/// Returned by [IncrementalTopo::add_edge] when the edge isn't added.
+#[derive(Clone, Debug, Error)]
+pub enum InsertEdgeError {
+    #[error(transparent)]
+    Graph(#[from] GraphError),
+    /// The edge would close a cycle. `path` leads from the target of the edge to its source (a single node for a self-loop).
+    #[error("Edge would close a cycle through {} node(s)", .path.len())]
+    Cycle { path: Vec<NodeID> },
+}
+
+/// A [Graph] without cycles that keeps a topological order of its nodes, see the [module documentation](self).
+#[derive(Clone)]
+pub struct IncrementalTopo<N, E> {
+    graph: Graph<N, E>,
+    /// The position of every node in `slots`.
+    position: HashMap<NodeID, usize>,
+    /// The nodes in topological order, with holes left by removed nodes.
+    slots: Vec<Option<NodeID>>,
+}
+
+impl<N, E> Default for IncrementalTopo<N, E> {
+    fn default() -> Self {
+        IncrementalTopo {
+            graph: Graph::new(),
+            position: HashMap::default(),
+            slots: Vec::new(),
+        }
+    }
+}
+
+impl<N, E> IncrementalTopo<N, E> {
+    /// Sorts `graph` once with [toposort], or returns the [CycleError] if it already has a cycle.
+    pub fn new(graph: Graph<N, E>) -> Result<Self, CycleError> {
+        let order = toposort(&graph)?;
+        let mut topo = IncrementalTopo {
+            graph,
+            position: HashMap::default(),
+            slots: Vec::new(),
+        };
+        topo.renumber(order.into_iter());
+        Ok(topo)
+    }
+
+    /// Returns the underlying graph. Add edges through [IncrementalTopo::add_edge], so the order stays valid.
+    pub fn graph(&self) -> &Graph<N, E> {
+        &self.graph
+    }
+
+    pub fn into_graph(self) -> Graph<N, E> {
+        self.graph
+    }
+
+    /// Returns the nodes in the current topological order.
+    pub fn order(&self) -> impl Iterator<Item = NodeID> + '_ {
+        self.slots.iter().flatten().copied()
+    }
+
+    /// Returns true if `a` comes before `b` in the current order, false if not or if either node doesn't exist.
+    ///
+    /// Nodes without a path between them can change places when edges are added.
+    pub fn comes_before(&self, a: NodeID, b: NodeID) -> bool {
+        matches!((self.position.get(&a), self.position.get(&b)), (Some(a), Some(b)) if a < b)
+    }
+
+    /// Adds a node at the end of the order.
+    pub fn add_node(&mut self, data: N) -> NodeID {
+        let id = self.graph.add_node(data);
+        self.position.insert(id, self.slots.len());
+        self.slots.push(Some(id));
+        id
+    }
+
+    /// Adds an edge from `from` to `to`, moving nodes as needed so that everything that reaches `to` comes before it.
+    ///
+    /// Returns [InsertEdgeError::Cycle] without changing anything if `to` already reaches `from`. Only the nodes between
+    /// `to` and `from` in the current order are searched.
+    pub fn add_edge(
+        &mut self,
+        from: NodeID,
+        to: NodeID,
+        data: E,
+    ) -> Result<EdgeID, InsertEdgeError> {
+        interface::check_endpoints(&self.graph, from, to)?;
+        if from == to {
+            return Err(InsertEdgeError::Cycle { path: vec![from] });
+        }
+        let lower = self.position[&to];
+        let upper = self.position[&from];
+        if lower < upper {
+            // The nodes reachable from `to` that are placed up to `from`, and the nodes reaching `from` placed after `to`.
+            let forward = self.search(
+                to,
+                |edge| (edge.from, edge.to),
+                |position| position <= upper,
+            );
+            if forward.contains_key(&from) {
+                let mut path = vec![from];
+                while let Some(Some(previous)) = forward.get(path.last().unwrap()) {
+                    path.push(*previous);
+                }
+                path.reverse();
+                return Err(InsertEdgeError::Cycle { path });
+            }
+            let backward = self.search(
+                from,
+                |edge| (edge.to, edge.from),
+                |position| position > lower,
+            );
+            self.reorder(backward.into_keys(), forward.into_keys());
+        }
+        Ok(self.graph.add_edge(from, to, data))
+    }
+
+    /// Removes an edge. The order stays valid.
+    pub fn remove_edge(&mut self, id: EdgeID) -> Result<(), GraphError> {
+        self.graph.remove_edge(id)
+    }
+
+    /// Removes a node and its edges. The order of the other nodes stays valid.
+    pub fn remove_node(&mut self, id: NodeID) -> Result<(), GraphError> {
+        self.graph.remove_node(id)?;
+        if let Some(position) = self.position.remove(&id) {
+            self.slots[position] = None;
+        }
+        // Drop the holes once they make up most of the slots.
+        if self.position.len() * 2 < self.slots.len() {
+            let order: Vec<NodeID> = self.order().collect();
+            self.renumber(order.into_iter());
+        }
+        Ok(())
+    }
+
+    fn renumber(&mut self, order: impl Iterator<Item = NodeID>) {
+        self.slots.clear();
+        self.position.clear();
+        for (position, id) in order.enumerate() {
+            self.position.insert(id, position);
+            self.slots.push(Some(id));
+        }
+    }
+
+    /// Depth first search from `start` over the edges `ends` maps to `(this node, next node)`, only entering nodes whose
+    /// position passes `bound`. Returns every visited node with the node it was reached from.
+    fn search(
+        &self,
+        start: NodeID,
+        ends: impl Fn(&Edge<E>) -> (NodeID, NodeID),
+        bound: impl Fn(usize) -> bool,
+    ) -> HashMap<NodeID, Option<NodeID>> {
+        let mut visited = HashMap::default();
+        visited.insert(start, None);
+        let mut stack = vec![start];
+        while let Some(id) = stack.pop() {
+            for edge in self.graph.nodes[id]
+                .connections
+                .iter()
+                .filter_map(|edge| self.graph.edge(*edge).ok())
+            {
+                let (this, next) = ends(edge);
+                if this != id || visited.contains_key(&next) {
+                    continue;
+                }
+                if matches!(self.position.get(&next), Some(position) if bound(*position)) {
+                    visited.insert(next, Some(id));
+                    stack.push(next);
+                }
+            }
+        }
+        visited
+    }
+
+    /// Gives the positions of both sets of nodes to the `backward` nodes followed by the `forward` nodes, keeping the relative
+    /// order within each set.
+    fn reorder(
+        &mut self,
+        backward: impl Iterator<Item = NodeID>,
+        forward: impl Iterator<Item = NodeID>,
+    ) {
+        let mut backward: Vec<NodeID> = backward.collect();
+        let mut forward: Vec<NodeID> = forward.collect();
+        backward.sort_by_key(|id| self.position[id]);
+        forward.sort_by_key(|id| self.position[id]);
+        let mut positions: Vec<usize> = backward
+            .iter()
+            .chain(&forward)
+            .map(|id| self.position[id])
+            .collect();
+        positions.sort_unstable();
+        for (id, position) in backward.into_iter().chain(forward).zip(positions) {
+            self.position.insert(id, position);
+            self.slots[position] = Some(id);
+        }
+    }
+}
+
+impl<N, E> GraphRef for IncrementalTopo<N, E> {
+    type NodeData = N;
+    type EdgeData = E;
+
+    fn nodes(&self) -> impl Iterator<Item = NodeID> {
+        self.graph.nodes()
+    }
+
+    fn node_count(&self) -> usize {
+        self.graph.node_count()
+    }
+
+    fn node(&self, id: NodeID) -> Result<&Node<N>, GraphError> {
+        self.graph.node(id)
+    }
+
+    fn edge(&self, id: EdgeID) -> Result<&Edge<E>, GraphError> {
+        self.graph.edge(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_incremental_topo() {
+        let mut graph: Graph<usize, ()> = Graph::new();
+        let nodes = graph.add_nodes(&(0..8).collect::<Vec<_>>());
+        graph.add_edges(&[(nodes[0], nodes[1]), (nodes[1], nodes[2])]);
+        let mut topo = IncrementalTopo::new(graph).unwrap();
+
+        // Edges against the order, in an order that keeps moving nodes around.
+        let edges = [(7, 6), (6, 5), (5, 0), (2, 4), (4, 3), (1, 3)];
+        for (from, to) in edges {
+            topo.add_edge(nodes[from], nodes[to], ()).unwrap();
+            assert_eq!(topo.order().count(), 8);
+            for edge in topo.graph().edge_references() {
+                assert!(topo.comes_before(edge.from, edge.to));
+            }
+        }
+        // 7 → 6 → 5 → 0 → 1 → ... → 3
+        let Err(InsertEdgeError::Cycle { path }) = topo.add_edge(nodes[3], nodes[7], ()) else {
+            panic!("expected a cycle");
+        };
+        assert_eq!(
+            path[..5],
+            [nodes[7], nodes[6], nodes[5], nodes[0], nodes[1]]
+        );
+        assert_eq!(path.last(), Some(&nodes[3]));
+        assert!(matches!(
+            topo.add_edge(nodes[4], nodes[4], ()),
+            Err(InsertEdgeError::Cycle { .. })
+        ));
+        assert_eq!(topo.graph().edges.len(), 8);
+
+        topo.remove_node(nodes[3]).unwrap();
+        let last = topo.add_node(8);
+        topo.add_edge(last, nodes[7], ()).unwrap();
+        assert!(topo.comes_before(last, nodes[5]));
+        assert_eq!(topo.order().count(), 8);
+        assert_eq!(toposort(topo.graph()).unwrap().len(), 8);
+
+        let mut graph: Graph<(), ()> = Graph::new();
+        let nodes = graph.add_nodes(&[(); 2]);
+        graph.add_edges(&[(nodes[0], nodes[1]), (nodes[1], nodes[0])]);
+        assert!(IncrementalTopo::new(graph).is_err());
+    }
+}
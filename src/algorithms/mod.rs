@@ -1,3 +1,58 @@
+//! # Graph algorithms.
+//!
+//! The functions, iterators and indices in this module read the graph through [GraphRef](crate::GraphRef), and the helper
+//! traits ([IterDepthFirst], [Visitable]) are implemented for every [GraphRef]. Since [GraphInterface](crate::GraphInterface)
+//! extends [GraphRef](crate::GraphRef), they work the same on a [Graph](crate::Graph), a [CategorizedGraph](crate::CategorizedGraph)
+//! (where category nodes and their edges are ordinary nodes and edges), a [PersistentGraph](crate::PersistentGraph), views and
+//! `&G`. `tests/test_algorithms.rs` runs them against all three graph types.
+//!
+//! The exception is [IncrementalTopo], which owns a [Graph](crate::Graph) so that every edge goes through it and the order stays valid.
+//! [DisjointSet], [NodeSet], [NodePriorityQueue] and [VisitMap] are building blocks that don't take a graph at all.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::algorithms::{bfs_distances, toposort};
+//!
+//! fn depth<G: GraphInterface<NodeData = (), EdgeData = ()>>(mut graph: G) -> u32 {
+//!     let nodes = graph.add_nodes(&[(); 3]);
+//!     graph.add_edges(&[(nodes[0], nodes[1]), (nodes[1], nodes[2])]);
+//!     assert_eq!(toposort(&graph).unwrap(), nodes);
+//!     bfs_distances(&graph, nodes[0])[&nodes[2]]
+//! }
+//!
+//! assert_eq!(depth(Graph::new()), 2);
+//! assert_eq!(depth(PersistentGraph::new()), 2);
+//! ```
+
+mod best_first;
+mod bounded;
+mod clustering;
+mod contraction;
 mod dfs;
+mod disjoint_set;
 //mod bfs;
+mod distance;
+mod incremental_topo;
+mod node_set;
+mod ordering;
+mod priority_queue;
+mod reachability;
+mod toposort;
+mod triangles;
+mod visit;
+pub use best_first::*;
+pub use bounded::*;
+pub use clustering::*;
+pub use contraction::*;
 pub use dfs::*;
+pub use disjoint_set::*;
+pub use distance::*;
+pub use incremental_topo::*;
+pub use node_set::*;
+pub use ordering::*;
+pub use priority_queue::*;
+pub use reachability::*;
+pub use toposort::*;
+pub use triangles::*;
+pub use visit::*;
@@ -0,0 +1,193 @@
+//! # Bitset of nodes.
+//!
+//! A [NodeSet] is a set of [NodeID]s stored as a bitset keyed by slot index, like [VisitMap](super::VisitMap), but it also stores the
+//! version of every member. When a node is removed and its slot reused, the old ID is not a member just because the new one is,
+//! so a set stays correct while the graph changes. Membership tests are a bit operation and a comparison instead of hashing,
+//! which makes it a faster alternative to `HashSet<NodeID>` for visited sets and component membership.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::algorithms::NodeSet;
+//!
+//! let mut graph: Graph<(), ()> = Graph::new();
+//! let nodes = graph.add_nodes(&[(); 3]);
+//!
+//! let mut set = NodeSet::with_capacity(graph.node_count());
+//! assert!(set.insert(nodes[2]));
+//! assert!(!set.insert(nodes[2]));
+//!
+//! graph.remove_node(nodes[2]).unwrap();
+//! let reused = graph.add_node(());
+//! assert!(!set.contains(reused));
+//! assert_eq!(set.iter().collect::<Vec<_>>(), vec![nodes[2]]);
+//! ```
+
+use slotmap::KeyData;
+
+use crate::NodeID;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A set of nodes stored as a bitset keyed by slot index, see the [module documentation](self).
+#[derive(Clone, Debug, Default)]
+pub struct NodeSet {
+    words: Vec<u64>,
+    /// The version of the member in every slot with a set bit.
+    versions: Vec<u32>,
+    len: usize,
+}
+
+impl NodeSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a set that can hold nodes with a slot index below `slots` without reallocating.
+    pub fn with_capacity(slots: usize) -> Self {
+        NodeSet {
+            words: vec![0; slots.div_ceil(WORD_BITS)],
+            versions: vec![0; slots],
+            len: 0,
+        }
+    }
+
+    fn split(node: NodeID) -> (usize, u32) {
+        // The FFI representation is the version in the upper and the slot index in the lower 32 bits.
+        let ffi = node.to_u64();
+        (ffi as u32 as usize, (ffi >> 32) as u32)
+    }
+
+    fn is_set(&self, slot: usize) -> bool {
+        self.words
+            .get(slot / WORD_BITS)
+            .is_some_and(|word| word & (1 << (slot % WORD_BITS)) != 0)
+    }
+
+    /// Adds a node. Returns true if it wasn't in the set before.
+    ///
+    /// An older node with the same slot (which must have been removed from the graph) is replaced.
+    pub fn insert(&mut self, node: NodeID) -> bool {
+        let (slot, version) = Self::split(node);
+        if self.is_set(slot) {
+            let replaced = self.versions[slot] != version;
+            self.versions[slot] = version;
+            return replaced;
+        }
+        if self.words.len() <= slot / WORD_BITS {
+            self.words.resize(slot / WORD_BITS + 1, 0);
+        }
+        if self.versions.len() <= slot {
+            self.versions.resize(slot + 1, 0);
+        }
+        self.words[slot / WORD_BITS] |= 1 << (slot % WORD_BITS);
+        self.versions[slot] = version;
+        self.len += 1;
+        true
+    }
+
+    /// Removes a node. Returns true if it was in the set.
+    pub fn remove(&mut self, node: NodeID) -> bool {
+        if !self.contains(node) {
+            return false;
+        }
+        let (slot, _) = Self::split(node);
+        self.words[slot / WORD_BITS] &= !(1 << (slot % WORD_BITS));
+        self.len -= 1;
+        true
+    }
+
+    pub fn contains(&self, node: NodeID) -> bool {
+        let (slot, version) = Self::split(node);
+        self.is_set(slot) && self.versions[slot] == version
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all nodes, keeping the allocation.
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+        self.len = 0;
+    }
+
+    /// Iterates over the nodes in slot order.
+    pub fn iter(&self) -> impl Iterator<Item = NodeID> + '_ {
+        self.words.iter().enumerate().flat_map(move |(i, word)| {
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let slot = i * WORD_BITS + word.trailing_zeros() as usize;
+                word &= word - 1;
+                let ffi = (self.versions[slot] as u64) << 32 | slot as u64;
+                Some(NodeID::from(KeyData::from_ffi(ffi)))
+            })
+        })
+    }
+}
+
+impl PartialEq for NodeSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().all(|node| other.contains(node))
+    }
+}
+
+impl Eq for NodeSet {}
+
+impl FromIterator<NodeID> for NodeSet {
+    fn from_iter<I: IntoIterator<Item = NodeID>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<NodeID> for NodeSet {
+    fn extend<I: IntoIterator<Item = NodeID>>(&mut self, iter: I) {
+        for node in iter {
+            self.insert(node);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Graph, GraphInterface};
+
+    #[test]
+    fn test_node_set() {
+        let mut graph: Graph<(), ()> = Graph::new();
+        let nodes = graph.add_nodes(&[(); 130]);
+
+        let mut set: NodeSet = [nodes[129], nodes[3], nodes[64]].into_iter().collect();
+        assert_eq!(set.len(), 3);
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec![nodes[3], nodes[64], nodes[129]]
+        );
+        assert!(set.remove(nodes[64]));
+        assert!(!set.remove(nodes[64]));
+        assert!(!set.contains(nodes[64]));
+
+        // A new node in a reused slot is a different member.
+        graph.remove_node(nodes[3]).unwrap();
+        let reused = graph.add_node(());
+        assert!(!set.contains(reused));
+        assert!(set.insert(reused));
+        assert!(!set.contains(nodes[3]));
+        assert_eq!(set.len(), 2);
+        assert_eq!(set, [reused, nodes[129]].into_iter().collect());
+
+        set.clear();
+        assert!(set.is_empty());
+        assert_eq!(set.iter().count(), 0);
+    }
+}
@@ -0,0 +1,164 @@
+//! # Node orderings.
+//!
+//! These functions return every node of a graph once, in an order that is useful for storage or export:
+//!
+//! - [reverse_cuthill_mckee] numbers nodes breadth first from a low degree node, visiting neighbors by increasing degree, and
+//!   reverses the result. Neighbors end up close to each other, which reduces the [bandwidth] of the adjacency matrix and
+//!   improves the locality of traversals (see [LayoutOrder::ReverseCuthillMcKee](crate::locality::LayoutOrder)).
+//! - [degree_order] sorts the nodes by increasing [degree].
+//!
+//! Pass an ordering to [Graph::optimize_layout_with](crate::Graph::optimize_layout_with) to store the graph in it, or to
+//! `Graph::to_adjacency_matrix_ordered` (with the `ndarray` feature) to export a matrix in it.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::algorithms::{bandwidth, reverse_cuthill_mckee};
+//!
+//! // A path added in a scrambled order: 0 - 3 - 1 - 4 - 2.
+//! let mut graph: Graph<(), ()> = Graph::new();
+//! let nodes = graph.add_nodes(&[(); 5]);
+//! graph.add_edges(&[(nodes[0], nodes[3]), (nodes[3], nodes[1]), (nodes[1], nodes[4]), (nodes[4], nodes[2])]);
+//! assert_eq!(bandwidth(&graph, &nodes), 3);
+//!
+//! let order = reverse_cuthill_mckee(&graph);
+//! assert_eq!(bandwidth(&graph, &order), 1);
+//! ```
+
+use std::collections::VecDeque;
+
+use crate::hash::HashMap;
+use crate::metrics::degree;
+use crate::{GraphRef, NodeID};
+
+/// The distinct neighbors of every node in either direction (without the node itself), by position in `graph.nodes()`.
+fn undirected_neighbors<G: GraphRef>(graph: &G) -> (Vec<NodeID>, Vec<Vec<usize>>) {
+    let nodes: Vec<NodeID> = graph.nodes().collect();
+    let index: HashMap<NodeID, usize> = nodes.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+    let mut neighbors = vec![Vec::new(); nodes.len()];
+    for (i, id) in nodes.iter().enumerate() {
+        let Ok(node) = graph.node(*id) else {
+            continue;
+        };
+        for edge in node
+            .connections
+            .iter()
+            .filter_map(|edge| graph.edge(*edge).ok())
+        {
+            let other = if edge.from == *id { edge.to } else { edge.from };
+            match index.get(&other) {
+                Some(j) if *j != i => neighbors[i].push(*j),
+                _ => {}
+            }
+        }
+        neighbors[i].sort_unstable();
+        neighbors[i].dedup();
+    }
+    (nodes, neighbors)
+}
+
+/// Returns the nodes in reverse Cuthill–McKee order, ignoring edge directions, see the [module documentation](self).
+///
+/// Every connected component is numbered from its node with the fewest neighbors (ties in slot order), and the components
+/// follow each other. Runs in O(nodes + edges · log(degree)).
+pub fn reverse_cuthill_mckee<G: GraphRef>(graph: &G) -> Vec<NodeID> {
+    let (nodes, neighbors) = undirected_neighbors(graph);
+    let mut starts: Vec<usize> = (0..nodes.len()).collect();
+    starts.sort_by_key(|i| neighbors[*i].len());
+
+    let mut order = Vec::with_capacity(nodes.len());
+    let mut placed = vec![false; nodes.len()];
+    let mut queue = VecDeque::new();
+    let mut next: Vec<usize> = Vec::new();
+    for start in starts {
+        if placed[start] {
+            continue;
+        }
+        placed[start] = true;
+        queue.push_back(start);
+        while let Some(i) = queue.pop_front() {
+            order.push(nodes[i]);
+            next.extend(neighbors[i].iter().filter(|j| !placed[**j]));
+            next.sort_by_key(|j| neighbors[*j].len());
+            for j in next.drain(..) {
+                placed[j] = true;
+                queue.push_back(j);
+            }
+        }
+    }
+    order.reverse();
+    order
+}
+
+/// Returns the nodes sorted by increasing [degree] (ties in slot order). Reverse it for hubs first,
+/// or use [nodes_by_degree](crate::metrics::nodes_by_degree) to only take the first few.
+pub fn degree_order<G: GraphRef>(graph: &G) -> Vec<NodeID> {
+    let mut order: Vec<(usize, NodeID)> = graph.nodes().map(|id| (degree(graph, id), id)).collect();
+    order.sort_by_key(|(degree, _)| *degree);
+    order.into_iter().map(|(_, id)| id).collect()
+}
+
+/// Returns the bandwidth of the adjacency matrix with rows and columns in `order`: the largest distance in `order` between
+/// the two ends of an edge. Edges touching nodes that aren't in `order` are ignored.
+pub fn bandwidth<G: GraphRef>(graph: &G, order: &[NodeID]) -> usize {
+    let position: HashMap<NodeID, usize> =
+        order.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+    let mut bandwidth = 0;
+    for (i, id) in order.iter().enumerate() {
+        let Ok(node) = graph.node(*id) else {
+            continue;
+        };
+        for edge in node
+            .connections
+            .iter()
+            .filter_map(|edge| graph.edge(*edge).ok())
+        {
+            let other = if edge.from == *id { edge.to } else { edge.from };
+            if let Some(j) = position.get(&other) {
+                bandwidth = bandwidth.max(i.abs_diff(*j));
+            }
+        }
+    }
+    bandwidth
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Graph, GraphInterface};
+
+    #[test]
+    fn test_orderings() {
+        // An 8x8 grid with its nodes added in a scrambled order, plus an isolated node.
+        let mut graph: Graph<(), ()> = Graph::new();
+        let nodes = graph.add_nodes(&[(); 65]);
+        let cell = |x: usize, y: usize| nodes[(y * 8 + x) * 37 % 64];
+        for y in 0..8 {
+            for x in 0..8 {
+                if x < 7 {
+                    graph.add_edge(cell(x, y), cell(x + 1, y), ());
+                }
+                if y < 7 {
+                    graph.add_edge(cell(x, y + 1), cell(x, y), ());
+                }
+            }
+        }
+        graph.add_edge(cell(0, 0), cell(0, 0), ());
+
+        let order = reverse_cuthill_mckee(&graph);
+        assert_eq!(order.len(), 65);
+        let mut sorted = order.clone();
+        sorted.sort();
+        assert_eq!(sorted, nodes);
+        // Level sets of a grid from a corner are diagonals of at most 8 nodes.
+        assert!(bandwidth(&graph, &nodes) > 20);
+        assert!(bandwidth(&graph, &order) <= 8);
+        // The isolated node has the fewest neighbors, so it's numbered first and ends up last.
+        assert_eq!(order.last(), Some(&nodes[64]));
+
+        let by_degree = degree_order(&graph);
+        assert_eq!(by_degree[0], nodes[64]);
+        assert_eq!(degree(&graph, by_degree[1]), 2);
+        assert_eq!(degree(&graph, by_degree[64]), 4);
+    }
+}
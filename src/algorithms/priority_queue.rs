@@ -0,0 +1,243 @@
+//! # Indexed priority queue.
+//!
+//! [NodePriorityQueue] is a binary min-heap of nodes that also knows where every node is in the heap, so the priority of a queued node
+//! can be looked up, lowered ([NodePriorityQueue::decrease_key]) or changed in `O(log n)`. This keeps every node in the queue at most
+//! once, unlike [BestFirst](super::BestFirst), which pushes a new entry for every improvement and skips the stale ones.
+//!
+//! Priorities have to implement [Ord]; wrap floating point priorities in a type that does. Ties are broken by [NodeID].
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::algorithms::NodePriorityQueue;
+//!
+//! let mut graph: Graph<(), u32> = Graph::new();
+//! let nodes = graph.add_nodes(&[(); 3]);
+//! graph.add_edge(nodes[0], nodes[1], 5);
+//! graph.add_edge(nodes[0], nodes[2], 1);
+//! graph.add_edge(nodes[2], nodes[1], 1);
+//!
+//! // Dijkstra.
+//! let mut queue = NodePriorityQueue::new();
+//! let mut distances = Vec::new();
+//! queue.push(nodes[0], 0);
+//! while let Some((id, distance)) = queue.pop() {
+//!     distances.push((id, distance));
+//!     for edge in graph.node(id).unwrap().connections.iter().map(|edge| graph.edge(*edge).unwrap()) {
+//!         if edge.from == id && !distances.iter().any(|(done, _)| *done == edge.to) {
+//!             queue.push_decrease(edge.to, distance + edge.data);
+//!         }
+//!     }
+//! }
+//! assert_eq!(distances, vec![(nodes[0], 0), (nodes[2], 1), (nodes[1], 2)]);
+//! ```
+
+use slotmap::SecondaryMap;
+
+use crate::NodeID;
+
+/// A min-heap of nodes with decrease-key, see the [module documentation](self).
+#[derive(Clone, Debug)]
+pub struct NodePriorityQueue<W> {
+    heap: Vec<(W, NodeID)>,
+    /// The index of every queued node in `heap`.
+    positions: SecondaryMap<NodeID, usize>,
+}
+
+impl<W> Default for NodePriorityQueue<W> {
+    fn default() -> Self {
+        NodePriorityQueue {
+            heap: Vec::new(),
+            positions: SecondaryMap::new(),
+        }
+    }
+}
+
+impl<W: Ord> NodePriorityQueue<W> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        NodePriorityQueue {
+            heap: Vec::with_capacity(capacity),
+            positions: SecondaryMap::with_capacity(capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn contains(&self, node: NodeID) -> bool {
+        self.positions.contains_key(node)
+    }
+
+    /// Returns the priority of a queued node.
+    pub fn priority(&self, node: NodeID) -> Option<&W> {
+        self.positions.get(node).map(|i| &self.heap[*i].0)
+    }
+
+    /// Returns the node with the smallest priority without removing it.
+    pub fn peek(&self) -> Option<(NodeID, &W)> {
+        self.heap.first().map(|(priority, node)| (*node, priority))
+    }
+
+    /// Queues a node, or changes its priority if it is already queued. Returns the previous priority.
+    pub fn push(&mut self, node: NodeID, priority: W) -> Option<W> {
+        if let Some(i) = self.positions.get(node).copied() {
+            let old = std::mem::replace(&mut self.heap[i].0, priority);
+            self.restore(i);
+            return Some(old);
+        }
+        self.heap.push((priority, node));
+        self.positions.insert(node, self.heap.len() - 1);
+        self.sift_up(self.heap.len() - 1);
+        None
+    }
+
+    /// Lowers the priority of a queued node. Returns false (and changes nothing) if the node isn't queued
+    /// or `priority` isn't smaller than its current one.
+    pub fn decrease_key(&mut self, node: NodeID, priority: W) -> bool {
+        match self.positions.get(node).copied() {
+            Some(i) if priority < self.heap[i].0 => {
+                self.heap[i].0 = priority;
+                self.sift_up(i);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Queues a node, or lowers its priority if it is already queued with a larger one, like the relaxation step of Dijkstra.
+    /// Returns true if the queue changed.
+    pub fn push_decrease(&mut self, node: NodeID, priority: W) -> bool {
+        if self.contains(node) {
+            self.decrease_key(node, priority)
+        } else {
+            self.push(node, priority);
+            true
+        }
+    }
+
+    /// Removes and returns the node with the smallest priority.
+    pub fn pop(&mut self) -> Option<(NodeID, W)> {
+        if self.heap.is_empty() {
+            return None;
+        }
+        let (priority, node) = self.take(0);
+        Some((node, priority))
+    }
+
+    /// Removes a node from the queue, returning its priority.
+    pub fn remove(&mut self, node: NodeID) -> Option<W> {
+        let i = *self.positions.get(node)?;
+        Some(self.take(i).0)
+    }
+
+    pub fn clear(&mut self) {
+        self.heap.clear();
+        self.positions.clear();
+    }
+
+    fn take(&mut self, i: usize) -> (W, NodeID) {
+        let entry = self.heap.swap_remove(i);
+        self.positions.remove(entry.1);
+        if i < self.heap.len() {
+            self.positions[self.heap[i].1] = i;
+            self.restore(i);
+        }
+        entry
+    }
+
+    /// Moves the entry at `i` up or down after its priority changed.
+    fn restore(&mut self, i: usize) {
+        if i > 0 && self.heap[i] < self.heap[(i - 1) / 2] {
+            self.sift_up(i);
+        } else {
+            self.sift_down(i);
+        }
+    }
+
+    fn sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if self.heap[i] >= self.heap[parent] {
+                break;
+            }
+            self.swap(i, parent);
+            i = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut i: usize) {
+        loop {
+            let mut smallest = i;
+            for child in [2 * i + 1, 2 * i + 2] {
+                if child < self.heap.len() && self.heap[child] < self.heap[smallest] {
+                    smallest = child;
+                }
+            }
+            if smallest == i {
+                return;
+            }
+            self.swap(i, smallest);
+            i = smallest;
+        }
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.positions[self.heap[a].1] = a;
+        self.positions[self.heap[b].1] = b;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Graph, GraphInterface};
+
+    #[test]
+    fn test_node_priority_queue() {
+        let mut graph: Graph<(), ()> = Graph::new();
+        let nodes = graph.add_nodes(&[(); 6]);
+
+        let mut queue = NodePriorityQueue::with_capacity(nodes.len());
+        for (i, node) in nodes.iter().enumerate() {
+            assert_eq!(queue.push(*node, 10 * (i as u32 + 1)), None);
+        }
+        assert_eq!(queue.peek(), Some((nodes[0], &10)));
+
+        assert!(queue.decrease_key(nodes[4], 5));
+        assert!(!queue.decrease_key(nodes[4], 7));
+        assert_eq!(queue.priority(nodes[4]), Some(&5));
+        // Ties are broken by ID.
+        assert!(queue.push_decrease(nodes[3], 5));
+        assert!(!queue.push_decrease(nodes[3], 40));
+        assert_eq!(queue.push(nodes[0], 100), Some(10));
+        assert_eq!(queue.remove(nodes[1]), Some(20));
+        assert_eq!(queue.remove(nodes[1]), None);
+        assert_eq!(queue.len(), 5);
+
+        let mut order = Vec::new();
+        while let Some(entry) = queue.pop() {
+            order.push(entry);
+        }
+        assert_eq!(
+            order,
+            vec![
+                (nodes[3], 5),
+                (nodes[4], 5),
+                (nodes[2], 30),
+                (nodes[5], 60),
+                (nodes[0], 100)
+            ]
+        );
+        assert!(queue.is_empty() && !queue.contains(nodes[0]));
+    }
+}
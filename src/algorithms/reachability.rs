@@ -0,0 +1,201 @@
+//! # Reachability index with 2-hop labels.
+//!
+//! [ReachabilityIndex] answers "is there a path from `a` to `b`?" without a traversal, by pruned landmark labeling:
+//! every node gets a list of landmarks it can reach (`out`) and a list of landmarks that can reach it (`in`), and `a` reaches `b`
+//! exactly if the two lists share a landmark. The nodes are processed as landmarks from the highest to the lowest degree, and
+//! every breadth first search stops at nodes whose reachability is already covered by earlier landmarks, which keeps the labels
+//! short on real-world graphs (and DAGs in particular).
+//!
+//! Building takes a search per node (most of them pruned early); a query is a merge of two short sorted lists.
+//! Edges are followed from `edge.from` to `edge.to`. The index is a snapshot: rebuild it after changing the graph.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::algorithms::ReachabilityIndex;
+//!
+//! let mut graph: Graph<(), ()> = Graph::new();
+//! let nodes = graph.add_nodes(&[(); 4]);
+//! graph.add_edges(&[(nodes[0], nodes[1]), (nodes[1], nodes[2]), (nodes[3], nodes[2])]);
+//!
+//! let index = ReachabilityIndex::build(&graph);
+//! assert!(index.can_reach(nodes[0], nodes[2]));
+//! assert!(!index.can_reach(nodes[2], nodes[0]));
+//! assert!(!index.can_reach(nodes[0], nodes[3]));
+//! ```
+
+use std::collections::VecDeque;
+
+use crate::hash::HashMap;
+use crate::{outgoing_edges, GraphRef, NodeID};
+
+/// A reachability index built with pruned landmark labeling, see the [module documentation](self).
+#[derive(Clone, Debug, Default)]
+pub struct ReachabilityIndex {
+    index: HashMap<NodeID, usize>,
+    /// The ranks of the landmarks every node reaches, sorted.
+    out_labels: Vec<Vec<u32>>,
+    /// The ranks of the landmarks that reach every node, sorted.
+    in_labels: Vec<Vec<u32>>,
+}
+
+impl ReachabilityIndex {
+    /// Builds the index for the current state of the graph.
+    ///
+    /// Connections to removed edges and edges to nodes that don't exist are skipped.
+    pub fn build<G: GraphRef>(graph: &G) -> Self {
+        let nodes: Vec<NodeID> = graph.nodes().collect();
+        let index: HashMap<NodeID, usize> =
+            nodes.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+
+        let (mut successors, mut predecessors) =
+            (vec![Vec::new(); nodes.len()], vec![Vec::new(); nodes.len()]);
+        for (i, id) in nodes.iter().enumerate() {
+            for edge in outgoing_edges(graph, *id) {
+                if let Some(to) = index.get(&edge.to) {
+                    successors[i].push(*to);
+                    predecessors[*to].push(i);
+                }
+            }
+        }
+
+        // Landmarks that touch many paths first.
+        let mut order: Vec<usize> = (0..nodes.len()).collect();
+        order.sort_by_key(|i| {
+            std::cmp::Reverse((successors[*i].len() + 1) * (predecessors[*i].len() + 1))
+        });
+
+        let mut labels = ReachabilityIndex {
+            index,
+            out_labels: vec![Vec::new(); nodes.len()],
+            in_labels: vec![Vec::new(); nodes.len()],
+        };
+        // The search (landmark and direction) that last visited every node, to avoid clearing a visited set per search.
+        let mut visited = vec![usize::MAX; nodes.len()];
+        let mut queue = VecDeque::new();
+        for (rank, landmark) in order.into_iter().enumerate() {
+            let rank = rank as u32;
+            labels.out_labels[landmark].push(rank);
+            labels.in_labels[landmark].push(rank);
+
+            for forward in [true, false] {
+                let neighbors = if forward { &successors } else { &predecessors };
+                visited[landmark] = 2 * landmark + forward as usize;
+                queue.push_back(landmark);
+                while let Some(i) = queue.pop_front() {
+                    if i != landmark {
+                        // Already covered by an earlier landmark, and so is everything behind it.
+                        let (from, to) = if forward {
+                            (landmark, i)
+                        } else {
+                            (i, landmark)
+                        };
+                        if labels.reaches(from, to) {
+                            continue;
+                        }
+                        if forward {
+                            labels.in_labels[i].push(rank);
+                        } else {
+                            labels.out_labels[i].push(rank);
+                        }
+                    }
+                    for next in &neighbors[i] {
+                        if visited[*next] != 2 * landmark + forward as usize {
+                            visited[*next] = 2 * landmark + forward as usize;
+                            queue.push_back(*next);
+                        }
+                    }
+                }
+            }
+        }
+        labels
+    }
+
+    /// Returns true if there is a path from `from` to `to` (every node reaches itself).
+    ///
+    /// Nodes that weren't in the graph when the index was built reach nothing.
+    pub fn can_reach(&self, from: NodeID, to: NodeID) -> bool {
+        match (self.index.get(&from), self.index.get(&to)) {
+            (Some(from), Some(to)) => self.reaches(*from, *to),
+            _ => false,
+        }
+    }
+
+    /// The total number of labels, a measure of the size of the index (at least two per node).
+    pub fn label_count(&self) -> usize {
+        self.out_labels
+            .iter()
+            .chain(&self.in_labels)
+            .map(Vec::len)
+            .sum()
+    }
+
+    fn reaches(&self, from: usize, to: usize) -> bool {
+        let (mut a, mut b) = (
+            self.out_labels[from].iter().peekable(),
+            self.in_labels[to].iter().peekable(),
+        );
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            match x.cmp(y) {
+                std::cmp::Ordering::Less => {
+                    a.next();
+                }
+                std::cmp::Ordering::Greater => {
+                    b.next();
+                }
+                std::cmp::Ordering::Equal => return true,
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::bfs_distances;
+    use crate::{Graph, GraphInterface};
+
+    #[test]
+    fn test_reachability_index_matches_bfs() {
+        let mut graph: Graph<(), ()> = Graph::new();
+        let nodes = graph.add_nodes(&[(); 12]);
+        // A layered DAG with a cycle (7 -> 8 -> 9 -> 7) and a few isolated parts.
+        graph.add_edges(&[
+            (nodes[0], nodes[1]),
+            (nodes[0], nodes[2]),
+            (nodes[1], nodes[3]),
+            (nodes[2], nodes[3]),
+            (nodes[3], nodes[4]),
+            (nodes[4], nodes[5]),
+            (nodes[2], nodes[6]),
+            (nodes[6], nodes[7]),
+            (nodes[7], nodes[8]),
+            (nodes[8], nodes[9]),
+            (nodes[9], nodes[7]),
+            (nodes[9], nodes[5]),
+            (nodes[10], nodes[10]),
+        ]);
+        let removed = graph.add_edge(nodes[5], nodes[0], ());
+        graph.remove_edge(removed).unwrap();
+
+        let index = ReachabilityIndex::build(&graph);
+        for from in &nodes {
+            let reachable = bfs_distances(&graph, *from);
+            for to in &nodes {
+                assert_eq!(
+                    index.can_reach(*from, *to),
+                    reachable.contains_key(to),
+                    "{:?} -> {:?}",
+                    from,
+                    to
+                );
+            }
+        }
+        // Pruning keeps the labels well below one per reachable pair.
+        assert!(index.label_count() < 2 * nodes.len() + 30);
+
+        let added = graph.add_node(());
+        assert!(!index.can_reach(added, added));
+    }
+}
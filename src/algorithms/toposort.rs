@@ -0,0 +1,198 @@
+//! # Topological sorting with deterministic tie-breaking.
+//!
+//! Kahn's algorithm repeatedly takes a node without remaining incoming edges. Usually several nodes are ready at the same time,
+//! and which one is taken first decides the order. Here the ready nodes are kept in a min-heap, so the order only depends on
+//! the graph and the tie-breaker, not on slot order:
+//!
+//! - [toposort_by_key] takes the ready node with the smallest key, e.g. a priority (lexicographic toposort with the node data as key).
+//! - [toposort_by] takes the ready node that compares smallest with a comparator.
+//! - [toposort] breaks ties by [NodeID].
+//!
+//! Edges are followed from `edge.from` to `edge.to`, i.e. every node comes after the nodes with edges into it.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::algorithms::toposort_by_key;
+//!
+//! let mut graph: Graph<&str, ()> = Graph::new();
+//! let [fetch, compile, lint, link] = graph.add_nodes(&["fetch", "compile", "lint", "link"])[..] else { unreachable!() };
+//! graph.add_edges(&[(fetch, compile), (fetch, lint), (compile, link)]);
+//!
+//! // `compile` and `lint` are both ready after `fetch`, the name decides.
+//! let order = toposort_by_key(&graph, |_, name| *name).unwrap();
+//! assert_eq!(order, vec![fetch, compile, link, lint]);
+//! ```
+
+use std::cmp::Ordering;
+
+use thiserror::Error;
+
+use crate::hash::HashMap;
+use crate::{outgoing_edges, GraphRef, NodeID};
+
+/// Returned when the graph has a cycle, so there is no topological order.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+#[error("Graph has a cycle, {} node(s) couldn't be ordered", .remaining.len())]
+pub struct CycleError {
+    /// The nodes that are on a cycle or only reachable through one, in slot order.
+    pub remaining: Vec<NodeID>,
+}
+
+/// Returns the nodes in topological order, taking the ready node with the smallest [NodeID] first, see the [module documentation](self).
+pub fn toposort<G: GraphRef>(graph: &G) -> Result<Vec<NodeID>, CycleError> {
+    toposort_by(graph, |a, b| a.cmp(&b))
+}
+
+/// Returns the nodes in topological order, taking the ready node with the smallest key first (ties by [NodeID]).
+pub fn toposort_by_key<G, K, F>(graph: &G, mut key: F) -> Result<Vec<NodeID>, CycleError>
+where
+    G: GraphRef,
+    K: Ord,
+    F: FnMut(NodeID, &G::NodeData) -> K,
+{
+    let mut keys = HashMap::with_capacity_and_hasher(graph.node_count(), Default::default());
+    for id in graph.nodes() {
+        if let Ok(node) = graph.node(id) {
+            keys.insert(id, key(id, &node.data));
+        }
+    }
+    toposort_by(graph, |a, b| keys[&a].cmp(&keys[&b]).then(a.cmp(&b)))
+}
+
+/// Returns the nodes in topological order, taking the ready node that is smallest according to `compare` first.
+pub fn toposort_by<G, F>(graph: &G, mut compare: F) -> Result<Vec<NodeID>, CycleError>
+where
+    G: GraphRef,
+    F: FnMut(NodeID, NodeID) -> Ordering,
+{
+    // The number of incoming edges of every node, skipping stale connections and dangling edges.
+    let mut in_degree: HashMap<NodeID, usize> =
+        HashMap::with_capacity_and_hasher(graph.node_count(), Default::default());
+    for id in graph.nodes() {
+        in_degree.entry(id).or_default();
+        for target in targets(graph, id) {
+            *in_degree.entry(target).or_default() += 1;
+        }
+    }
+
+    let mut ready = MinHeap::default();
+    for id in graph.nodes() {
+        if in_degree[&id] == 0 {
+            ready.push(id, &mut compare);
+        }
+    }
+
+    let mut order = Vec::with_capacity(in_degree.len());
+    while let Some(id) = ready.pop(&mut compare) {
+        order.push(id);
+        for target in targets(graph, id) {
+            let degree = in_degree.get_mut(&target).expect("targets exist");
+            *degree -= 1;
+            if *degree == 0 {
+                ready.push(target, &mut compare);
+            }
+        }
+    }
+
+    if order.len() == in_degree.len() {
+        Ok(order)
+    } else {
+        let remaining = graph.nodes().filter(|id| in_degree[id] > 0).collect();
+        Err(CycleError { remaining })
+    }
+}
+
+/// The targets of the outgoing edges of a node that exist, once per edge.
+fn targets<G: GraphRef>(graph: &G, id: NodeID) -> Vec<NodeID> {
+    outgoing_edges(graph, id)
+        .map(|edge| edge.to)
+        .filter(|to| graph.node(*to).is_ok())
+        .collect()
+}
+
+/// A binary min-heap ordered by a comparator that is passed to every operation ([std::collections::BinaryHeap] needs [Ord]).
+#[derive(Default)]
+struct MinHeap {
+    items: Vec<NodeID>,
+}
+
+impl MinHeap {
+    fn push(&mut self, id: NodeID, compare: &mut impl FnMut(NodeID, NodeID) -> Ordering) {
+        self.items.push(id);
+        let mut i = self.items.len() - 1;
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if compare(self.items[i], self.items[parent]) != Ordering::Less {
+                break;
+            }
+            self.items.swap(i, parent);
+            i = parent;
+        }
+    }
+
+    fn pop(&mut self, compare: &mut impl FnMut(NodeID, NodeID) -> Ordering) -> Option<NodeID> {
+        if self.items.is_empty() {
+            return None;
+        }
+        let top = self.items.swap_remove(0);
+        let mut i = 0;
+        loop {
+            let mut smallest = i;
+            for child in [2 * i + 1, 2 * i + 2] {
+                if child < self.items.len()
+                    && compare(self.items[child], self.items[smallest]) == Ordering::Less
+                {
+                    smallest = child;
+                }
+            }
+            if smallest == i {
+                return Some(top);
+            }
+            self.items.swap(i, smallest);
+            i = smallest;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Graph, GraphInterface};
+
+    #[test]
+    fn test_toposort_tie_breaking_and_cycles() {
+        let mut graph: Graph<u32, ()> = Graph::new();
+        let nodes = graph.add_nodes(&[5, 3, 4, 1, 2]);
+        graph.add_edges(&[
+            (nodes[0], nodes[3]),
+            (nodes[1], nodes[3]),
+            (nodes[2], nodes[4]),
+        ]);
+        let data = |order: Vec<NodeID>| -> Vec<u32> {
+            order
+                .iter()
+                .map(|id| graph.node(*id).unwrap().data)
+                .collect()
+        };
+
+        assert_eq!(data(toposort(&graph).unwrap()), vec![5, 3, 4, 1, 2]);
+        assert_eq!(
+            data(toposort_by_key(&graph, |_, data| *data).unwrap()),
+            vec![3, 4, 2, 5, 1]
+        );
+        // Highest priority first.
+        let order = toposort_by(&graph, |a, b| {
+            let (a, b) = (graph.node(a).unwrap().data, graph.node(b).unwrap().data);
+            b.cmp(&a)
+        });
+        assert_eq!(data(order.unwrap()), vec![5, 4, 3, 2, 1]);
+
+        let back = graph.add_edge(nodes[4], nodes[2], ());
+        let error = toposort(&graph).unwrap_err();
+        assert_eq!(error.remaining, vec![nodes[2], nodes[4]]);
+        graph.remove_edge(back).unwrap();
+        graph.add_edge(nodes[3], nodes[3], ());
+        assert_eq!(toposort(&graph).unwrap_err().remaining, vec![nodes[3]]);
+    }
+}
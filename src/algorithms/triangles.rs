@@ -0,0 +1,209 @@
+//! # Triangle counting.
+//!
+//! [triangle_count] counts the triangles of a graph, ignoring edge directions, self-loops and parallel edges (so a triangle is
+//! three nodes that are pairwise connected). It is selected with a [TriangleMethod]:
+//!
+//! - [TriangleMethod::Exact] builds the neighbor set of every node and intersects them per edge, which needs memory for the whole
+//!   adjacency.
+//! - `TriangleMethod::Approximate` (requires the `rand` feature) streams the edges once and only keeps a reservoir of sampled edges
+//!   ([TRIÈST-IMPR](https://doi.org/10.1145/3059194)): every incoming edge adds the triangles it closes within the sample, weighted
+//!   by the probability that the sample still holds both other edges. The estimate is unbiased and its variance shrinks as the
+//!   reservoir grows; with a reservoir at least as large as the number of edges it is exact.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::algorithms::{triangle_count, TriangleMethod};
+//!
+//! let mut graph: Graph<(), ()> = Graph::new();
+//! let nodes = graph.add_nodes(&[(); 4]);
+//! // Two triangles sharing the edge 0-2, plus a parallel edge.
+//! graph.add_edges(&[
+//!     (nodes[0], nodes[1]),
+//!     (nodes[1], nodes[2]),
+//!     (nodes[2], nodes[0]),
+//!     (nodes[2], nodes[3]),
+//!     (nodes[3], nodes[0]),
+//!     (nodes[1], nodes[0]),
+//! ]);
+//!
+//! assert_eq!(triangle_count(&graph, TriangleMethod::Exact), 2.0);
+//! ```
+
+use crate::hash::{HashMap, HashSet};
+use crate::{GraphRef, NodeID};
+
+/// How [triangle_count] counts, see the [module documentation](self).
+///
+/// Non-exhaustive because the variants depend on the enabled features: enabling `rand` mustn't break a `match` elsewhere.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TriangleMethod {
+    /// Counts every triangle, using memory proportional to the number of edges.
+    Exact,
+    /// Estimates the count from a reservoir of at most `reservoir` edges (at least 2), sampled with a generator seeded with `seed`.
+    #[cfg(feature = "rand")]
+    Approximate { reservoir: usize, seed: u64 },
+}
+
+/// Returns the number of triangles in the graph, ignoring edge directions, self-loops and parallel edges.
+///
+/// The result of [TriangleMethod::Exact] is a whole number. Connections to removed edges and edges to nodes that don't exist are skipped.
+pub fn triangle_count<G: GraphRef>(graph: &G, method: TriangleMethod) -> f64 {
+    match method {
+        TriangleMethod::Exact => exact(graph) as f64,
+        #[cfg(feature = "rand")]
+        TriangleMethod::Approximate { reservoir, seed } => approximate(graph, reservoir, seed),
+    }
+}
+
+/// Calls `f` once for every pair of connected nodes (the smaller [NodeID] first), keeping only the neighbors of one node in memory.
+fn for_each_undirected_edge<G: GraphRef>(graph: &G, mut f: impl FnMut(NodeID, NodeID)) {
+    let mut neighbors: Vec<NodeID> = Vec::new();
+    for id in graph.nodes() {
+        let Ok(node) = graph.node(id) else {
+            continue;
+        };
+        neighbors.clear();
+        neighbors.extend(
+            node.connections
+                .iter()
+                .filter_map(|edge| graph.edge(*edge).ok())
+                .map(|edge| if edge.from == id { edge.to } else { edge.from })
+                .filter(|other| *other > id && graph.node(*other).is_ok()),
+        );
+        neighbors.sort();
+        neighbors.dedup();
+        for other in &neighbors {
+            f(id, *other);
+        }
+    }
+}
+
+fn exact<G: GraphRef>(graph: &G) -> u64 {
+    let mut adjacency: HashMap<NodeID, HashSet<NodeID>> = HashMap::default();
+    for_each_undirected_edge(graph, |a, b| {
+        adjacency.entry(a).or_default().insert(b);
+        adjacency.entry(b).or_default().insert(a);
+    });
+
+    // Every triangle a < b < c is counted once, at its edge (a, b).
+    let mut count = 0;
+    for (a, neighbors) in &adjacency {
+        for b in neighbors.iter().filter(|b| *b > a) {
+            let (small, large) = if neighbors.len() <= adjacency[b].len() {
+                (neighbors, &adjacency[b])
+            } else {
+                (&adjacency[b], neighbors)
+            };
+            count += small
+                .iter()
+                .filter(|c| *c > b && large.contains(*c))
+                .count() as u64;
+        }
+    }
+    count
+}
+
+#[cfg(feature = "rand")]
+fn approximate<G: GraphRef>(graph: &G, reservoir: usize, seed: u64) -> f64 {
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+    let reservoir = reservoir.max(2);
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut sample: Vec<(NodeID, NodeID)> = Vec::with_capacity(reservoir);
+    let mut adjacency: HashMap<NodeID, HashSet<NodeID>> = HashMap::default();
+    let mut estimate = 0.0;
+    let mut seen: u64 = 0;
+
+    for_each_undirected_edge(graph, |a, b| {
+        seen += 1;
+        // The inverse of the probability that two earlier edges are both in the sample.
+        let (t, m) = (seen as f64, reservoir as f64);
+        let weight = ((t - 1.0) * (t - 2.0) / (m * (m - 1.0))).max(1.0);
+        if let (Some(left), Some(right)) = (adjacency.get(&a), adjacency.get(&b)) {
+            let (small, large) = if left.len() <= right.len() {
+                (left, right)
+            } else {
+                (right, left)
+            };
+            estimate += weight * small.iter().filter(|c| large.contains(*c)).count() as f64;
+        }
+
+        if sample.len() < reservoir {
+            sample.push((a, b));
+        } else {
+            let i = rng.gen_range(0..seen) as usize;
+            if i >= reservoir {
+                return;
+            }
+            let (x, y) = std::mem::replace(&mut sample[i], (a, b));
+            for (from, to) in [(x, y), (y, x)] {
+                if let Some(neighbors) = adjacency.get_mut(&from) {
+                    neighbors.remove(&to);
+                    if neighbors.is_empty() {
+                        adjacency.remove(&from);
+                    }
+                }
+            }
+        }
+        adjacency.entry(a).or_default().insert(b);
+        adjacency.entry(b).or_default().insert(a);
+    });
+    estimate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Graph, GraphInterface};
+
+    #[test]
+    fn test_triangle_count() {
+        // A complete graph on 12 nodes has 220 triangles and 66 edges.
+        let mut graph: Graph<(), ()> = Graph::new();
+        let nodes = graph.add_nodes(&[(); 13]);
+        for (i, a) in nodes[..12].iter().enumerate() {
+            for b in &nodes[i + 1..12] {
+                // Mixed directions, parallel edges and self-loops don't change the count.
+                if i % 2 == 0 {
+                    graph.add_edge(*a, *b, ());
+                } else {
+                    graph.add_edge(*b, *a, ());
+                    graph.add_edge(*a, *b, ());
+                }
+            }
+            graph.add_edge(*a, *a, ());
+        }
+        let removed = graph.add_edge(nodes[12], nodes[0], ());
+        graph.add_edge(nodes[12], nodes[1], ());
+        graph.remove_edge(removed).unwrap();
+        assert_eq!(triangle_count(&graph, TriangleMethod::Exact), 220.0);
+
+        #[cfg(feature = "rand")]
+        {
+            // A reservoir holding every edge is exact.
+            let method = TriangleMethod::Approximate {
+                reservoir: 100,
+                seed: 1,
+            };
+            assert_eq!(triangle_count(&graph, method), 220.0);
+
+            // Smaller reservoirs are unbiased: the mean over many seeds is close.
+            let runs = 200;
+            let mean = (0..runs)
+                .map(|seed| {
+                    triangle_count(
+                        &graph,
+                        TriangleMethod::Approximate {
+                            reservoir: 30,
+                            seed,
+                        },
+                    )
+                })
+                .sum::<f64>()
+                / runs as f64;
+            assert!((mean - 220.0).abs() < 22.0, "{}", mean);
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! # Reusable visited-sets for traversals.
+//!
+//! A [VisitMap] is a bitset keyed by the slot index of a [NodeID]. Marking and checking a node is a single bit operation (no hashing),
+//! and [VisitMap::reset] clears it without giving up its allocation, so one map can be reused across thousands of traversals.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::algorithms::{DepthFirstSearch, Visitable};
+//!
+//! let mut graph: Graph<u32, ()> = Graph::new();
+//! let nodes = graph.add_nodes(&[1, 2, 3]);
+//! graph.add_edges(&[(nodes[0], nodes[1]), (nodes[1], nodes[2])]);
+//!
+//! let mut map = graph.visit_map();
+//! for start in &nodes {
+//!     graph.reset_map(&mut map);
+//!     let mut search = DepthFirstSearch::new(&graph, *start).with_visit_map(map);
+//!     assert!(search.by_ref().count() >= 1);
+//!     map = search.into_visit_map();
+//! }
+//! ```
+
+use crate::{GraphRef, NodeID};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A set of visited nodes stored as a bitset keyed by slot index, see the [module documentation](self).
+///
+/// Only the slot index of a [NodeID] is used, so a map should only be used with the nodes of one graph at a time,
+/// and not across removals of nodes. [NodeSet](super::NodeSet) also checks the version.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VisitMap {
+    words: Vec<u64>,
+}
+
+impl VisitMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a map that can hold nodes with a slot index below `slots` without reallocating.
+    pub fn with_capacity(slots: usize) -> Self {
+        VisitMap {
+            words: vec![0; slots.div_ceil(WORD_BITS)],
+        }
+    }
+
+    fn slot(node: NodeID) -> usize {
+        // The lower 32 bits of the FFI representation are the slot index.
+        node.to_u64() as u32 as usize
+    }
+
+    /// Marks a node as visited. Returns true if it hadn't been visited before.
+    pub fn visit(&mut self, node: NodeID) -> bool {
+        let slot = Self::slot(node);
+        if self.words.len() <= slot / WORD_BITS {
+            self.words.resize(slot / WORD_BITS + 1, 0);
+        }
+        let word = &mut self.words[slot / WORD_BITS];
+        let bit = 1 << (slot % WORD_BITS);
+        let unvisited = *word & bit == 0;
+        *word |= bit;
+        unvisited
+    }
+
+    /// Returns true if the node has been visited.
+    pub fn is_visited(&self, node: NodeID) -> bool {
+        let slot = Self::slot(node);
+        self.words
+            .get(slot / WORD_BITS)
+            .is_some_and(|word| word & (1 << (slot % WORD_BITS)) != 0)
+    }
+
+    /// Marks every node as unvisited, keeping the allocation.
+    pub fn reset(&mut self) {
+        self.words.fill(0);
+    }
+}
+
+/// Graphs that can create and reset [VisitMap]s sized for their nodes, like petgraph's `Visitable`.
+pub trait Visitable {
+    /// Returns a new, empty [VisitMap] for this graph.
+    fn visit_map(&self) -> VisitMap;
+
+    /// Clears a [VisitMap] so it can be used for another traversal of this graph.
+    fn reset_map(&self, map: &mut VisitMap);
+}
+
+impl<G: GraphRef> Visitable for G {
+    fn visit_map(&self) -> VisitMap {
+        VisitMap::with_capacity(self.node_count())
+    }
+
+    fn reset_map(&self, map: &mut VisitMap) {
+        map.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Graph, GraphInterface};
+
+    #[test]
+    fn test_visit_and_reset() {
+        let mut graph: Graph<(), ()> = Graph::new();
+        let nodes = graph.add_nodes(&[(); 200]);
+
+        let mut map = VisitMap::new();
+        assert!(map.visit(nodes[150]));
+        assert!(!map.visit(nodes[150]));
+        assert!(map.is_visited(nodes[150]));
+        assert!(!map.is_visited(nodes[0]));
+
+        graph.reset_map(&mut map);
+        assert!(!map.is_visited(nodes[150]));
+        assert!(map.visit(nodes[150]));
+    }
+}
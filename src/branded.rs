@@ -0,0 +1,211 @@
+//! # Graphs whose IDs can't be used on other graphs.
+//!
+//! A [NodeID] is only a slot index and a version, so an ID from one graph can be passed to another graph, where it silently resolves to
+//! an unrelated node if that slot happens to be occupied. [BrandedGraph] gives every graph a unique brand and hands out [Branded] IDs that
+//! carry it, so using an ID on the wrong graph returns [GraphError::ForeignId] instead.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//!
+//! let mut a: BrandedGraph<&str, ()> = BrandedGraph::new();
+//! let mut b: BrandedGraph<&str, ()> = BrandedGraph::new();
+//! let node_a = a.add_node("in a");
+//! let node_b = b.add_node("in b");
+//!
+//! assert_eq!(a.node(node_a).unwrap().data, "in a");
+//! // The raw IDs are equal (same slot in both graphs), but the brands aren't.
+//! assert_eq!(node_a.id(), node_b.id());
+//! assert!(matches!(a.node(node_b), Err(GraphError::ForeignId { .. })));
+//! ```
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::*;
+
+static NEXT_BRAND: AtomicU64 = AtomicU64::new(1);
+
+/// An ID together with the brand of the [BrandedGraph] it belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Branded<K> {
+    id: K,
+    brand: u64,
+}
+
+impl<K: Copy> Branded<K> {
+    /// Returns the plain ID, e.g. to use it with algorithms that run on [BrandedGraph::graph].
+    pub fn id(&self) -> K {
+        self.id
+    }
+
+    pub fn brand(&self) -> u64 {
+        self.brand
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                BrandedGraph                                */
+/* -------------------------------------------------------------------------- */
+
+/// A [Graph] that checks that the IDs passed to it were created by it, see the [module documentation](self).
+pub struct BrandedGraph<N, E> {
+    graph: Graph<N, E>,
+    brand: u64,
+}
+
+impl<N, E> BrandedGraph<N, E> {
+    /// Creates an empty graph with a brand that no other graph in this process has.
+    pub fn new() -> Self {
+        Self::from_graph(Graph::new())
+    }
+
+    /// Brands an existing graph. IDs obtained from `graph` before have to be re-branded with [BrandedGraph::brand_node]/[BrandedGraph::brand_edge].
+    pub fn from_graph(graph: Graph<N, E>) -> Self {
+        BrandedGraph {
+            graph,
+            brand: NEXT_BRAND.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    pub fn brand(&self) -> u64 {
+        self.brand
+    }
+
+    /// Returns the underlying graph, e.g. to run algorithms on it. The IDs it returns are plain, unbranded IDs.
+    pub fn graph(&self) -> &Graph<N, E> {
+        &self.graph
+    }
+
+    pub fn into_graph(self) -> Graph<N, E> {
+        self.graph
+    }
+
+    /// Brands a plain node ID of this graph. Returns `None` if the node doesn't exist.
+    pub fn brand_node(&self, id: NodeID) -> Option<Branded<NodeID>> {
+        self.graph.contains_node(id).then_some(self.wrap(id))
+    }
+
+    /// Brands a plain edge ID of this graph. Returns `None` if the edge doesn't exist.
+    pub fn brand_edge(&self, id: EdgeID) -> Option<Branded<EdgeID>> {
+        self.graph.contains_edge(id).then_some(self.wrap(id))
+    }
+
+    fn wrap<K>(&self, id: K) -> Branded<K> {
+        Branded {
+            id,
+            brand: self.brand,
+        }
+    }
+
+    /// Returns the plain ID if `id` belongs to this graph, otherwise [GraphError::ForeignId].
+    pub fn check<K: Copy>(&self, id: Branded<K>) -> Result<K, GraphError> {
+        if id.brand == self.brand {
+            Ok(id.id)
+        } else {
+            Err(GraphError::ForeignId {
+                expected: self.brand,
+                found: id.brand,
+            })
+        }
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.graph.node_count()
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = Branded<NodeID>> + '_ {
+        self.graph.nodes().map(|id| self.wrap(id))
+    }
+
+    pub fn node(&self, id: Branded<NodeID>) -> Result<&Node<N>, GraphError> {
+        self.graph.node(self.check(id)?)
+    }
+
+    pub fn node_mut(&mut self, id: Branded<NodeID>) -> Result<&mut Node<N>, GraphError> {
+        let id = self.check(id)?;
+        self.graph.node_mut(id)
+    }
+
+    pub fn edge(&self, id: Branded<EdgeID>) -> Result<&Edge<E>, GraphError> {
+        self.graph.edge(self.check(id)?)
+    }
+
+    pub fn edge_mut(&mut self, id: Branded<EdgeID>) -> Result<&mut Edge<E>, GraphError> {
+        let id = self.check(id)?;
+        self.graph.edge_mut(id)
+    }
+
+    pub fn add_node(&mut self, data: N) -> Branded<NodeID> {
+        let id = self.graph.add_node(data);
+        self.wrap(id)
+    }
+
+    /// Adds an edge. Fails if either endpoint is from another graph or doesn't exist (see [GraphInterface::try_add_edge]).
+    pub fn add_edge(
+        &mut self,
+        from: Branded<NodeID>,
+        to: Branded<NodeID>,
+        data: E,
+    ) -> Result<Branded<EdgeID>, GraphError> {
+        let (from, to) = (self.check(from)?, self.check(to)?);
+        let id = self.graph.try_add_edge(from, to, data)?;
+        Ok(self.wrap(id))
+    }
+
+    pub fn remove_node(&mut self, id: Branded<NodeID>) -> Result<(), GraphError> {
+        let id = self.check(id)?;
+        self.graph.remove_node(id)
+    }
+
+    pub fn remove_edge(&mut self, id: Branded<EdgeID>) -> Result<(), GraphError> {
+        let id = self.check(id)?;
+        self.graph.remove_edge(id)
+    }
+}
+
+impl<N, E> Default for BrandedGraph<N, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: fmt::Debug + Clone, E: fmt::Debug + Clone> fmt::Debug for BrandedGraph<N, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "BrandedGraph {{ brand: {}, graph: {:#?} }}",
+            self.brand, self.graph
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_foreign_ids_are_rejected() {
+        let mut a: BrandedGraph<u32, ()> = BrandedGraph::new();
+        let mut b: BrandedGraph<u32, ()> = BrandedGraph::new();
+        assert_ne!(a.brand(), b.brand());
+
+        let [a0, a1] = [a.add_node(0), a.add_node(1)];
+        let b0 = b.add_node(10);
+        let edge = a.add_edge(a0, a1, ()).unwrap();
+
+        assert!(matches!(
+            b.node(a0),
+            Err(GraphError::ForeignId { expected, found }) if expected == b.brand() && found == a.brand()
+        ));
+        assert!(a.add_edge(a0, b0, ()).is_err());
+        assert!(b.remove_edge(edge).is_err());
+        assert_eq!(a.graph().edges.len(), 1);
+
+        // Plain IDs from algorithms can be branded again.
+        let plain = a.graph().nodes().next().unwrap();
+        assert_eq!(a.brand_node(plain), Some(a0));
+        a.remove_node(a0).unwrap();
+        assert_eq!(a.brand_node(a0.id()), None);
+        assert_eq!(a.nodes().collect::<Vec<_>>(), vec![a1]);
+    }
+}
@@ -0,0 +1,400 @@
+//! # Building graphs from external identifiers.
+//!
+//! [GraphBuilder] maps external node keys (e.g. the `u64` IDs in a SNAP edge list) to [NodeID]s while the graph is being built.
+//!
+//! [GraphBuilder::add_edge_streamed] inserts every edge immediately, so an edge stream of any size can be ingested without buffering it:
+//! only the resulting graph and the key → [NodeID] map are kept in memory.
+//! Storage is grown in fixed-size chunks (see [GraphBuilder::with_chunk_size]) instead of relying on the slotmaps' growth on every insertion.
+//!
+//! [GraphBuilder::add_node] and [GraphBuilder::add_edge] instead defer everything to [GraphBuilder::build], so nodes and edges can be declared
+//! in any order (e.g. from a config file where edges come before the nodes they reference). `build` pre-sizes the slotmaps, resolves the keys
+//! and reports every duplicate node key and every edge referencing an undeclared key at once, see [BuildError].
+//!
+//! # Examples
+//! ```
+//! use fast_graph::*;
+//!
+//! let mut builder: GraphBuilder<(), ()> = GraphBuilder::new();
+//! for (from, to) in [(10, 20), (20, 30), (30, 10), (10, 20)] {
+//!     builder.add_edge_streamed(from, to);
+//! }
+//!
+//! let (graph, ids) = builder.build().unwrap();
+//! assert_eq!(graph.node_count(), 3);
+//! assert_eq!(graph.edges.len(), 4);
+//! assert_eq!(graph.node(ids[&10]).unwrap().connections.len(), 3);
+//! ```
+//!
+//! ```
+//! use fast_graph::*;
+//!
+//! let mut builder: GraphBuilder<&str, u32, &str> = GraphBuilder::new();
+//! builder.add_edge("db", "cache", 1);
+//! builder.add_edge("api", "db", 2);
+//! builder.add_node("api", "API server");
+//! builder.add_node("db", "Database");
+//!
+//! let error = builder.build().unwrap_err();
+//! assert_eq!(error.unresolved_edges[0].missing, vec!["cache"]);
+//! ```
+
+use std::fmt::{self, Debug};
+use std::hash::Hash;
+
+use slotmap::SecondaryMap;
+
+use crate::hash::HashMap;
+
+use crate::{EdgeID, Graph, GraphInterface, NodeID};
+
+/// The default number of nodes/edges storage grows by when it runs full.
+pub const DEFAULT_CHUNK_SIZE: usize = 1 << 16;
+
+/// Builds a [Graph] whose nodes are identified by external keys of type `K`.
+pub struct GraphBuilder<N, E, K = u64> {
+    graph: Graph<N, E>,
+    ids: HashMap<K, NodeID>,
+    chunk_size: usize,
+    deferred_nodes: Vec<(K, N)>,
+    deferred_edges: Vec<(K, K, E)>,
+}
+
+/// A built graph and its key → [NodeID] mapping, returned by [GraphBuilder::build].
+pub type Built<N, E, K> = (Graph<N, E>, HashMap<K, NodeID>);
+
+/// An edge added with [GraphBuilder::add_edge] whose endpoints couldn't all be resolved, see [BuildError].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnresolvedEdge<K> {
+    /// The position of the edge among the deferred edges, in the order they were added.
+    pub index: usize,
+    pub from: K,
+    pub to: K,
+    /// The endpoint keys that were never declared (one or both).
+    pub missing: Vec<K>,
+}
+
+/// Returned by [GraphBuilder::build] when deferred nodes or edges can't be resolved. Lists every problem, not just the first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BuildError<K> {
+    /// Keys declared with [GraphBuilder::add_node] more than once, or that were already created by a streamed edge.
+    pub duplicate_nodes: Vec<K>,
+    pub unresolved_edges: Vec<UnresolvedEdge<K>>,
+}
+
+// Written out instead of derived, so that only formatting the error needs `K: Debug`, not building.
+impl<K: Debug> fmt::Display for BuildError<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Graph could not be built: {} duplicate node key(s) {:?}, {} edge(s) with undeclared endpoints",
+            self.duplicate_nodes.len(),
+            self.duplicate_nodes,
+            self.unresolved_edges.len()
+        )
+    }
+}
+
+impl<K: Debug> std::error::Error for BuildError<K> {}
+
+impl<N, E, K: Hash + Eq> GraphBuilder<N, E, K> {
+    pub fn new() -> Self {
+        Self::with_chunk_size(DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Creates a builder that grows its storage by `chunk_size` nodes/edges at a time.
+    pub fn with_chunk_size(chunk_size: usize) -> Self {
+        GraphBuilder {
+            graph: Graph::new(),
+            ids: HashMap::default(),
+            chunk_size: chunk_size.max(1),
+            deferred_nodes: Vec::new(),
+            deferred_edges: Vec::new(),
+        }
+    }
+
+    /// Returns the [NodeID] of a key that has been seen so far.
+    pub fn node_id(&self, key: &K) -> Option<NodeID> {
+        self.ids.get(key).copied()
+    }
+
+    /// Returns the number of distinct keys seen so far, not counting deferred nodes.
+    pub fn node_count(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Returns the graph built so far.
+    pub fn graph(&self) -> &Graph<N, E> {
+        &self.graph
+    }
+
+    /// Adds an edge between two external keys with default edge data, creating nodes (with default data) for unseen keys.
+    pub fn add_edge_streamed(&mut self, from: K, to: K) -> EdgeID
+    where
+        N: Default,
+        E: Default,
+    {
+        self.add_edge_streamed_with(from, to, E::default())
+    }
+
+    /// Adds an edge between two external keys with the given edge data, creating nodes (with default data) for unseen keys.
+    pub fn add_edge_streamed_with(&mut self, from: K, to: K, data: E) -> EdgeID
+    where
+        N: Default,
+    {
+        let from = self.node_or_insert_with(from, N::default);
+        let to = self.node_or_insert_with(to, N::default);
+        if self.graph.edges.len() == self.graph.edges.capacity() {
+            self.graph.edges.reserve(self.chunk_size);
+        }
+        self.graph.add_edge(from, to, data)
+    }
+
+    /// Adds every edge of the stream, see [GraphBuilder::add_edge_streamed].
+    pub fn extend_streamed(&mut self, edges: impl IntoIterator<Item = (K, K)>)
+    where
+        N: Default,
+        E: Default,
+    {
+        for (from, to) in edges {
+            self.add_edge_streamed(from, to);
+        }
+    }
+
+    /// Returns the node for `key`, creating it with the data returned by `data` if the key hasn't been seen yet.
+    pub fn node_or_insert_with(&mut self, key: K, data: impl FnOnce() -> N) -> NodeID {
+        if let Some(id) = self.ids.get(&key) {
+            return *id;
+        }
+        if self.graph.nodes.len() == self.graph.nodes.capacity() {
+            self.graph.nodes.reserve(self.chunk_size);
+            self.ids.reserve(self.chunk_size);
+        }
+        let id = self.graph.add_node(data());
+        self.ids.insert(key, id);
+        id
+    }
+
+    /// Declares a node, which is only created by [GraphBuilder::build].
+    pub fn add_node(&mut self, key: K, data: N) {
+        self.deferred_nodes.push((key, data));
+    }
+
+    /// Declares an edge between two keys, which is only resolved and created by [GraphBuilder::build].
+    /// The keys can be declared with [GraphBuilder::add_node] before or after this, or be created by streamed edges.
+    pub fn add_edge(&mut self, from: K, to: K, data: E) {
+        self.deferred_edges.push((from, to, data));
+    }
+
+    /// Finishes building and returns the graph together with the key → [NodeID] mapping.
+    ///
+    /// Creates the deferred nodes and edges first (reserving room for all of them up front).
+    /// Fails if a node key was declared twice or a deferred edge references a key that was never declared, see [BuildError].
+    pub fn build(mut self) -> Result<Built<N, E, K>, BuildError<K>>
+    where
+        K: Clone,
+    {
+        let mut duplicate_nodes = Vec::new();
+        let mut unresolved_edges = Vec::new();
+
+        self.graph.nodes.reserve(self.deferred_nodes.len());
+        self.ids.reserve(self.deferred_nodes.len());
+        for (key, data) in std::mem::take(&mut self.deferred_nodes) {
+            if self.ids.contains_key(&key) {
+                duplicate_nodes.push(key);
+            } else {
+                let id = self.graph.add_node(data);
+                self.ids.insert(key, id);
+            }
+        }
+
+        self.graph.edges.reserve(self.deferred_edges.len());
+        for (index, (from, to, data)) in std::mem::take(&mut self.deferred_edges)
+            .into_iter()
+            .enumerate()
+        {
+            match (self.ids.get(&from), self.ids.get(&to)) {
+                (Some(from), Some(to)) => {
+                    self.graph.add_edge(*from, *to, data);
+                }
+                (from_id, to_id) => {
+                    let (from_missing, to_missing) = (from_id.is_none(), to_id.is_none());
+                    let mut edge = UnresolvedEdge {
+                        index,
+                        from,
+                        to,
+                        missing: Vec::with_capacity(2),
+                    };
+                    if from_missing {
+                        edge.missing.push(edge.from.clone());
+                    }
+                    if to_missing {
+                        edge.missing.push(edge.to.clone());
+                    }
+                    unresolved_edges.push(edge);
+                }
+            }
+        }
+
+        if duplicate_nodes.is_empty() && unresolved_edges.is_empty() {
+            Ok((self.graph, self.ids))
+        } else {
+            Err(BuildError {
+                duplicate_nodes,
+                unresolved_edges,
+            })
+        }
+    }
+}
+
+/// A bidirectional mapping between external integer IDs and [NodeID]s, returned by [Graph::from_numbered_edges].
+#[derive(Clone, Debug, Default)]
+pub struct IdMap {
+    pub nodes: HashMap<u64, NodeID>,
+    pub external: SecondaryMap<NodeID, u64>,
+}
+
+impl IdMap {
+    /// Returns the node of an external ID.
+    pub fn node(&self, external: u64) -> Option<NodeID> {
+        self.nodes.get(&external).copied()
+    }
+
+    /// Returns the external ID of a node.
+    pub fn external(&self, node: NodeID) -> Option<u64> {
+        self.external.get(node).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+impl<N: Default, E: Default> Graph<N, E> {
+    /// Builds a graph from edges between external integer IDs (e.g. a SNAP edge list), creating a node with default data for every distinct ID.
+    ///
+    /// Returns the graph and the mapping between external IDs and [NodeID]s in both directions. Room for the edges is reserved
+    /// according to the iterator's size hint, and nodes are allocated in chunks, see [GraphBuilder].
+    pub fn from_numbered_edges(edges: impl IntoIterator<Item = (u64, u64)>) -> (Self, IdMap) {
+        let edges = edges.into_iter();
+        let mut builder = GraphBuilder::new();
+        builder.graph.edges.reserve(edges.size_hint().0);
+        builder.extend_streamed(edges);
+
+        let (graph, nodes) = builder.build().expect("streamed edges always resolve");
+        let mut external = SecondaryMap::with_capacity(nodes.len());
+        for (key, id) in nodes.iter() {
+            external.insert(*id, *key);
+        }
+        (graph, IdMap { nodes, external })
+    }
+}
+
+impl<N, E, K: Hash + Eq> Default for GraphBuilder<N, E, K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GraphRef;
+
+    #[test]
+    fn test_streamed_edges_grow_in_chunks() {
+        let mut builder: GraphBuilder<(), u8, u64> = GraphBuilder::with_chunk_size(8);
+        let mut capacity = builder.graph().nodes.capacity();
+        let mut growths = 0;
+        for i in 0..100u64 {
+            builder.add_edge_streamed_with(i, (i + 1) % 100, (i % 7) as u8);
+            assert!(builder.graph().nodes.capacity() >= builder.node_count());
+            if builder.graph().nodes.capacity() != capacity {
+                capacity = builder.graph().nodes.capacity();
+                growths += 1;
+            }
+        }
+        // Node storage grows by at least a chunk of 8 at a time, so 100 nodes take at most 13 growths.
+        assert!(growths <= 13, "{} growths", growths);
+
+        assert_eq!(builder.node_count(), 100);
+        let (graph, ids) = builder.build().unwrap();
+        assert_eq!(graph.edges.len(), 100);
+        let node = graph.node(ids[&0]).unwrap();
+        assert_eq!(node.connections.len(), 2);
+    }
+
+    #[test]
+    fn test_node_or_insert_with_keeps_first_data() {
+        let mut builder: GraphBuilder<&str, (), &str> = GraphBuilder::new();
+        let a = builder.node_or_insert_with("a", || "first");
+        let again = builder.node_or_insert_with("a", || "second");
+        assert_eq!(a, again);
+        assert_eq!(builder.graph().node(a).unwrap().data, "first");
+    }
+
+    #[test]
+    fn test_deferred_build_reports_every_problem() {
+        let mut builder: GraphBuilder<&str, (), &str> = GraphBuilder::new();
+        builder.add_edge_streamed_with("streamed", "a", ());
+        builder.add_edge("a", "b", ());
+        builder.add_edge("x", "y", ());
+        builder.add_edge("b", "streamed", ());
+        builder.add_node("b", "b");
+        builder.add_node("a", "duplicate of a streamed node");
+        builder.add_node("b", "duplicate");
+
+        let error = builder.build().unwrap_err();
+        assert_eq!(error.duplicate_nodes, vec!["a", "b"]);
+        assert_eq!(
+            error.unresolved_edges,
+            vec![UnresolvedEdge {
+                index: 1,
+                from: "x",
+                to: "y",
+                missing: vec!["x", "y"]
+            }]
+        );
+
+        let mut builder: GraphBuilder<&str, (), &str> = GraphBuilder::new();
+        builder.add_edge("a", "b", ());
+        builder.add_node("b", "B");
+        builder.add_node("a", "A");
+        let (graph, ids) = builder.build().unwrap();
+        let edge = graph.edges.values().next().unwrap();
+        assert_eq!((edge.from, edge.to), (ids["a"], ids["b"]));
+        assert_eq!(graph.node(ids["b"]).unwrap().data, "B");
+
+        // Building doesn't need `Debug` keys, only formatting the error does.
+        #[derive(Clone, PartialEq, Eq, Hash)]
+        struct Key(u32);
+        let mut builder: GraphBuilder<(), (), Key> = GraphBuilder::new();
+        builder.add_node(Key(1), ());
+        builder.add_edge(Key(1), Key(2), ());
+        let Err(error) = builder.build() else {
+            panic!("edge to an undeclared key was built");
+        };
+        assert!(error.unresolved_edges[0].missing == vec![Key(2)]);
+    }
+
+    #[test]
+    fn test_from_numbered_edges() {
+        let edges = [(100u64, 7u64), (7, 3_000_000_000), (100, 100)];
+        let (graph, ids): (Graph<(), ()>, _) = Graph::from_numbered_edges(edges);
+
+        assert_eq!((graph.node_count(), graph.edges.len()), (3, 3));
+        assert_eq!(ids.len(), 3);
+        for (from, to) in edges {
+            let (from_id, to_id) = (ids.node(from).unwrap(), ids.node(to).unwrap());
+            assert_eq!(ids.external(from_id), Some(from));
+            assert!(graph
+                .edges
+                .values()
+                .any(|edge| edge.from == from_id && edge.to == to_id));
+        }
+        assert_eq!(ids.node(8), None);
+    }
+}
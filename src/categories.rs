@@ -1,6 +1,6 @@
 //! # A graph with category nodes.
 //!
-//! The [CategorizedGraph] struct uses a hash map to map category names ([String]) to a category node ([NodeID]) (where the node's edges are the nodes belonging to the category).
+//! The [CategorizedGraph] struct uses a hash map to map category keys ([String] by default) to a category node ([NodeID]) (where the node's edges are the nodes belonging to the category).
 //! There's also some useful extra functions to query categories and their nodes, and a [Categorized] trait that can be implemented for a custom struct if needed.
 //!
 //! In other words a simple extension to the graph that allows for efficient and easy grouping of nodes by strings.
@@ -30,29 +30,100 @@
 //! assert_eq!(graph.all_categories().len(), 2);
 //! ```
 
-use crate::*;
-#[cfg(feature = "hashbrown")]
-use hashbrown::HashMap;
-#[cfg(feature = "hashbrown")]
-use hashbrown::HashSet;
-
-#[cfg(not(feature = "hashbrown"))]
-use std::collections::HashMap;
-#[cfg(not(feature = "hashbrown"))]
-use std::collections::HashSet;
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::hash::Hash;
 
-use thiserror::Error;
+use crate::algorithms::DepthFirstSearch;
+use crate::hash::{HashMap, HashSet};
+use crate::*;
 
 /// A graph with category nodes (where the nodes contain an ID of the category and a list of nodes in that category) and a hash map that maps category names to category nodes efficiently.
+///
+/// Categories are identified by keys of type `K`, which is [String] by default. Any `Hash + Eq` type (e.g. an enum or an integer ID) can be used instead to avoid allocating strings:
+/// ```
+/// use fast_graph::*;
+///
+/// #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+/// enum Kind {
+///     Even,
+///     Odd,
+/// }
+///
+/// let mut graph: CategorizedGraph<u32, (), Kind> = CategorizedGraph::new();
+/// let nodes = graph.add_nodes(&[1, 2, 3]);
+/// graph.add_to_category(&Kind::Odd, vec![nodes[0], nodes[2]]);
+/// graph.add_to_category(&Kind::Even, vec![nodes[1]]);
+///
+/// assert_eq!(graph.nodes_by_category(&Kind::Odd).len(), 2);
+/// ```
+///
+/// Cloning keeps every [NodeID] and [EdgeID] valid in the clone, like for [Graph]; the listeners registered with
+/// [CategorizedGraph::on_category_event] are not cloned. [CategorizedGraph::clone_with_mapping] makes a compacted copy instead.
+///
+/// With the `serde` feature the graph is (de)serialized in a stable format that doesn't depend on slotmap keys:
+/// nodes and edges are written as lists that refer to each other by index, and every category is written (in order) with its key, the index of its node,
+/// the indices of its members and the data of its membership edges. A category node with several keys is written once, with all of them.
+/// [NodeID]s and [EdgeID]s are therefore not preserved.
 #[derive(Debug)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct CategorizedGraph<N, E> {
+pub struct CategorizedGraph<N, E, K = String> {
     pub nodes: SlotMap<NodeID, Node<N>>,
     pub edges: SlotMap<EdgeID, Edge<E>>,
-    pub categories: HashMap<String, NodeID>,
+    pub categories: HashMap<K, NodeID>,
+    /// The category nodes in the order their categories were created, see [CategorizedGraph::all_categories_ordered].
+    pub category_order: Vec<NodeID>,
+    /// The number of keys in `categories` every category node is registered under, so category nodes are recognized
+    /// without scanning `categories`. Kept up to date by the [Categorized] methods.
+    pub(crate) category_nodes: HashMap<NodeID, usize>,
+    /// What [GraphInterface::remove_node] does when it's asked to remove a category node.
+    pub category_node_policy: CategoryNodePolicy,
+    /// Whether removing edges updates the connections of their endpoints right away, see [DeletionMode].
+    pub deletion_mode: DeletionMode,
+    /// If true, [GraphInterface::add_edge] panics when `from` or `to` doesn't exist, see [Graph::strict].
+    pub strict: bool,
+    #[cfg(feature = "ordered")]
+    order: crate::order::InsertionOrder,
+    listeners: CategoryListeners<K>,
+}
+
+/// A change to the categories of a [CategorizedGraph], passed to the callbacks registered with [CategorizedGraph::on_category_event].
+#[derive(Clone, Debug, PartialEq)]
+pub enum CategoryEvent<K> {
+    /// A category was created (or registered under a key for the first time).
+    Created { category: NodeID, key: K },
+    /// An existing category was registered under a new key.
+    Renamed { category: NodeID, key: K },
+    /// A category is no longer registered under any key.
+    Deleted { category: NodeID },
+    /// Nodes were added to a category.
+    MembersAdded {
+        category: NodeID,
+        nodes: Vec<NodeID>,
+    },
+    /// Nodes were removed from a category (because their membership edges were removed).
+    MembersRemoved {
+        category: NodeID,
+        nodes: Vec<NodeID>,
+    },
+}
+
+type CategoryListener<K> = Box<dyn FnMut(&CategoryEvent<K>) + Send + Sync>;
+
+struct CategoryListeners<K>(Vec<CategoryListener<K>>);
+
+impl<K> Default for CategoryListeners<K> {
+    fn default() -> Self {
+        CategoryListeners(Vec::new())
+    }
+}
+
+impl<K> fmt::Debug for CategoryListeners<K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{} listener(s)]", self.0.len())
+    }
 }
 
-impl<N, E> GraphInterface for CategorizedGraph<N, E> {
+impl<N, E, K> GraphRef for CategorizedGraph<N, E, K> {
     type NodeData = N;
     type EdgeData = E;
 
@@ -64,41 +135,77 @@ impl<N, E> GraphInterface for CategorizedGraph<N, E> {
         self.nodes.len()
     }
 
-    fn remove_node(&mut self, id: NodeID) -> Result<(), GraphError> {
-        let node = self
-            .nodes
-            .remove(id)
-            .map_or(Err(GraphError::NodeNotFound), |n| Ok(n))?;
+    fn node(&self, id: NodeID) -> Result<&Node<N>, GraphError> {
+        self.nodes
+            .get(id)
+            .ok_or_else(|| GraphError::node_not_found(id))
+    }
 
-        for edge_id in node.connections.iter() {
-            self.remove_edge(*edge_id).or_else(|e| Ok(()))?;
+    fn edge(&self, id: EdgeID) -> Result<&Edge<E>, GraphError> {
+        self.edges
+            .get(id)
+            .ok_or_else(|| GraphError::edge_not_found(id))
+    }
+}
+
+impl<N, E, K> GraphInterface for CategorizedGraph<N, E, K> {
+    fn remove_node(&mut self, id: NodeID) -> Result<(), GraphError> {
+        if self.is_category_node(id) {
+            match self.category_node_policy {
+                CategoryNodePolicy::Refuse => return Err(GraphError::IsCategoryNode(id)),
+                CategoryNodePolicy::Unregister => {
+                    self.categories.retain(|_, category| *category != id);
+                    self.category_nodes.remove(&id);
+                    self.category_order.retain(|category| *category != id);
+                    self.emit(|| CategoryEvent::Deleted { category: id });
+                }
+            }
         }
 
-        Ok(())
+        self.remove_node_and_edges(id)
     }
 
     fn remove_edge(&mut self, id: EdgeID) -> Result<(), GraphError> {
-        let edge = self.edge(id)?;
+        let edge = self
+            .edge(id)
+            .map_err(|e| e.during(GraphOperation::RemoveEdge(id)))?;
         let from = edge.from;
         let to = edge.to;
 
-        if let Ok(node) = self.node_mut(from) {
-            node.connections.retain(|&x| x != id)
+        // Memberships removed together with their category node aren't reported, the category's Deleted event covers them.
+        if !self.listeners.0.is_empty()
+            && self.nodes.contains_key(from)
+            && self.is_category_node(from)
+        {
+            self.emit(|| CategoryEvent::MembersRemoved {
+                category: from,
+                nodes: vec![to],
+            });
         }
 
-        if let Ok(node) = self.node_mut(to) {
-            node.connections.retain(|&x| x != id)
+        // In lazy mode the connections keep a tombstone until `vacuum` is called.
+        if self.deletion_mode == DeletionMode::Eager {
+            if let Ok(node) = self.node_mut(from) {
+                node.connections.retain(|x| *x != id)
+            }
+
+            if let Ok(node) = self.node_mut(to) {
+                node.connections.retain(|x| *x != id)
+            }
         }
 
-        self.edges
-            .remove(id)
-            .map_or(Err(GraphError::EdgeNotFound), |_| Ok(()))?;
+        self.edges.remove(id).ok_or(GraphError::EdgeNotFound {
+            id,
+            during: GraphOperation::RemoveEdge(id),
+        })?;
 
         Ok(())
     }
 
     fn add_node(&mut self, data: N) -> NodeID {
         let id = self.nodes.insert_with_key(|id| Node::new(id, data));
+        #[cfg(feature = "ordered")]
+        self.order.add_node(id);
         id
     }
 
@@ -128,9 +235,16 @@ impl<N, E> GraphInterface for CategorizedGraph<N, E> {
     }
 
     fn add_edge(&mut self, from: NodeID, to: NodeID, data: E) -> EdgeID {
+        if self.strict {
+            if let Err(error) = crate::interface::check_endpoints(self, from, to) {
+                panic!("{}", error);
+            }
+        }
         let id = self
             .edges
             .insert_with_key(|id| Edge::new(id, from, to, data));
+        #[cfg(feature = "ordered")]
+        self.order.add_edge(id);
         if let Some(node) = self.nodes.get_mut(from) {
             node.add_connection(id);
         }
@@ -140,30 +254,294 @@ impl<N, E> GraphInterface for CategorizedGraph<N, E> {
         id
     }
 
-    fn node(&self, id: NodeID) -> Result<&Node<N>, GraphError> {
-        self.nodes.get(id).ok_or(GraphError::NodeNotFound)
+    fn node_mut(&mut self, id: NodeID) -> Result<&mut Node<N>, GraphError> {
+        self.nodes
+            .get_mut(id)
+            .ok_or_else(|| GraphError::node_not_found(id))
     }
 
-    fn node_mut(&mut self, id: NodeID) -> Result<&mut Node<N>, GraphError> {
-        self.nodes.get_mut(id).ok_or(GraphError::NodeNotFound)
+    fn edge_mut(&mut self, id: EdgeID) -> Result<&mut Edge<E>, GraphError> {
+        self.edges
+            .get_mut(id)
+            .ok_or_else(|| GraphError::edge_not_found(id))
     }
+}
 
-    fn edge(&self, id: EdgeID) -> Result<&Edge<E>, GraphError> {
-        self.edges.get(id).ok_or(GraphError::EdgeNotFound)
+impl<N, E, K> Default for CategorizedGraph<N, E, K> {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    fn edge_mut(&mut self, id: EdgeID) -> Result<&mut Edge<E>, GraphError> {
-        self.edges.get_mut(id).ok_or(GraphError::EdgeNotFound)
+impl<N: Clone, E: Clone, K: Clone> Clone for CategorizedGraph<N, E, K> {
+    /// Copies the graph with all its IDs. Category listeners aren't cloned, the clone starts without any.
+    fn clone(&self) -> Self {
+        CategorizedGraph {
+            nodes: self.nodes.clone(),
+            edges: self.edges.clone(),
+            categories: self.categories.clone(),
+            category_order: self.category_order.clone(),
+            category_nodes: self.category_nodes.clone(),
+            category_node_policy: self.category_node_policy,
+            deletion_mode: self.deletion_mode,
+            strict: self.strict,
+            #[cfg(feature = "ordered")]
+            order: self.order.clone(),
+            listeners: CategoryListeners::default(),
+        }
     }
 }
 
-impl<N, E> CategorizedGraph<N, E> {
+impl<N, E, K> CategorizedGraph<N, E, K> {
+    /// Removes a node and its edges, without looking at the registered categories.
+    fn remove_node_and_edges(&mut self, id: NodeID) -> Result<(), GraphError> {
+        let node = self.nodes.remove(id).ok_or(GraphError::NodeNotFound {
+            id,
+            during: GraphOperation::RemoveNode(id),
+        })?;
+
+        for edge_id in node.connections.iter() {
+            // The edge may already be gone (e.g. a self-loop listed twice).
+            let _ = self.remove_edge(*edge_id);
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if the node is registered as a category.
+    pub(crate) fn is_category_node(&self, id: NodeID) -> bool {
+        self.category_nodes.contains_key(&id)
+    }
+
+    /// Counts a key registered for a category node.
+    pub(crate) fn register_category_node(&mut self, id: NodeID) {
+        *self.category_nodes.entry(id).or_default() += 1;
+    }
+
+    /// Forgets a key registered for a category node. Returns true if it was the node's last key.
+    fn unregister_category_node(&mut self, id: NodeID) -> bool {
+        match self.category_nodes.get_mut(&id) {
+            Some(count) if *count > 1 => {
+                *count -= 1;
+                false
+            }
+            Some(_) => {
+                self.category_nodes.remove(&id);
+                true
+            }
+            None => true,
+        }
+    }
+
     pub fn new() -> Self {
         CategorizedGraph {
             edges: SlotMap::with_key(),
             nodes: SlotMap::with_key(),
-            categories: HashMap::new(),
+            categories: HashMap::default(),
+            category_order: Vec::new(),
+            category_nodes: HashMap::default(),
+            category_node_policy: CategoryNodePolicy::default(),
+            deletion_mode: DeletionMode::default(),
+            strict: false,
+            #[cfg(feature = "ordered")]
+            order: Default::default(),
+            listeners: CategoryListeners::default(),
+        }
+    }
+
+    /// Returns a compacted copy of the graph (see [Graph::compact]) together with the mapping from the IDs of this graph to the IDs of the copy.
+    ///
+    /// Categories are carried over to the new IDs; category listeners aren't cloned. Use [Clone] instead to keep the IDs.
+    pub fn clone_with_mapping(&self) -> (Self, IdMapping)
+    where
+        N: Clone,
+        E: Clone,
+        K: Clone + Hash + Eq,
+    {
+        let mut graph = Graph {
+            nodes: self.nodes.clone(),
+            edges: self.edges.clone(),
+            strict: self.strict,
+            stats: Default::default(),
+            #[cfg(feature = "ordered")]
+            order: self.order.clone(),
+        };
+        let mapping = graph.compact_with_mapping();
+        let categories: HashMap<K, NodeID> = self
+            .categories
+            .iter()
+            .filter_map(|(key, id)| Some((key.clone(), *mapping.nodes.get(id)?)))
+            .collect();
+        let mut category_nodes = HashMap::default();
+        for id in categories.values() {
+            *category_nodes.entry(*id).or_default() += 1;
+        }
+        let copy = CategorizedGraph {
+            nodes: graph.nodes,
+            edges: graph.edges,
+            categories,
+            category_nodes,
+            category_order: self
+                .category_order
+                .iter()
+                .filter_map(|id| mapping.nodes.get(id).copied())
+                .collect(),
+            category_node_policy: self.category_node_policy,
+            deletion_mode: self.deletion_mode,
+            strict: self.strict,
+            #[cfg(feature = "ordered")]
+            order: graph.order,
+            listeners: CategoryListeners::default(),
+        };
+        (copy, mapping)
+    }
+
+    /// Returns every edge (including membership edges) as `(from, to, &data)`, in slot order, see [Graph::edge_triples].
+    pub fn edge_triples(&self) -> impl Iterator<Item = (NodeID, NodeID, &E)> + Clone {
+        self.edges
+            .values()
+            .map(|edge| (edge.from, edge.to, &edge.data))
+    }
+
+    /// Returns a reference to every edge (including membership edges), in slot order.
+    pub fn edge_references(&self) -> impl Iterator<Item = &Edge<E>> + Clone {
+        self.edges.values()
+    }
+
+    /// Returns every node (including category nodes) with its data and its outgoing edges (including membership edges), see [Graph::rows].
+    #[allow(clippy::type_complexity)]
+    pub fn rows(
+        &self,
+    ) -> impl Iterator<
+        Item = (
+            NodeID,
+            &N,
+            impl Iterator<Item = (EdgeID, NodeID, &E)> + Clone,
+        ),
+    > + Clone {
+        crate::rows(&self.nodes, &self.edges)
+    }
+
+    /// Returns the node IDs (including category nodes) in the order they were added (requires the `ordered` feature), see [Graph::nodes_ordered].
+    #[cfg(feature = "ordered")]
+    pub fn nodes_ordered(&self) -> Vec<NodeID> {
+        self.order.sort_nodes(self.nodes.keys())
+    }
+
+    /// Returns the edge IDs in the order they were added (requires the `ordered` feature), see [Graph::nodes_ordered].
+    #[cfg(feature = "ordered")]
+    pub fn edges_ordered(&self) -> Vec<EdgeID> {
+        self.order.sort_edges(self.edges.keys())
+    }
+
+    /// Removes the tombstones left behind by [DeletionMode::Lazy] from every node in one pass. Returns the number of tombstones removed.
+    pub fn vacuum(&mut self) -> usize {
+        vacuum_connections(&mut self.nodes, &self.edges)
+    }
+
+    /// Removes the edges whose `from` or `to` node no longer exists, returning their IDs, see [Graph::collect_dangling_edges].
+    pub fn collect_dangling_edges(&mut self) -> Vec<EdgeID> {
+        crate::collect_dangling_edges(&mut self.nodes, &mut self.edges)
+    }
+
+    /// Registers a callback that is invoked on every category creation, rename, deletion and membership change.
+    ///
+    /// Membership changes are only reported for categories registered under a key.
+    pub fn on_category_event(
+        &mut self,
+        listener: impl FnMut(&CategoryEvent<K>) + Send + Sync + 'static,
+    ) {
+        self.listeners.0.push(Box::new(listener));
+    }
+
+    /// Removes all callbacks registered with [CategorizedGraph::on_category_event].
+    pub fn clear_category_listeners(&mut self) {
+        self.listeners.0.clear();
+    }
+
+    fn emit(&mut self, event: impl FnOnce() -> CategoryEvent<K>) {
+        if self.listeners.0.is_empty() {
+            return;
+        }
+        let event = event();
+        for listener in self.listeners.0.iter_mut() {
+            listener(&event);
+        }
+    }
+
+    /// Returns a list of all categories in the order they were created (renaming a category keeps its position).
+    ///
+    /// Categories missing from [CategorizedGraph::category_order] (e.g. inserted into [CategorizedGraph::categories] directly) come last.
+    pub fn all_categories_ordered(&self) -> Vec<(&K, NodeID)> {
+        let mut positions: HashMap<NodeID, usize> =
+            HashMap::with_capacity_and_hasher(self.category_order.len(), Default::default());
+        for (position, id) in self.category_order.iter().enumerate() {
+            positions.entry(*id).or_insert(position);
+        }
+
+        let mut categories: Vec<(&K, NodeID)> = self
+            .categories
+            .iter()
+            .map(|(cat, node)| (cat, *node))
+            .collect();
+        categories.sort_by_key(|(_, id)| positions.get(id).copied().unwrap_or(usize::MAX));
+        categories
+    }
+
+    /// Moves a category to `position` in the order returned by [CategorizedGraph::all_categories_ordered], shifting the ones after it.
+    ///
+    /// Returns Error([CategorizedGraphError::CategoryNotFound]) if the category doesn't exist.
+    pub fn move_category<Q: CategoryKey<K> + ?Sized>(
+        &mut self,
+        category: &Q,
+        position: usize,
+    ) -> Result<(), CategorizedGraphError> {
+        let id = *category
+            .get_in(&self.categories)
+            .ok_or_else(|| CategorizedGraphError::CategoryNotFound(format!("{:?}", category)))?;
+        self.category_order.retain(|other| *other != id);
+        self.category_order
+            .insert(position.min(self.category_order.len()), id);
+        Ok(())
+    }
+}
+
+/// A standalone [Graph] copied out of a [CategorizedGraph], together with a mapping from the original [NodeID]s to the copied ones.
+pub type Subgraph<N, E> = (Graph<N, E>, HashMap<NodeID, NodeID>);
+
+impl<N: Clone, E: Clone, K: Hash + Eq> CategorizedGraph<N, E, K> {
+    /// Copies the members of a category and the edges among them into a standalone [Graph]. The category node itself isn't included.
+    ///
+    /// Returns the graph together with a mapping from the [NodeID]s in this graph to the ones in the subgraph,
+    /// otherwise returns Error([CategorizedGraphError::CategoryNotFound]).
+    pub fn subgraph_of_category<Q: CategoryKey<K> + ?Sized>(
+        &self,
+        category: &Q,
+    ) -> Result<Subgraph<N, E>, CategorizedGraphError> {
+        let category_id = *category
+            .get_in(&self.categories)
+            .ok_or_else(|| CategorizedGraphError::CategoryNotFound(format!("{:?}", category)))?;
+
+        let mut subgraph = Graph::new();
+        let mut mapping = HashMap::default();
+        for member in self.nodes_by_category_id(category_id) {
+            if mapping.contains_key(&member) || member == category_id {
+                continue;
+            }
+            let node = self.node(member)?;
+            mapping.insert(member, subgraph.add_node(node.data.clone()));
+        }
+
+        for member in mapping.keys() {
+            // Skips the tombstones left by lazy deletion, and lists a self-loop once.
+            for edge in outgoing_edges(self, *member) {
+                if let Some(to) = mapping.get(&edge.to) {
+                    subgraph.add_edge(mapping[member], *to, edge.data.clone());
+                }
+            }
         }
+
+        Ok((subgraph, mapping))
     }
 }
 
@@ -174,15 +552,88 @@ pub enum CategorizedGraphError {
     CategoryAlreadyExists(String),
     #[error("Category identified by `{0}` does not exists")]
     CategoryNotFound(String),
+    #[error(transparent)]
+    Graph(#[from] GraphError),
+}
+
+/// How a [CategorizedGraph] removes edges (including the edges of removed nodes).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DeletionMode {
+    /// The removed edge is also removed from the connections of both endpoints, which costs O(degree) per edge.
+    #[default]
+    Eager,
+    /// Only the edge itself is removed, leaving a tombstone (a stale [EdgeID]) in the connections of its endpoints.
+    /// Lookups and traversals skip tombstones, and [CategorizedGraph::vacuum] removes all of them in one pass.
+    Lazy,
+}
+
+/// What [GraphInterface::remove_node] does with a node that is registered as a category in a [CategorizedGraph].
+///
+/// Categories can always be removed with [Categorized::delete_category], regardless of the policy.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum CategoryNodePolicy {
+    /// The node is removed and unregistered from [CategorizedGraph::categories], so no dangling entry is left behind.
+    #[default]
+    Unregister,
+    /// The node isn't removed and Error([GraphError::IsCategoryNode]) is returned.
+    Refuse,
+}
+
+/// What [Categorized::delete_category] does with the nodes that belong to the deleted category.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DeletePolicy {
+    /// Only the category node (and its membership edges) is removed, the member nodes are kept.
+    KeepMembers,
+    /// The member nodes are removed together with the category node.
+    Cascade,
+}
+
+/// The borrowed form of a category key `K` that lookups accept, e.g. `str` for [String] keys or `K` itself for `Clone` keys.
+///
+/// This is implemented automatically for every type that `K` can be borrowed as and turned back into, so it never has to be implemented by hand.
+pub trait CategoryKey<K>: Hash + Eq + Debug {
+    /// Returns the owned key, used when a category is inserted.
+    fn to_key(&self) -> K;
+
+    /// Looks the key up in a map keyed by `K`.
+    fn get_in<'a, V>(&self, map: &'a HashMap<K, V>) -> Option<&'a V>;
+
+    /// Removes the key from a map keyed by `K`.
+    fn remove_from<V>(&self, map: &mut HashMap<K, V>) -> Option<V>;
+}
+
+impl<K, Q> CategoryKey<K> for Q
+where
+    K: Hash + Eq + Borrow<Q>,
+    Q: Hash + Eq + Debug + ToOwned<Owned = K> + ?Sized,
+{
+    fn to_key(&self) -> K {
+        self.to_owned()
+    }
+
+    fn get_in<'a, V>(&self, map: &'a HashMap<K, V>) -> Option<&'a V> {
+        map.get(self)
+    }
+
+    fn remove_from<V>(&self, map: &mut HashMap<K, V>) -> Option<V> {
+        map.remove(self)
+    }
 }
 
 /// Methods for a graph with categories.
-pub trait Categorized<N, E, C>: GraphInterface<NodeData = N, EdgeData = E> {
+///
+/// `C` is the type of the data a category node is created with, and `K` the type of the keys categories are identified by.
+pub trait Categorized<N, E, C, K = String>: GraphInterface<NodeData = N, EdgeData = E> {
     /// Returns the category ID by name. In the standard implementation this is a hashmap lookup.
-    fn category_id_by_name(&self, category_name: &str) -> Option<&NodeID>;
+    fn category_id_by_name<Q: CategoryKey<K> + ?Sized>(&self, category_name: &Q)
+        -> Option<&NodeID>;
 
     /// Checks if the category exists by name.
-    fn category_exists(&self, category_name: &str) -> bool {
+    fn category_exists<Q: CategoryKey<K> + ?Sized>(&self, category_name: &Q) -> bool {
         self.category_id_by_name(category_name).is_some()
     }
 
@@ -196,13 +647,9 @@ pub trait Categorized<N, E, C>: GraphInterface<NodeData = N, EdgeData = E> {
         E: Default + Clone,
         N: Clone,
     {
-        let category_node = self.node(category_id).map_or(
-            Err(CategorizedGraphError::CategoryNotFound(format!(
-                "NodeID({:?})",
-                category_id
-            ))),
-            |node| Ok(node),
-        )?;
+        let category_node = self.node(category_id).map_err(|_| {
+            CategorizedGraphError::CategoryNotFound(format!("NodeID({:?})", category_id))
+        })?;
 
         let edges: Vec<(NodeID, NodeID)> = nodes
             .iter()
@@ -215,52 +662,197 @@ pub trait Categorized<N, E, C>: GraphInterface<NodeData = N, EdgeData = E> {
     }
 
     /// In the default implementation this is used to insert the category ID into the hashmap.
-    fn insert_category_id_by_name(&mut self, category_name: &str, category_id: NodeID) {
+    fn insert_category_id_by_name<Q: CategoryKey<K> + ?Sized>(
+        &mut self,
+        _category_name: &Q,
+        _category_id: NodeID,
+    ) {
+        // Default implementation (optional logic)
+        // You can leave this empty or provide some default behavior
+    }
+
+    /// In the default implementation this is used to remove the category ID from the hashmap. Returns the removed ID.
+    fn remove_category_id_by_name<Q: CategoryKey<K> + ?Sized>(
+        &mut self,
+        _category_name: &Q,
+    ) -> Option<NodeID> {
         // Default implementation (optional logic)
         // You can leave this empty or provide some default behavior
+        None
+    }
+
+    /// Removes a category node and its edges, but leaves it registered. Used by [Categorized::delete_category] and
+    /// [Categorized::merge_categories] before they unregister the category, so a failed removal leaves the category intact.
+    ///
+    /// In the default implementation this is [GraphInterface::remove_node].
+    fn remove_category_node(&mut self, category_id: NodeID) -> Result<(), GraphError> {
+        self.remove_node(category_id)
+    }
+
+    /// Deletes a category by name. The category node is always removed, the member nodes are kept or removed according to `policy`.
+    ///
+    /// Returns the member nodes of the deleted category, otherwise returns Error([CategorizedGraphError::CategoryNotFound]).
+    fn delete_category<Q: CategoryKey<K> + ?Sized>(
+        &mut self,
+        category_name: &Q,
+        policy: DeletePolicy,
+    ) -> Result<Vec<NodeID>, CategorizedGraphError> {
+        let category_id = *self.category_id_by_name(category_name).ok_or_else(|| {
+            CategorizedGraphError::CategoryNotFound(format!("{:?}", category_name))
+        })?;
+        let members = self.nodes_by_category_id(category_id);
+
+        self.remove_category_node(category_id)?;
+        self.remove_category_id_by_name(category_name);
+        if policy == DeletePolicy::Cascade {
+            for member in &members {
+                // A node can be listed twice if it was added to the category twice.
+                if self.node(*member).is_ok() {
+                    self.remove_node(*member)?;
+                }
+            }
+        }
+
+        Ok(members)
+    }
+
+    /// Renames a category, keeping its node, data and members.
+    ///
+    /// Returns Error([CategorizedGraphError::CategoryNotFound]) if `old_name` doesn't exist, or Error([CategorizedGraphError::CategoryAlreadyExists]) if `new_name` already does.
+    fn rename_category<Q: CategoryKey<K> + ?Sized>(
+        &mut self,
+        old_name: &Q,
+        new_name: &Q,
+    ) -> Result<NodeID, CategorizedGraphError> {
+        let category_id = *self
+            .category_id_by_name(old_name)
+            .ok_or_else(|| CategorizedGraphError::CategoryNotFound(format!("{:?}", old_name)))?;
+        if old_name == new_name {
+            return Ok(category_id);
+        }
+        if self.category_exists(new_name) {
+            return Err(CategorizedGraphError::CategoryAlreadyExists(format!(
+                "{:?}",
+                new_name
+            )));
+        }
+
+        self.insert_category_id_by_name(new_name, category_id);
+        self.remove_category_id_by_name(old_name);
+        Ok(category_id)
+    }
+
+    /// Merges two categories into a new category node registered under `merged_name`, whose data is created by `merge` from the data of both category nodes.
+    ///
+    /// The new category contains the union of both memberships. The old category nodes are removed, their member nodes are kept.
+    /// `merged_name` may be the name of one of the merged categories.
+    ///
+    /// Returns the [NodeID] of the merged category, otherwise returns Error([CategorizedGraphError::CategoryNotFound]) or Error([CategorizedGraphError::CategoryAlreadyExists]).
+    fn merge_categories<Q: CategoryKey<K> + ?Sized>(
+        &mut self,
+        a: &Q,
+        b: &Q,
+        merged_name: &Q,
+        merge: impl FnOnce(&N, &N) -> N,
+    ) -> Result<NodeID, CategorizedGraphError>
+    where
+        E: Default + Clone,
+        N: Clone,
+    {
+        let a_id = *self
+            .category_id_by_name(a)
+            .ok_or_else(|| CategorizedGraphError::CategoryNotFound(format!("{:?}", a)))?;
+        let b_id = *self
+            .category_id_by_name(b)
+            .ok_or_else(|| CategorizedGraphError::CategoryNotFound(format!("{:?}", b)))?;
+        if merged_name != a && merged_name != b && self.category_exists(merged_name) {
+            return Err(CategorizedGraphError::CategoryAlreadyExists(format!(
+                "{:?}",
+                merged_name
+            )));
+        }
+
+        let data = merge(&self.node(a_id)?.data, &self.node(b_id)?.data);
+        let mut seen = HashSet::default();
+        let members: Vec<NodeID> = self
+            .nodes_by_category_id(a_id)
+            .into_iter()
+            .chain(self.nodes_by_category_id(b_id))
+            .filter(|member| *member != a_id && *member != b_id && seen.insert(*member))
+            .collect();
+
+        self.remove_category_node(a_id)?;
+        if b_id != a_id {
+            self.remove_category_node(b_id)?;
+        }
+        self.remove_category_id_by_name(a);
+        self.remove_category_id_by_name(b);
+
+        let merged = self.add_node(data);
+        self.insert_category_id_by_name(merged_name, merged);
+        self.add_to_category_by_id(merged, members)?;
+        Ok(merged)
     }
 
-    /// If the category does not exist, it is created. Returns the [NodeID] of the category.
-    fn add_to_category(&mut self, category_name: &str, nodes: Vec<NodeID>) -> NodeID
+    /// If the category does not exist, it is created (with `N::default()` as data). Returns the [NodeID] of the category.
+    ///
+    /// See [Categorized::add_to_category_or_create_with] to give an auto-created category node meaningful data.
+    fn add_to_category<Q: CategoryKey<K> + ?Sized>(
+        &mut self,
+        category_name: &Q,
+        nodes: Vec<NodeID>,
+    ) -> NodeID
     where
         E: Default + Clone,
         N: Clone + Default,
     {
-        let existing: Option<&NodeID> = self.category_id_by_name(category_name);
-        let category_node: NodeID;
+        self.add_to_category_or_create_with(category_name, nodes, N::default)
+    }
 
-        if existing.is_some() {
-            category_node = *existing.unwrap();
+    /// If the category does not exist, it is created with the data returned by `data`, which isn't called otherwise. Returns the [NodeID] of the category.
+    fn add_to_category_or_create_with<Q: CategoryKey<K> + ?Sized>(
+        &mut self,
+        category_name: &Q,
+        nodes: Vec<NodeID>,
+        data: impl FnOnce() -> N,
+    ) -> NodeID
+    where
+        E: Default + Clone,
+        N: Clone,
+    {
+        let existing: Option<NodeID> = self.category_id_by_name(category_name).copied();
+
+        if let Some(category_node) = existing {
             self.add_to_category_by_id(category_node, nodes).unwrap();
+            category_node
         } else {
-            category_node = self.add_node(N::default());
+            let category_node = self.add_node(data());
+            self.insert_category_id_by_name(category_name, category_node);
             self.add_to_category_by_id(category_node, nodes).unwrap();
-            self.insert_category_id_by_name(category_name, category_node)
+            category_node
         }
-
-        category_node
     }
 
     /// Creates a new category [Node] with the given name, nodes, and (optionally) data.
     ///
-    /// Returns the [NodeID] of the category if successful, otherwise returns Error(CategorizedGraphError::CategoryAlreadyExists).
+    /// Returns the [NodeID] of the category if successful, otherwise returns Error([CategorizedGraphError::CategoryAlreadyExists]).
     ///
     /// An empty vector of nodes can be passed.
-    fn create_category(
+    fn create_category<Q: CategoryKey<K> + ?Sized>(
         &mut self,
-        category: &str,
+        category: &Q,
         nodes: Vec<NodeID>,
         data: C,
-    ) -> Result<NodeID, String>
+    ) -> Result<NodeID, CategorizedGraphError>
     where
         E: Default + Clone,
-        N: Clone + Default;
+        N: Clone;
 
     /// Returns a list of all categories.
-    fn all_categories(&self) -> Vec<(&String, NodeID)>;
+    fn all_categories(&self) -> Vec<(&K, NodeID)>;
 
     /// Returns the category node by name.
-    fn category(&self, category: &str) -> Option<&Node<N>>;
+    fn category<Q: CategoryKey<K> + ?Sized>(&self, category: &Q) -> Option<&Node<N>>;
 
     /// Checks if the category exists by ID.
     fn category_exists_by_id(&self, category: NodeID) -> bool {
@@ -274,14 +866,47 @@ pub trait Categorized<N, E, C>: GraphInterface<NodeData = N, EdgeData = E> {
     fn nodes_by_category_id(&self, category: NodeID) -> Vec<NodeID>;
 
     /// Returns a list of nodes in the category by name.
-    fn nodes_by_category(&self, category: &str) -> Vec<NodeID>;
+    fn nodes_by_category<Q: CategoryKey<K> + ?Sized>(&self, category: &Q) -> Vec<NodeID>;
+
+    /// Returns an iterator over the member nodes of a category by name, without collecting their IDs first.
+    ///
+    /// The iterator is empty if the category doesn't exist.
+    fn category_nodes_iter<'a, Q: CategoryKey<K> + ?Sized>(
+        &'a self,
+        category: &Q,
+    ) -> impl Iterator<Item = &'a Node<N>>
+    where
+        N: 'a,
+        E: 'a,
+    {
+        let category_id = self.category_id_by_name(category).copied();
+        category_id
+            .and_then(|id| self.node(id).ok())
+            .into_iter()
+            .flat_map(|category_node| category_node.connections.iter())
+            .filter_map(|edge_id| self.edge(*edge_id).ok())
+            // Only outgoing edges are memberships, incoming ones point at the category itself.
+            .filter(move |edge| Some(edge.from) == category_id)
+            .filter_map(|edge| self.node(edge.to).ok())
+    }
+
+    /// Returns an iterator over the data of the member nodes of a category by name, see [Categorized::category_nodes_iter].
+    fn category_data_iter<'a, Q: CategoryKey<K> + ?Sized>(
+        &'a self,
+        category: &Q,
+    ) -> impl Iterator<Item = &'a N>
+    where
+        N: 'a,
+        E: 'a,
+    {
+        self.category_nodes_iter(category).map(|node| &node.data)
+    }
 
     /// Returns a list of nodes in the categories by name.
-    fn nodes_by_categories(&self, categories: Vec<&str>) -> Vec<NodeID> {
+    fn nodes_by_categories<Q: CategoryKey<K> + ?Sized>(&self, categories: Vec<&Q>) -> Vec<NodeID> {
         categories
             .iter()
-            .map(|category| self.nodes_by_category(category))
-            .flatten()
+            .flat_map(|category| self.nodes_by_category(*category))
             .collect()
     }
 
@@ -289,54 +914,140 @@ pub trait Categorized<N, E, C>: GraphInterface<NodeData = N, EdgeData = E> {
     fn nodes_by_category_ids(&self, categories: Vec<NodeID>) -> Vec<NodeID> {
         categories
             .iter()
-            .map(|category| self.nodes_by_category_id(*category))
-            .flatten()
+            .flat_map(|category| self.nodes_by_category_id(*category))
             .collect()
     }
+
+    /// Returns a *depth first search* iterator starting from a given node that only visits members of the category.
+    ///
+    /// The category's membership is looked up once, so no subgraph has to be built. If `start` isn't a member (or the category doesn't exist) nothing is visited.
+    fn iter_depth_first_in_category<Q: CategoryKey<K> + ?Sized>(
+        &self,
+        start: NodeID,
+        category: &Q,
+    ) -> DepthFirstSearch<'_, Self, impl FnMut(NodeID) -> bool>
+    where
+        Self: Sized,
+    {
+        let members: HashSet<NodeID> = self.nodes_by_category(category).into_iter().collect();
+        DepthFirstSearch::with_filter(self, start, move |id| members.contains(&id))
+    }
 }
 
-impl<N, E> Categorized<N, E, N> for CategorizedGraph<N, E>
+impl<N, E, K: Hash + Eq> Categorized<N, E, N, K> for CategorizedGraph<N, E, K>
 where
     Self: GraphInterface<NodeData = N, EdgeData = E>,
 {
-    fn category_id_by_name(&self, category_name: &str) -> Option<&NodeID> {
-        self.categories.get(category_name)
+    fn category_id_by_name<Q: CategoryKey<K> + ?Sized>(
+        &self,
+        category_name: &Q,
+    ) -> Option<&NodeID> {
+        category_name.get_in(&self.categories)
     }
 
-    fn insert_category_id_by_name(&mut self, category_name: &str, category_id: NodeID) {
-        self.categories
-            .insert(category_name.to_string(), category_id);
+    fn add_to_category_by_id(
+        &mut self,
+        category_id: NodeID,
+        nodes: Vec<NodeID>,
+    ) -> Result<(), CategorizedGraphError>
+    where
+        E: Default + Clone,
+        N: Clone,
+    {
+        if self.node(category_id).is_err() {
+            return Err(CategorizedGraphError::CategoryNotFound(format!(
+                "NodeID({:?})",
+                category_id
+            )));
+        }
+
+        let edges: Vec<(NodeID, NodeID)> = nodes.iter().map(|node| (category_id, *node)).collect();
+        self.add_edges(&edges);
+
+        if self.is_category_node(category_id) {
+            self.emit(|| CategoryEvent::MembersAdded {
+                category: category_id,
+                nodes,
+            });
+        }
+        Ok(())
     }
 
-    fn create_category(
+    fn insert_category_id_by_name<Q: CategoryKey<K> + ?Sized>(
         &mut self,
-        category: &str,
+        category_name: &Q,
+        category_id: NodeID,
+    ) {
+        let existed = self.is_category_node(category_id);
+        if let Some(replaced) = self.categories.insert(category_name.to_key(), category_id) {
+            self.unregister_category_node(replaced);
+        }
+        self.register_category_node(category_id);
+        if !existed {
+            self.category_order.push(category_id);
+            self.emit(|| CategoryEvent::Created {
+                category: category_id,
+                key: category_name.to_key(),
+            });
+        } else {
+            self.emit(|| CategoryEvent::Renamed {
+                category: category_id,
+                key: category_name.to_key(),
+            });
+        }
+    }
+
+    fn remove_category_id_by_name<Q: CategoryKey<K> + ?Sized>(
+        &mut self,
+        category_name: &Q,
+    ) -> Option<NodeID> {
+        let removed = category_name.remove_from(&mut self.categories)?;
+        // The node may still be registered under another name (e.g. while it's being renamed).
+        if self.unregister_category_node(removed) {
+            self.category_order.retain(|id| *id != removed);
+            self.emit(|| CategoryEvent::Deleted { category: removed });
+        }
+        Some(removed)
+    }
+
+    /// Removes the node regardless of [CategorizedGraph::category_node_policy].
+    fn remove_category_node(&mut self, category_id: NodeID) -> Result<(), GraphError> {
+        self.remove_node_and_edges(category_id)
+    }
+
+    fn create_category<Q: CategoryKey<K> + ?Sized>(
+        &mut self,
+        category: &Q,
         nodes: Vec<NodeID>,
         data: N,
-    ) -> Result<NodeID, String>
+    ) -> Result<NodeID, CategorizedGraphError>
     where
         E: Default + Clone,
-        N: Clone + Default,
+        N: Clone,
     {
-        let existing_category: Option<&NodeID> = self.categories.get(category);
-        if existing_category.is_some() {
-            return Err(format!("Category {} already exists", category));
+        if category.get_in(&self.categories).is_some() {
+            return Err(CategorizedGraphError::CategoryAlreadyExists(format!(
+                "{:?}",
+                category
+            )));
         }
+        // Register the data-carrying node itself, so no second (default) node gets created.
         let category_node = self.add_node(data);
-        self.add_to_category(category, nodes);
+        self.insert_category_id_by_name(category, category_node);
+        self.add_to_category_by_id(category_node, nodes)?;
         Ok(category_node)
     }
 
-    fn all_categories(&self) -> Vec<(&String, NodeID)> {
+    fn all_categories(&self) -> Vec<(&K, NodeID)> {
         self.categories
             .iter()
             .map(|(cat, node)| (cat, *node))
             .collect()
     }
 
-    fn category(&self, category: &str) -> Option<&Node<N>> {
-        self.categories
-            .get(category)
+    fn category<Q: CategoryKey<K> + ?Sized>(&self, category: &Q) -> Option<&Node<N>> {
+        category
+            .get_in(&self.categories)
             .map(|id| self.node(*id).unwrap())
     }
 
@@ -345,22 +1056,14 @@ where
     }
 
     fn nodes_by_category_id(&self, category: NodeID) -> Vec<NodeID> {
-        self.node(category)
-            .and_then(|category_node| {
-                category_node
-                    .connections
-                    .iter()
-                    .filter_map(|edge_id| self.edge(*edge_id).map_or(None, |edge| Some(edge)))
-                    .map(|edge| Ok(edge.to))
-                    .collect()
-            })
-            .unwrap_or(Vec::new())
-    }
-
-    fn nodes_by_category(&self, category: &str) -> Vec<NodeID> {
-        self.categories
-            .get(category)
+        // Only outgoing edges are memberships, incoming ones point at the category itself.
+        outgoing_edges(self, category).map(|edge| edge.to).collect()
+    }
+
+    fn nodes_by_category<Q: CategoryKey<K> + ?Sized>(&self, category: &Q) -> Vec<NodeID> {
+        category
+            .get_in(&self.categories)
             .map(|id| self.nodes_by_category_id(*id))
-            .unwrap_or(Vec::new())
+            .unwrap_or_default()
     }
 }
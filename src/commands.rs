@@ -0,0 +1,196 @@
+//! # Batched mutations.
+//!
+//! A [GraphCommand] describes a single mutation without needing access to the graph, so lists of commands can be built
+//! in parallel (e.g. one per thread or per input file) and applied afterwards in one go with [Graph::apply_commands].
+//! Nodes created by the same batch are referred to by their position among the batch's [GraphCommand::AddNode] commands ([NodeRef::New]).
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::commands::{GraphCommand, NodeRef};
+//!
+//! let mut graph: Graph<&str, u32> = Graph::new();
+//! let root = graph.add_node("root");
+//!
+//! let applied = graph
+//!     .apply_commands([
+//!         GraphCommand::AddNode("child"),
+//!         GraphCommand::AddEdge { from: NodeRef::Existing(root), to: NodeRef::New(0), data: 1 },
+//!     ])
+//!     .unwrap();
+//!
+//! assert_eq!(graph.node(applied.nodes[0]).unwrap().data, "child");
+//! assert_eq!(graph.edge(applied.edges[0]).unwrap().from, root);
+//! ```
+
+use thiserror::Error;
+
+use crate::{EdgeID, Graph, GraphError, GraphInterface, NodeID};
+
+/// A node referred to by a [GraphCommand].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NodeRef {
+    /// A node that existed before the batch was applied.
+    Existing(NodeID),
+    /// The node created by the `n`th [GraphCommand::AddNode] of the batch (counting from 0).
+    New(usize),
+}
+
+/// A mutation of a graph, see the [module documentation](self).
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GraphCommand<N, E> {
+    AddNode(N),
+    AddEdge { from: NodeRef, to: NodeRef, data: E },
+    RemoveNode(NodeRef),
+    RemoveEdge(EdgeID),
+}
+
+/// The IDs created by [Graph::apply_commands], in the order of the commands that created them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AppliedCommands {
+    pub nodes: Vec<NodeID>,
+    pub edges: Vec<EdgeID>,
+}
+
+/// Returned by [Graph::apply_commands] when a command fails. The commands before it have been applied.
+#[derive(Clone, Debug, Error)]
+#[error("Command {index} failed: {source}")]
+pub struct CommandError {
+    /// The position of the failed command in the batch.
+    pub index: usize,
+    pub source: CommandErrorKind,
+    /// The IDs created by the commands before the failed one.
+    pub applied: AppliedCommands,
+}
+
+/// Why a command failed, see [CommandError].
+#[derive(Clone, Debug, Error)]
+pub enum CommandErrorKind {
+    #[error(transparent)]
+    Graph(#[from] GraphError),
+    /// A [NodeRef::New] whose index doesn't refer to an `AddNode` earlier in the batch.
+    #[error("New node {0} doesn't refer to an earlier AddNode of the batch")]
+    UnknownNewNode(usize),
+}
+
+impl<N, E> Graph<N, E> {
+    /// Applies a batch of commands in order, returning the IDs of the created nodes and edges.
+    ///
+    /// Room for all created nodes and edges is reserved up front. Edges are added with [GraphInterface::try_add_edge], so an edge to a node
+    /// that doesn't exist (or a [NodeRef::New] that doesn't refer to an earlier `AddNode`) fails instead of dangling.
+    /// Applying stops at the first failing command, see [CommandError].
+    pub fn apply_commands(
+        &mut self,
+        commands: impl IntoIterator<Item = GraphCommand<N, E>>,
+    ) -> Result<AppliedCommands, CommandError> {
+        let commands: Vec<GraphCommand<N, E>> = commands.into_iter().collect();
+        let mut applied = AppliedCommands::default();
+        let (node_count, edge_count) =
+            commands
+                .iter()
+                .fold((0, 0), |(n, e), command| match command {
+                    GraphCommand::AddNode(_) => (n + 1, e),
+                    GraphCommand::AddEdge { .. } => (n, e + 1),
+                    _ => (n, e),
+                });
+        self.nodes.reserve(node_count);
+        self.edges.reserve(edge_count);
+        applied.nodes.reserve(node_count);
+        applied.edges.reserve(edge_count);
+
+        for (index, command) in commands.into_iter().enumerate() {
+            let result = match command {
+                GraphCommand::AddNode(data) => {
+                    applied.nodes.push(self.add_node(data));
+                    Ok(())
+                }
+                GraphCommand::AddEdge { from, to, data } => resolve(&applied, from)
+                    .and_then(|from| Ok((from, resolve(&applied, to)?)))
+                    .and_then(|(from, to)| Ok(self.try_add_edge(from, to, data)?))
+                    .map(|id| applied.edges.push(id)),
+                GraphCommand::RemoveNode(node) => {
+                    resolve(&applied, node).and_then(|id| Ok(self.remove_node(id)?))
+                }
+                GraphCommand::RemoveEdge(id) => self.remove_edge(id).map_err(Into::into),
+            };
+            if let Err(source) = result {
+                return Err(CommandError {
+                    index,
+                    source,
+                    applied,
+                });
+            }
+        }
+        Ok(applied)
+    }
+}
+
+fn resolve(applied: &AppliedCommands, node: NodeRef) -> Result<NodeID, CommandErrorKind> {
+    match node {
+        NodeRef::Existing(id) => Ok(id),
+        NodeRef::New(n) => applied
+            .nodes
+            .get(n)
+            .copied()
+            .ok_or(CommandErrorKind::UnknownNewNode(n)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GraphRef;
+
+    #[test]
+    fn test_apply_commands() {
+        let mut graph: Graph<u32, ()> = Graph::new();
+        let existing = graph.add_node(0);
+        let old_edge = graph.add_edge(existing, existing, ());
+
+        // Build the batches on other threads.
+        let batches: Vec<Vec<GraphCommand<u32, ()>>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (1..=2)
+                .map(|batch| {
+                    scope.spawn(move || {
+                        vec![
+                            GraphCommand::AddNode(batch),
+                            GraphCommand::AddEdge {
+                                from: NodeRef::Existing(existing),
+                                to: NodeRef::New(0),
+                                data: (),
+                            },
+                        ]
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+        for batch in batches {
+            graph.apply_commands(batch).unwrap();
+        }
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edges.len(), 3);
+
+        let error = graph
+            .apply_commands([
+                GraphCommand::RemoveEdge(old_edge),
+                GraphCommand::AddNode(3),
+                GraphCommand::AddEdge {
+                    from: NodeRef::New(0),
+                    to: NodeRef::New(1),
+                    data: (),
+                },
+            ])
+            .unwrap_err();
+        assert_eq!(error.index, 2);
+        assert!(matches!(error.source, CommandErrorKind::UnknownNewNode(1)));
+        assert_eq!(error.applied.nodes.len(), 1);
+        assert!(graph.edge(old_edge).is_err());
+        assert_eq!(graph.edges.len(), 2);
+    }
+}
@@ -0,0 +1,525 @@
+//! # Structural comparison of graphs.
+//!
+//! Two graphs built the same way rarely have the same slotmap keys (removals leave free slots and bump versions), so comparing
+//! their slotmaps is fragile. [structural_diff] instead pairs up the nodes of both graphs and compares:
+//!
+//! - the number of nodes and the data of every pair of nodes,
+//! - the outgoing edges of every pair of nodes as multisets of (paired target, data), ignoring [EdgeID]s and the order of the connections.
+//!
+//! This is what [PartialEq] for [Graph] and [CategorizedGraph](crate::CategorizedGraph) uses (the latter also compares the category keys
+//! of every pair of nodes).
+//!
+//! The nodes are first paired by their position in iteration (slot) order, which is all it takes for a clone or a serde round trip.
+//! If that pairing has a difference, they are paired by their structure instead: nodes are grouped by their data, and the groups are
+//! split by the data and groups of the nodes at the other end of their edges, in both directions, until that doesn't split any further
+//! (like the Weisfeiler–Lehman refinement of [signature](crate::signature)). Every group must have as many nodes of both graphs, and
+//! nodes that are still indistinguishable are paired in order, splitting the remaining groups again. So the same graph with its nodes
+//! and edges added in a different order, or with other slots reused, is equal.
+//!
+//! This doesn't search for an isomorphism: a pairing choice is never revisited, so for highly regular graphs (where the refinement
+//! can't tell nodes apart that aren't interchangeable) an existing pairing can be missed and the graphs reported as different.
+//! The refinement compares data with [PartialEq] only, so it is quadratic in the number of nodes in the worst case, and only runs
+//! when the slot order pairing fails. If no pairing is found, the reported difference is the first one in slot order.
+//!
+//! Connections to removed edges are ignored, see [Graph::vacuum].
+//!
+//! [assert_graph_eq!](crate::assert_graph_eq) panics with the first difference when two graphs aren't equal.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//!
+//! let mut graph: Graph<&str, u32> = Graph::new();
+//! let [a, b] = graph.add_nodes(&["a", "b"])[..] else { unreachable!() };
+//! graph.add_edge(a, b, 1);
+//!
+//! let mut compacted = graph.clone();
+//! let temp = compacted.add_node("temp");
+//! compacted.remove_node(temp).unwrap();
+//! compacted.compact();
+//! assert_graph_eq!(graph, compacted);
+//!
+//! // The same graph, built in a different order.
+//! let mut reversed: Graph<&str, u32> = Graph::new();
+//! let [b, a] = reversed.add_nodes(&["b", "a"])[..] else { unreachable!() };
+//! reversed.add_edge(a, b, 1);
+//! assert_graph_eq!(graph, reversed);
+//!
+//! let mut changed = graph.clone();
+//! changed.add_edge(a, a, 2);
+//! assert_ne!(graph, changed);
+//! ```
+
+use crate::hash::HashMap;
+use crate::{outgoing_edges, Graph, GraphRef, NodeID};
+
+/// Returns a description of the first structural difference between two graphs, or `None` if they are equal.
+///
+/// Nodes are paired by slot order if that makes the graphs equal, and by their data and edges otherwise, see the [module documentation](self).
+pub fn structural_diff<A, B>(a: &A, b: &B) -> Option<String>
+where
+    A: GraphRef,
+    B: GraphRef<NodeData = A::NodeData, EdgeData = A::EdgeData>,
+    A::NodeData: PartialEq,
+    A::EdgeData: PartialEq,
+{
+    pair_nodes(a, b, |_| (), |_| ()).err()
+}
+
+/// Pairs up the nodes of two graphs so that paired nodes have the same data, the same label (the category keys) and the same edges.
+///
+/// Returns the nodes of both graphs, paired by position, or the first difference when paired by slot order.
+fn pair_nodes<A, B, L: PartialEq>(
+    a: &A,
+    b: &B,
+    a_label: impl Fn(NodeID) -> L,
+    b_label: impl Fn(NodeID) -> L,
+) -> Result<(Vec<NodeID>, Vec<NodeID>), String>
+where
+    A: GraphRef,
+    B: GraphRef<NodeData = A::NodeData, EdgeData = A::EdgeData>,
+    A::NodeData: PartialEq,
+    A::EdgeData: PartialEq,
+{
+    let (a_nodes, b_nodes): (Vec<NodeID>, Vec<NodeID>) = (a.nodes().collect(), b.nodes().collect());
+    if a_nodes.len() != b_nodes.len() {
+        return Err(format!(
+            "node counts differ: {} != {}",
+            a_nodes.len(),
+            b_nodes.len()
+        ));
+    }
+    // Clones and round trips keep the slot order, so that pairing is tried first.
+    let Some(diff) = diff_paired(a, b, &a_nodes, &b_nodes, &a_label, &b_label) else {
+        return Ok((a_nodes, b_nodes));
+    };
+
+    let labels: Vec<(Option<&A::NodeData>, L)> = a_nodes
+        .iter()
+        .map(|id| (a.node(*id).ok().map(|node| &node.data), a_label(*id)))
+        .chain(
+            b_nodes
+                .iter()
+                .map(|id| (b.node(*id).ok().map(|node| &node.data), b_label(*id))),
+        )
+        .collect();
+    let (a_index, b_index) = (positions(&a_nodes), positions(&b_nodes));
+    let offset = a_nodes.len();
+    let out: Vec<Vec<(Option<usize>, &A::EdgeData)>> = a_nodes
+        .iter()
+        .map(|id| outgoing(a, *id, &a_index))
+        .chain(b_nodes.iter().map(|id| {
+            outgoing(b, *id, &b_index)
+                .into_iter()
+                .map(|(to, data)| (to.map(|to| to + offset), data))
+                .collect()
+        }))
+        .collect();
+
+    if let Some(order) = canonical_order(&labels, &out) {
+        let b_nodes: Vec<NodeID> = order.into_iter().map(|i| b_nodes[i]).collect();
+        if diff_paired(a, b, &a_nodes, &b_nodes, &a_label, &b_label).is_none() {
+            return Ok((a_nodes, b_nodes));
+        }
+    }
+    Err(diff)
+}
+
+/// Returns a description of the first difference between the nodes at the same positions of `a_nodes` and `b_nodes`.
+fn diff_paired<A, B, L: PartialEq>(
+    a: &A,
+    b: &B,
+    a_nodes: &[NodeID],
+    b_nodes: &[NodeID],
+    a_label: impl Fn(NodeID) -> L,
+    b_label: impl Fn(NodeID) -> L,
+) -> Option<String>
+where
+    A: GraphRef,
+    B: GraphRef<NodeData = A::NodeData, EdgeData = A::EdgeData>,
+    A::NodeData: PartialEq,
+    A::EdgeData: PartialEq,
+{
+    let (a_index, b_index) = (positions(a_nodes), positions(b_nodes));
+
+    for (i, (a_id, b_id)) in a_nodes.iter().zip(b_nodes).enumerate() {
+        let (Ok(a_node), Ok(b_node)) = (a.node(*a_id), b.node(*b_id)) else {
+            continue;
+        };
+        if a_node.data != b_node.data {
+            return Some(format!("the data of node {} differs", i));
+        }
+        if a_label(*a_id) != b_label(*b_id) {
+            return Some(format!("the categories of node {} differ", i));
+        }
+
+        let a_edges = outgoing(a, *a_id, &a_index);
+        let mut b_edges: Vec<Option<_>> =
+            outgoing(b, *b_id, &b_index).into_iter().map(Some).collect();
+        if a_edges.len() != b_edges.len() {
+            return Some(format!(
+                "node {} has {} != {} outgoing edges",
+                i,
+                a_edges.len(),
+                b_edges.len()
+            ));
+        }
+        for (to, data) in a_edges {
+            let matching = b_edges.iter_mut().find(|edge| {
+                edge.as_ref()
+                    .is_some_and(|(b_to, b_data)| *b_to == to && data == *b_data)
+            });
+            match matching {
+                Some(edge) => *edge = None,
+                None => {
+                    let to = to.map_or("a missing node".to_string(), |to| format!("node {}", to));
+                    return Some(format!(
+                        "an edge from node {} to {} has no equal counterpart",
+                        i, to
+                    ));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// The position of every node in iteration order.
+fn positions(nodes: &[NodeID]) -> HashMap<NodeID, usize> {
+    nodes.iter().enumerate().map(|(i, id)| (*id, i)).collect()
+}
+
+/// The outgoing edges of a node as (position of the target, data), once per edge.
+fn outgoing<'a, G: GraphRef>(
+    graph: &'a G,
+    id: NodeID,
+    index: &HashMap<NodeID, usize>,
+) -> Vec<(Option<usize>, &'a G::EdgeData)> {
+    outgoing_edges(graph, id)
+        .map(|edge| (index.get(&edge.to).copied(), &edge.data))
+        .collect()
+}
+
+/// Pairs the nodes of two graphs, given as the first and the second half of `labels` and `out` (their outgoing edges), by their structure.
+///
+/// Returns the position in the second half paired with every position in the first half, or `None` if the classes don't match up.
+/// Nodes start out in classes of equal labels, which [refine] splits until it can't anymore. While a class has more than one node
+/// of each graph, the first ones of both are paired in a class of their own and the rest is refined again. That choice isn't
+/// revisited, so a pairing of some highly regular graphs can be missed.
+fn canonical_order<T: PartialEq, E: PartialEq>(
+    labels: &[T],
+    out: &[Vec<(Option<usize>, &E)>],
+) -> Option<Vec<usize>> {
+    let n = labels.len() / 2;
+    let mut incoming: Vec<Vec<(Option<usize>, &E)>> = vec![Vec::new(); labels.len()];
+    for (from, edges) in out.iter().enumerate() {
+        for (to, data) in edges {
+            if let Some(to) = to {
+                incoming[*to].push((Some(from), *data));
+            }
+        }
+    }
+
+    let mut representatives: Vec<usize> = Vec::new();
+    let mut classes = Vec::with_capacity(labels.len());
+    for (i, label) in labels.iter().enumerate() {
+        let class = representatives
+            .iter()
+            .position(|representative| labels[*representative] == *label);
+        classes.push(class.unwrap_or_else(|| {
+            representatives.push(i);
+            representatives.len() - 1
+        }));
+    }
+    let mut count = representatives.len();
+
+    loop {
+        (classes, count) = refine(classes, count, out, &incoming);
+        let mut members = vec![Vec::new(); count];
+        for (i, class) in classes.iter().enumerate() {
+            members[*class].push(i);
+        }
+        // Both graphs need the same number of nodes in every class.
+        if members
+            .iter()
+            .any(|class| class.iter().filter(|i| **i < n).count() * 2 != class.len())
+        {
+            return None;
+        }
+        // The nodes of the first graph come first, so the first node of the second one is in the middle.
+        let Some(ambiguous) = members.iter().find(|class| class.len() > 2) else {
+            break;
+        };
+        let (x, y) = (ambiguous[0], ambiguous[ambiguous.len() / 2]);
+        classes[x] = count;
+        classes[y] = count;
+        count += 1;
+    }
+
+    let mut paired = vec![0; count];
+    for (i, class) in classes[n..].iter().enumerate() {
+        paired[*class] = i;
+    }
+    Some(classes[..n].iter().map(|class| paired[*class]).collect())
+}
+
+/// Splits the classes until nodes in the same class have equal edges (by data and class of the other end), in both directions.
+fn refine<E: PartialEq>(
+    mut classes: Vec<usize>,
+    mut count: usize,
+    out: &[Vec<(Option<usize>, &E)>],
+    incoming: &[Vec<(Option<usize>, &E)>],
+) -> (Vec<usize>, usize) {
+    loop {
+        // The first node and the new class of every part of an old class.
+        let mut parts: Vec<Vec<(usize, usize)>> = vec![Vec::new(); count];
+        let mut next = Vec::with_capacity(classes.len());
+        let mut next_count = 0;
+        for i in 0..classes.len() {
+            let parts = &mut parts[classes[i]];
+            let part = parts.iter().find(|(first, _)| {
+                same_edges(&out[*first], &out[i], &classes)
+                    && same_edges(&incoming[*first], &incoming[i], &classes)
+            });
+            next.push(match part {
+                Some((_, class)) => *class,
+                None => {
+                    parts.push((i, next_count));
+                    next_count += 1;
+                    next_count - 1
+                }
+            });
+        }
+        // Classes are only ever split, so the same count means nothing changed.
+        if next_count == count {
+            return (next, count);
+        }
+        (classes, count) = (next, next_count);
+    }
+}
+
+/// Whether two lists of edges are equal as multisets, comparing their other ends by class.
+fn same_edges<E: PartialEq>(
+    x: &[(Option<usize>, &E)],
+    y: &[(Option<usize>, &E)],
+    classes: &[usize],
+) -> bool {
+    if x.len() != y.len() {
+        return false;
+    }
+    let mut used = vec![false; y.len()];
+    x.iter().all(|(end, data)| {
+        let class = end.map(|end| classes[end]);
+        let found = y.iter().enumerate().position(|(j, (other, other_data))| {
+            !used[j] && other.map(|other| classes[other]) == class && data == other_data
+        });
+        found.map(|j| used[j] = true).is_some()
+    })
+}
+
+/// Graphs that can be compared with [structural_diff] (plus whatever else they store), used by [assert_graph_eq!](crate::assert_graph_eq).
+pub trait StructuralDiff {
+    /// Returns a description of the first difference, or `None` if the graphs are equal.
+    fn structural_diff(&self, other: &Self) -> Option<String>;
+}
+
+impl<N: PartialEq, E: PartialEq> StructuralDiff for Graph<N, E> {
+    fn structural_diff(&self, other: &Self) -> Option<String> {
+        structural_diff(self, other)
+    }
+}
+
+impl<N: PartialEq, E: PartialEq> PartialEq for Graph<N, E> {
+    fn eq(&self, other: &Self) -> bool {
+        structural_diff(self, other).is_none()
+    }
+}
+
+#[cfg(feature = "categories")]
+impl<N: PartialEq, E: PartialEq, K: std::hash::Hash + Eq> StructuralDiff
+    for crate::CategorizedGraph<N, E, K>
+{
+    /// Also compares the categories: paired nodes must have the same category keys.
+    fn structural_diff(&self, other: &Self) -> Option<String> {
+        let (a_keys, b_keys) = (category_keys(self), category_keys(other));
+        pair_nodes(
+            self,
+            other,
+            |id| Keys(a_keys.get(&id).map_or(&[][..], Vec::as_slice)),
+            |id| Keys(b_keys.get(&id).map_or(&[][..], Vec::as_slice)),
+        )
+        .err()
+    }
+}
+
+/// The category keys of every category node.
+#[cfg(feature = "categories")]
+fn category_keys<N, E, K: std::hash::Hash + Eq>(
+    graph: &crate::CategorizedGraph<N, E, K>,
+) -> HashMap<NodeID, Vec<&K>> {
+    let mut keys: HashMap<NodeID, Vec<&K>> = HashMap::default();
+    for (key, id) in graph.categories.iter() {
+        keys.entry(*id).or_default().push(key);
+    }
+    keys
+}
+
+/// The category keys of a node, compared regardless of order.
+#[cfg(feature = "categories")]
+struct Keys<'a, K>(&'a [&'a K]);
+
+#[cfg(feature = "categories")]
+impl<K: Eq> PartialEq for Keys<'_, K> {
+    fn eq(&self, other: &Self) -> bool {
+        let count = |keys: &[&K], key: &K| keys.iter().filter(|other| **other == key).count();
+        self.0.len() == other.0.len()
+            && self
+                .0
+                .iter()
+                .all(|key| count(self.0, key) == count(other.0, key))
+    }
+}
+
+#[cfg(feature = "categories")]
+impl<N: PartialEq, E: PartialEq, K: std::hash::Hash + Eq> PartialEq
+    for crate::CategorizedGraph<N, E, K>
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.structural_diff(other).is_none()
+    }
+}
+
+/// Asserts that two graphs are structurally equal (see [compare](crate::compare)), panicking with their first difference otherwise.
+///
+/// Works with every graph that implements [StructuralDiff], like [Graph] and [CategorizedGraph](crate::CategorizedGraph).
+#[macro_export]
+macro_rules! assert_graph_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        if let Some(diff) = $crate::compare::StructuralDiff::structural_diff(&$left, &$right) {
+            panic!("assertion `left == right` failed for graphs: {}", diff);
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        if let Some(diff) = $crate::compare::StructuralDiff::structural_diff(&$left, &$right) {
+            panic!("assertion `left == right` failed for graphs: {}: {}", diff, format_args!($($arg)+));
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GraphInterface;
+
+    #[test]
+    fn test_structural_diff() {
+        let mut a: Graph<u32, &str> = Graph::new();
+        let nodes = a.add_nodes(&[1, 2, 3]);
+        a.add_edges_with_data(&[
+            (nodes[0], nodes[1], "x"),
+            (nodes[0], nodes[1], "y"),
+            (nodes[2], nodes[2], "loop"),
+        ]);
+
+        // The same graph with different keys and connection order.
+        let mut b: Graph<u32, &str> = Graph::new();
+        let removed = b.add_node(0);
+        b.remove_node(removed).unwrap();
+        let other = b.add_nodes(&[1, 2, 3]);
+        assert_ne!(nodes[0], other[0]);
+        b.add_edges_with_data(&[
+            (other[2], other[2], "loop"),
+            (other[0], other[1], "y"),
+            (other[0], other[1], "x"),
+        ]);
+        let stale = b.add_edge(other[1], other[0], "removed");
+        b.remove_edge(stale).unwrap();
+        assert_eq!(a, b);
+        assert_graph_eq!(a, b, "edges added in a different order");
+
+        b.node_mut(other[1]).unwrap().data = 5;
+        assert_eq!(
+            structural_diff(&a, &b).as_deref(),
+            Some("the data of node 1 differs")
+        );
+        b.node_mut(other[1]).unwrap().data = 2;
+        b.add_edge(other[1], other[0], "back");
+        assert_eq!(
+            structural_diff(&a, &b).as_deref(),
+            Some("node 1 has 0 != 1 outgoing edges")
+        );
+        b.add_node(4);
+        assert_eq!(
+            structural_diff(&a, &b).as_deref(),
+            Some("node counts differ: 3 != 4")
+        );
+    }
+
+    #[test]
+    fn test_structural_diff_pairs_nodes_by_structure() {
+        let mut a: Graph<&str, u32> = Graph::new();
+        let nodes = a.add_nodes(&["x", "y", "y", "y"]);
+        a.add_edges_with_data(&[
+            (nodes[0], nodes[1], 1),
+            (nodes[1], nodes[2], 2),
+            (nodes[2], nodes[3], 2),
+            (nodes[3], nodes[1], 3),
+        ]);
+
+        // The same graph with the nodes and edges added in a different order, and a reused slot.
+        let mut b: Graph<&str, u32> = Graph::new();
+        let removed = b.add_node("removed");
+        let other = b.add_nodes(&["y", "y"]);
+        b.remove_node(removed).unwrap();
+        let [x, y] = b.add_nodes(&["y", "x"])[..] else {
+            unreachable!()
+        };
+        b.add_edges_with_data(&[
+            (other[1], other[0], 3),
+            (other[0], x, 2),
+            (y, other[0], 1),
+            (x, other[1], 2),
+        ]);
+        assert_graph_eq!(a, b);
+
+        // Changing the data of the edge that closes the cycle leaves no pairing.
+        let closing = b.edges.iter().find(|(_, edge)| edge.data == 3).unwrap().0;
+        b.edge_mut(closing).unwrap().data = 2;
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_structural_diff_tells_regular_graphs_apart() {
+        // A 6-cycle and two triangles have the same data and degrees everywhere.
+        let mut cycle: Graph<(), ()> = Graph::new();
+        let nodes = cycle.add_nodes(&[(); 6]);
+        for i in 0..6 {
+            cycle.add_edge(nodes[i], nodes[(i + 1) % 6], ());
+        }
+        let mut triangles: Graph<(), ()> = Graph::new();
+        let nodes = triangles.add_nodes(&[(); 6]);
+        for i in 0..6 {
+            triangles.add_edge(nodes[i], nodes[i / 3 * 3 + (i + 1) % 3], ());
+        }
+        assert_ne!(cycle, triangles);
+
+        // The cycle rotated, so no node stays in place.
+        let mut rotated: Graph<(), ()> = Graph::new();
+        let nodes = rotated.add_nodes(&[(); 6]);
+        for i in (0..6).rev() {
+            rotated.add_edge(nodes[(i + 1) % 6], nodes[(i + 2) % 6], ());
+        }
+        assert_graph_eq!(cycle, rotated);
+    }
+
+    #[test]
+    #[should_panic(expected = "an edge from node 0 to node 1 has no equal counterpart")]
+    fn test_assert_graph_eq_panics() {
+        let mut a: Graph<(), u32> = Graph::new();
+        let nodes = a.add_nodes(&[(); 2]);
+        let mut b = a.clone();
+        a.add_edge(nodes[0], nodes[1], 1);
+        b.add_edge(nodes[0], nodes[1], 2);
+        assert_graph_eq!(a, b);
+    }
+}
@@ -0,0 +1,464 @@
+//! # Building new graphs out of existing ones.
+//!
+//! These functions take any [GraphRef] and return a new [Graph] together with a mapping from the original IDs to the new [NodeID]s,
+//! so results computed on the new graph can be related back to the original one.
+//!
+//! - [complement] connects every ordered pair of distinct nodes that isn't connected in the original.
+//! - [line_graph] has a node for every edge of the original, connected when the edges share an endpoint.
+//! - [cartesian_product] and [tensor_product] combine two graphs into one with a node for every pair of nodes, e.g. to generate grids
+//!   (the cartesian product of two paths) or the state space of two automata running together.
+//! - [coarsen] contracts matched pairs of adjacent nodes into single nodes, the step of multilevel partitioning and layout.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::constructions::{complement, line_graph};
+//!
+//! let mut graph: Graph<&str, &str> = Graph::new();
+//! let [a, b, c] = graph.add_nodes(&["a", "b", "c"])[..] else { unreachable!() };
+//! let ab = graph.add_edge(a, b, "ab");
+//! let bc = graph.add_edge(b, c, "bc");
+//!
+//! // 6 ordered pairs, 2 of which are edges.
+//! let (inverse, _) = complement(&graph);
+//! assert_eq!(inverse.edges.len(), 4);
+//!
+//! let (lines, ids) = line_graph(&graph);
+//! assert_eq!(lines.node_count(), 2);
+//! let shared = lines.node(ids[&ab]).unwrap().connections[0];
+//! assert_eq!(lines.edge(shared).unwrap().data, b);
+//! assert_eq!(lines.node(ids[&bc]).unwrap().data, "bc");
+//! ```
+
+use crate::hash::{HashMap, HashSet};
+use crate::{outgoing_edges, Edge, EdgeID, Graph, GraphInterface, GraphRef, NodeID};
+
+/// Returns the IDs of the edges of `graph` that exist and start at an existing node, each once.
+pub(crate) fn edge_ids<G: GraphRef>(graph: &G) -> Vec<EdgeID> {
+    let mut edges = Vec::new();
+    for id in graph.nodes() {
+        edges.extend(outgoing_edges(graph, id).map(|edge| edge.id));
+    }
+    edges
+}
+
+/// Copies the nodes of `graph` into a new graph, returning it and the mapping from old to new IDs.
+pub(crate) fn copy_nodes<G, E>(graph: &G) -> (Graph<G::NodeData, E>, HashMap<NodeID, NodeID>)
+where
+    G: GraphRef,
+    G::NodeData: Clone,
+{
+    let mut copy = Graph::with_capacity(graph.node_count(), 0);
+    let mut ids = HashMap::with_capacity_and_hasher(graph.node_count(), Default::default());
+    for id in graph.nodes() {
+        if let Ok(node) = graph.node(id) {
+            ids.insert(id, copy.add_node(node.data.clone()));
+        }
+    }
+    (copy, ids)
+}
+
+/// Returns the complement of `graph` and the mapping from its node IDs to the new ones.
+///
+/// The complement has the same nodes (with cloned data) and an edge `u -> v` for every pair of distinct nodes for which `graph` has no edge `u -> v`.
+/// There are no self-loops. This is O(n²), so it's meant for small or dense graphs.
+pub fn complement<G>(graph: &G) -> (Graph<G::NodeData, ()>, HashMap<NodeID, NodeID>)
+where
+    G: GraphRef,
+    G::NodeData: Clone,
+{
+    let (mut complement, ids) = copy_nodes(graph);
+    let adjacent: HashSet<(NodeID, NodeID)> = edge_ids(graph)
+        .into_iter()
+        .filter_map(|id| graph.edge(id).ok())
+        .map(|edge| (edge.from, edge.to))
+        .collect();
+
+    let nodes: Vec<NodeID> = graph.nodes().filter(|id| ids.contains_key(id)).collect();
+    for from in &nodes {
+        for to in &nodes {
+            if from != to && !adjacent.contains(&(*from, *to)) {
+                complement.add_edge(ids[from], ids[to], ());
+            }
+        }
+    }
+    (complement, ids)
+}
+
+/// Returns the line graph of `graph` and the mapping from its edge IDs to the new node IDs.
+///
+/// Every edge of `graph` becomes a node (with the cloned edge data), and two of them are connected when the edges share an endpoint,
+/// regardless of direction. Each such pair is connected once, from the edge found first to the other, with the shared node (an ID in `graph`) as data.
+pub fn line_graph<G>(graph: &G) -> (Graph<G::EdgeData, NodeID>, HashMap<EdgeID, NodeID>)
+where
+    G: GraphRef,
+    G::EdgeData: Clone,
+{
+    let edges = edge_ids(graph);
+    let mut lines = Graph::with_capacity(edges.len(), 0);
+    let mut ids = HashMap::with_capacity_and_hasher(edges.len(), Default::default());
+    for id in &edges {
+        let edge = graph
+            .edge(*id)
+            .expect("edge_ids only returns existing edges");
+        ids.insert(*id, lines.add_node(edge.data.clone()));
+    }
+
+    let mut connected = HashSet::default();
+    for shared in graph.nodes() {
+        let Ok(node) = graph.node(shared) else {
+            continue;
+        };
+        let mut incident: Vec<NodeID> = node
+            .connections
+            .iter()
+            .filter_map(|edge| ids.get(edge).copied())
+            .collect();
+        // Self-loops are listed twice.
+        incident.sort();
+        incident.dedup();
+        for (i, a) in incident.iter().enumerate() {
+            for b in &incident[i + 1..] {
+                if connected.insert((*a, *b)) {
+                    lines.add_edge(*a, *b, shared);
+                }
+            }
+        }
+    }
+    (lines, ids)
+}
+
+/// The data of an edge of a [cartesian_product]: the edge of the left or the right graph it comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ProductEdge<L, R> {
+    Left(L),
+    Right(R),
+}
+
+/// Node IDs of the two graphs of a product, mapped to the node of the product that stands for the pair.
+pub type ProductIds = HashMap<(NodeID, NodeID), NodeID>;
+
+/// The product of two graphs with edge data `E`, and the mapping from node pairs to its nodes.
+pub type Product<L, R, E> = (
+    Graph<(<L as GraphRef>::NodeData, <R as GraphRef>::NodeData), E>,
+    ProductIds,
+);
+
+/// Adds a node for every pair of nodes of `left` and `right`, in the order of `left` then `right`.
+fn product_nodes<L, R, E>(left: &L, right: &R) -> Product<L, R, E>
+where
+    L: GraphRef,
+    R: GraphRef,
+    L::NodeData: Clone,
+    R::NodeData: Clone,
+{
+    let count = left.node_count() * right.node_count();
+    let mut product = Graph::with_capacity(count, 0);
+    let mut ids = HashMap::with_capacity_and_hasher(count, Default::default());
+    for l in left.nodes() {
+        let Ok(l_node) = left.node(l) else {
+            continue;
+        };
+        for r in right.nodes() {
+            if let Ok(r_node) = right.node(r) {
+                let data = (l_node.data.clone(), r_node.data.clone());
+                ids.insert((l, r), product.add_node(data));
+            }
+        }
+    }
+    (product, ids)
+}
+
+/// Returns the cartesian product of two graphs and the mapping from node pairs to its nodes.
+///
+/// There's a node `(l, r)` for every node `l` of `left` and `r` of `right`. Every edge `l -> l'` of `left` is copied to `(l, r) -> (l', r)`
+/// for every `r`, and every edge `r -> r'` of `right` to `(l, r) -> (l, r')` for every `l`, with [ProductEdge] telling them apart.
+/// The product of two paths is a grid.
+pub fn cartesian_product<L, R>(
+    left: &L,
+    right: &R,
+) -> Product<L, R, ProductEdge<L::EdgeData, R::EdgeData>>
+where
+    L: GraphRef,
+    R: GraphRef,
+    L::NodeData: Clone,
+    R::NodeData: Clone,
+    L::EdgeData: Clone,
+    R::EdgeData: Clone,
+{
+    let (mut product, ids) = product_nodes(left, right);
+    let (left_edges, right_edges) = (edge_ids(left), edge_ids(right));
+    let (left_nodes, right_nodes): (Vec<NodeID>, Vec<NodeID>) =
+        (left.nodes().collect(), right.nodes().collect());
+
+    for edge in left_edges.iter().filter_map(|id| left.edge(*id).ok()) {
+        for r in &right_nodes {
+            if let (Some(from), Some(to)) = (ids.get(&(edge.from, *r)), ids.get(&(edge.to, *r))) {
+                product.add_edge(*from, *to, ProductEdge::Left(edge.data.clone()));
+            }
+        }
+    }
+    for edge in right_edges.iter().filter_map(|id| right.edge(*id).ok()) {
+        for l in &left_nodes {
+            if let (Some(from), Some(to)) = (ids.get(&(*l, edge.from)), ids.get(&(*l, edge.to))) {
+                product.add_edge(*from, *to, ProductEdge::Right(edge.data.clone()));
+            }
+        }
+    }
+    (product, ids)
+}
+
+/// Returns the tensor (categorical) product of two graphs and the mapping from node pairs to its nodes.
+///
+/// There's a node `(l, r)` for every node `l` of `left` and `r` of `right`, and an edge `(l, r) -> (l', r')` with the data of both edges
+/// for every edge `l -> l'` of `left` and `r -> r'` of `right`, i.e. both graphs take a step at the same time.
+pub fn tensor_product<L, R>(left: &L, right: &R) -> Product<L, R, (L::EdgeData, R::EdgeData)>
+where
+    L: GraphRef,
+    R: GraphRef,
+    L::NodeData: Clone,
+    R::NodeData: Clone,
+    L::EdgeData: Clone,
+    R::EdgeData: Clone,
+{
+    let (mut product, ids) = product_nodes(left, right);
+    let right_edges: Vec<_> = edge_ids(right)
+        .into_iter()
+        .filter_map(|id| right.edge(id).ok())
+        .collect();
+
+    for l_edge in edge_ids(left)
+        .into_iter()
+        .filter_map(|id| left.edge(id).ok())
+    {
+        for r_edge in &right_edges {
+            let from = ids.get(&(l_edge.from, r_edge.from));
+            let to = ids.get(&(l_edge.to, r_edge.to));
+            if let (Some(from), Some(to)) = (from, to) {
+                let data = (l_edge.data.clone(), r_edge.data.clone());
+                product.add_edge(*from, *to, data);
+            }
+        }
+    }
+    (product, ids)
+}
+
+/// Returns a coarser version of `graph` in which matched pairs of adjacent nodes are contracted into one node, and the mapping
+/// (the projection) from every node of `graph` to its node in the coarse graph.
+///
+/// The nodes are visited in order, and `matching` is called for every node that isn't matched yet with its unmatched neighbors
+/// (in either direction, except itself) and an edge to each; it returns the neighbor to match the node with, or `None` to keep it alone.
+/// Returning a node that isn't one of the candidates also keeps it alone. The data of a matched pair is `merge_nodes` of both.
+///
+/// Edges inside a contracted pair (and self-loops) are dropped. The other edges keep their direction, and all edges between the same
+/// two coarse nodes in the same direction become one, with the data of the first and `merge_edges` called with each other one, e.g. to sum weights.
+///
+/// # Example
+/// ```
+/// use fast_graph::*;
+/// use fast_graph::constructions::coarsen;
+///
+/// let mut graph: Graph<u32, u32> = Graph::new();
+/// let nodes = graph.add_nodes(&[1, 1, 1, 1]);
+/// graph.add_edges_with_data(&[(nodes[0], nodes[1], 5), (nodes[1], nodes[2], 1), (nodes[2], nodes[3], 5), (nodes[0], nodes[3], 1)]);
+///
+/// // Heavy-edge matching, summing node and edge weights.
+/// let (coarse, projection) = coarsen(
+///     &graph,
+///     |_, candidates| candidates.iter().max_by_key(|(_, edge)| edge.data).map(|(id, _)| *id),
+///     |a, b| a + b,
+///     |total, weight| *total += weight,
+/// );
+/// assert_eq!(coarse.node_count(), 2);
+/// assert_eq!(projection[&nodes[0]], projection[&nodes[1]]);
+/// assert_eq!(coarse.node(projection[&nodes[2]]).unwrap().data, 2);
+/// // Both light edges go from the first pair to the second.
+/// assert_eq!(coarse.edge_triples().map(|(_, _, weight)| *weight).collect::<Vec<_>>(), vec![2]);
+/// ```
+pub fn coarsen<G>(
+    graph: &G,
+    mut matching: impl FnMut(NodeID, &[(NodeID, &Edge<G::EdgeData>)]) -> Option<NodeID>,
+    mut merge_nodes: impl FnMut(&G::NodeData, &G::NodeData) -> G::NodeData,
+    mut merge_edges: impl FnMut(&mut G::EdgeData, &G::EdgeData),
+) -> (Graph<G::NodeData, G::EdgeData>, HashMap<NodeID, NodeID>)
+where
+    G: GraphRef,
+    G::NodeData: Clone,
+    G::EdgeData: Clone,
+{
+    let mut coarse = Graph::with_capacity(graph.node_count(), 0);
+    let mut projection: HashMap<NodeID, NodeID> =
+        HashMap::with_capacity_and_hasher(graph.node_count(), Default::default());
+    for id in graph.nodes() {
+        let Ok(node) = graph.node(id) else {
+            continue;
+        };
+        if projection.contains_key(&id) {
+            continue;
+        }
+        let mut candidates: Vec<(NodeID, &Edge<G::EdgeData>)> = Vec::new();
+        for edge in node
+            .connections
+            .iter()
+            .filter_map(|edge| graph.edge(*edge).ok())
+        {
+            let other = if edge.from == id { edge.to } else { edge.from };
+            if other != id && !projection.contains_key(&other) && graph.node(other).is_ok() {
+                candidates.push((other, edge));
+            }
+        }
+        let partner = matching(id, &candidates)
+            .filter(|partner| candidates.iter().any(|(other, _)| other == partner));
+        match partner.and_then(|partner| Some((partner, graph.node(partner).ok()?))) {
+            Some((partner, other)) => {
+                let super_node = coarse.add_node(merge_nodes(&node.data, &other.data));
+                projection.insert(id, super_node);
+                projection.insert(partner, super_node);
+            }
+            None => {
+                projection.insert(id, coarse.add_node(node.data.clone()));
+            }
+        }
+    }
+
+    let mut merged: HashMap<(NodeID, NodeID), EdgeID> = HashMap::default();
+    for edge in edge_ids(graph)
+        .into_iter()
+        .filter_map(|id| graph.edge(id).ok())
+    {
+        let (Some(from), Some(to)) = (projection.get(&edge.from), projection.get(&edge.to)) else {
+            continue;
+        };
+        if from == to {
+            continue;
+        }
+        match merged.get(&(*from, *to)) {
+            Some(id) => merge_edges(&mut coarse.edges[*id].data, &edge.data),
+            None => {
+                let id = coarse.add_edge(*from, *to, edge.data.clone());
+                merged.insert((*from, *to), id);
+            }
+        }
+    }
+    (coarse, projection)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_complement_and_line_graph() {
+        let mut graph: Graph<u32, u32> = Graph::new();
+        let nodes = graph.add_nodes(&[0, 1, 2, 3]);
+        // A star around node 0 plus a self-loop and a parallel edge.
+        let spokes = [
+            graph.add_edge(nodes[0], nodes[1], 1),
+            graph.add_edge(nodes[2], nodes[0], 2),
+            graph.add_edge(nodes[0], nodes[3], 3),
+        ];
+        let parallel = graph.add_edge(nodes[0], nodes[1], 4);
+        let self_loop = graph.add_edge(nodes[3], nodes[3], 5);
+
+        let (inverse, ids) = complement(&graph);
+        assert_eq!(inverse.edges.len(), 4 * 3 - 3);
+        assert!(inverse.edges.values().all(|edge| edge.from != edge.to));
+        assert_eq!(inverse.node(ids[&nodes[2]]).unwrap().data, 2);
+
+        let (lines, ids) = line_graph(&graph);
+        assert_eq!(lines.node_count(), 5);
+        // The 4 edges at node 0 are pairwise adjacent (6 pairs), and the loop touches the spoke to node 3.
+        assert_eq!(lines.edges.len(), 7);
+        let degree = |edge: EdgeID| lines.node(ids[&edge]).unwrap().connections.len();
+        assert_eq!(degree(spokes[2]), 4);
+        assert_eq!(degree(parallel), 3);
+        assert_eq!(degree(self_loop), 1);
+        assert_eq!(lines.node(ids[&self_loop]).unwrap().data, 5);
+    }
+
+    #[test]
+    fn test_products() {
+        // Two paths of 3 and 2 nodes.
+        let mut path3: Graph<u8, char> = Graph::new();
+        let a = path3.add_nodes(&[0, 1, 2]);
+        path3.add_edge(a[0], a[1], 'x');
+        path3.add_edge(a[1], a[2], 'y');
+        let mut path2: Graph<&str, u32> = Graph::new();
+        let b = path2.add_nodes(&["p", "q"]);
+        path2.add_edge(b[0], b[1], 7);
+
+        let (grid, ids) = cartesian_product(&path3, &path2);
+        assert_eq!(grid.node_count(), 6);
+        // 2 edges per row times 2 rows, plus 1 edge per column times 3 columns.
+        assert_eq!(grid.edges.len(), 7);
+        let corner = grid.node(ids[&(a[1], b[0])]).unwrap();
+        assert_eq!(corner.data, (1, "p"));
+        assert_eq!(corner.connections.len(), 3);
+        let rights = grid
+            .edges
+            .values()
+            .filter(|edge| edge.data == ProductEdge::Right(7))
+            .count();
+        assert_eq!(rights, 3);
+
+        let (tensor, ids) = tensor_product(&path3, &path2);
+        assert_eq!(tensor.edges.len(), 2);
+        let edge = first_edge(&tensor, ids[&(a[0], b[0])]);
+        assert_eq!((edge.to, edge.data), (ids[&(a[1], b[1])], ('x', 7)));
+    }
+
+    #[test]
+    fn test_coarsen() {
+        // A path 0 - 1 - 2 - 3 - 4 with a parallel edge and a backwards edge.
+        let mut graph: Graph<u32, u32> = Graph::new();
+        let nodes = graph.add_nodes(&[1, 2, 3, 4, 5]);
+        graph.add_edges_with_data(&[
+            (nodes[0], nodes[1], 1),
+            (nodes[1], nodes[2], 2),
+            (nodes[1], nodes[2], 3),
+            (nodes[3], nodes[2], 4),
+            (nodes[3], nodes[4], 5),
+            (nodes[4], nodes[4], 6),
+        ]);
+
+        // Matches every node with its first candidate, except node 2 which asks for the already matched node 0.
+        let (coarse, projection) = coarsen(
+            &graph,
+            |id, candidates| {
+                if id == nodes[2] {
+                    Some(nodes[0])
+                } else {
+                    candidates.first().map(|(other, _)| *other)
+                }
+            },
+            |a, b| a * 10 + b,
+            |total, weight| *total += weight,
+        );
+        assert_eq!(projection.len(), 5);
+        assert_eq!(coarse.node_count(), 3);
+        assert_eq!(projection[&nodes[0]], projection[&nodes[1]]);
+        assert_eq!(projection[&nodes[3]], projection[&nodes[4]]);
+        assert_eq!(coarse.node(projection[&nodes[0]]).unwrap().data, 12);
+        assert_eq!(coarse.node(projection[&nodes[2]]).unwrap().data, 3);
+        assert_eq!(coarse.node(projection[&nodes[3]]).unwrap().data, 45);
+
+        // The edges inside pairs and the self-loop are dropped, the parallel edges are summed.
+        let mut edges: Vec<(NodeID, NodeID, u32)> = coarse
+            .edge_triples()
+            .map(|(from, to, data)| (from, to, *data))
+            .collect();
+        edges.sort_by_key(|edge| edge.2);
+        assert_eq!(
+            edges,
+            vec![
+                (projection[&nodes[3]], projection[&nodes[2]], 4),
+                (projection[&nodes[1]], projection[&nodes[2]], 5)
+            ]
+        );
+    }
+
+    fn first_edge<N, E>(graph: &Graph<N, E>, from: NodeID) -> &crate::Edge<E> {
+        let id = graph.node(from).unwrap().connections[0];
+        graph.edge(id).unwrap()
+    }
+}
@@ -0,0 +1,136 @@
+//! # Diagnosing stale IDs.
+//!
+//! A [NodeID] or [EdgeID] is a slot index plus a version. When an item is removed its slot is freed, and when the slot is reused for a new item
+//! the version is bumped, so an old ID never resolves to the new item (the [ABA problem](https://en.wikipedia.org/wiki/ABA_problem)).
+//! That makes lookups safe, but "not found" alone doesn't say much. [Graph::diagnose_node] and [Graph::diagnose_edge] explain why an ID doesn't resolve.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::diagnostics::IdStatus;
+//!
+//! let mut graph: Graph<&str, ()> = Graph::new();
+//! let old = graph.add_node("old");
+//! graph.remove_node(old).unwrap();
+//! assert_eq!(graph.diagnose_node(old), IdStatus::Vacant);
+//!
+//! // The freed slot is reused for the next node, with a newer version.
+//! let new = graph.add_node("new");
+//! assert!(!graph.contains_node(old));
+//! assert_eq!(graph.diagnose_node(old), IdStatus::VersionMismatch { occupant: new });
+//! ```
+
+use std::fmt;
+
+use slotmap::{Key, SlotMap};
+
+use crate::{EdgeID, Graph, NodeID};
+
+/// Why an ID does or doesn't resolve in a graph, see the [module documentation](self).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdStatus<K> {
+    /// The ID refers to an existing item.
+    Live,
+    /// The ID is the null (default) key, which never refers to an item.
+    Null,
+    /// The slot is empty: the item was removed and the slot hasn't been reused yet, or the ID never belonged to this graph.
+    Vacant,
+    /// The slot has been reused by a newer item, `occupant`. The ID's item was removed, or the ID is from another graph.
+    VersionMismatch { occupant: K },
+}
+
+impl<K: fmt::Debug> fmt::Display for IdStatus<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdStatus::Live => write!(f, "the ID is live"),
+            IdStatus::Null => write!(f, "the ID is the null key"),
+            IdStatus::Vacant => write!(
+                f,
+                "the slot is empty (removed, or the ID is from another graph)"
+            ),
+            IdStatus::VersionMismatch { occupant } => write!(
+                f,
+                "the slot has been reused by {:?} (removed, or the ID is from another graph)",
+                occupant
+            ),
+        }
+    }
+}
+
+/// Returns the slot index of a key (the lower 32 bits of its FFI representation, the upper 32 are the version).
+fn slot<K: Key>(key: K) -> u32 {
+    key.data().as_ffi() as u32
+}
+
+/// Diagnoses a key, scanning the slotmap for the occupant of its slot if it isn't live (O(n)).
+pub(crate) fn diagnose<K: Key, V>(map: &SlotMap<K, V>, id: K) -> IdStatus<K> {
+    if map.contains_key(id) {
+        return IdStatus::Live;
+    }
+    if id.is_null() {
+        return IdStatus::Null;
+    }
+    let index = slot(id);
+    match map.keys().find(|key| slot(*key) == index) {
+        Some(occupant) => IdStatus::VersionMismatch { occupant },
+        None => IdStatus::Vacant,
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    /// Explains why a [NodeID] does or doesn't resolve, see [IdStatus]. This is O(n) for IDs that aren't live, so it's meant for debugging.
+    pub fn diagnose_node(&self, id: NodeID) -> IdStatus<NodeID> {
+        diagnose(&self.nodes, id)
+    }
+
+    /// Explains why an [EdgeID] does or doesn't resolve, see [IdStatus]. This is O(n) for IDs that aren't live, so it's meant for debugging.
+    pub fn diagnose_edge(&self, id: EdgeID) -> IdStatus<EdgeID> {
+        diagnose(&self.edges, id)
+    }
+}
+
+#[cfg(feature = "categories")]
+impl<N, E, K> crate::CategorizedGraph<N, E, K> {
+    /// Explains why a [NodeID] does or doesn't resolve, see [IdStatus]. This is O(n) for IDs that aren't live, so it's meant for debugging.
+    pub fn diagnose_node(&self, id: NodeID) -> IdStatus<NodeID> {
+        diagnose(&self.nodes, id)
+    }
+
+    /// Explains why an [EdgeID] does or doesn't resolve, see [IdStatus]. This is O(n) for IDs that aren't live, so it's meant for debugging.
+    pub fn diagnose_edge(&self, id: EdgeID) -> IdStatus<EdgeID> {
+        diagnose(&self.edges, id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GraphInterface, GraphRef};
+    use slotmap::KeyData;
+
+    #[test]
+    fn test_diagnose() {
+        let mut graph: Graph<u32, ()> = Graph::new();
+        let a = graph.add_node(1);
+        let b = graph.add_node(2);
+        let edge = graph.add_edge(a, b, ());
+
+        assert_eq!(graph.diagnose_node(a), IdStatus::Live);
+        assert!(graph.contains_node(a) && graph.contains_edge(edge));
+        assert_eq!(graph.diagnose_node(NodeID::default()), IdStatus::Null);
+        assert_eq!(
+            graph.diagnose_node(NodeID::from(KeyData::from_ffi((1 << 32) | 100))),
+            IdStatus::Vacant
+        );
+
+        graph.remove_node(b).unwrap();
+        assert!(!graph.contains_edge(edge));
+        assert_eq!(graph.diagnose_edge(edge), IdStatus::Vacant);
+        let c = graph.add_node(3);
+        assert_eq!(
+            graph.diagnose_node(b),
+            IdStatus::VersionMismatch { occupant: c }
+        );
+        assert!(graph.diagnose_node(b).to_string().contains("reused"));
+    }
+}
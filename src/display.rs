@@ -0,0 +1,209 @@
+//! # Human readable rendering of graphs.
+//!
+//! The [Debug](std::fmt::Debug) output of a graph is a dump of its slotmaps, which is hard to read for anything but tiny graphs.
+//! [display_with] renders a compact adjacency listing instead, one line per node with its outgoing edges (`A -> B, C`),
+//! using closures for the node (and optionally edge) labels. [Graph] and [CategorizedGraph](crate::CategorizedGraph) implement
+//! [Display](fmt::Display) with this listing when their node data implements it.
+//!
+//! [ascii_tree] draws the nodes reachable from a root as a tree, which is handy for hierarchies.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::display::{ascii_tree, display_with};
+//!
+//! let mut graph: Graph<&str, u32> = Graph::new();
+//! let [root, a, b, c] = graph.add_nodes(&["root", "a", "b", "c"])[..] else { unreachable!() };
+//! graph.add_edge(root, a, 1);
+//! graph.add_edge(root, b, 2);
+//! graph.add_edge(a, c, 3);
+//!
+//! assert_eq!(graph.to_string(), "root -> a, b\na -> c\nb\nc\n");
+//! assert_eq!(
+//!     display_with(&graph, |_, data| data.to_uppercase())
+//!         .edge_labels(|weight| weight.to_string())
+//!         .to_string(),
+//!     "ROOT -> A [1], B [2]\nA -> C [3]\nB\nC\n"
+//! );
+//! assert_eq!(ascii_tree(&graph, root, |_, data| data.to_string()), "root\n├── a\n│   └── c\n└── b\n");
+//! ```
+
+use std::fmt;
+
+use crate::algorithms::Visitable;
+use crate::{outgoing_edges, Graph, GraphRef, NodeID};
+
+type EdgeLabel<'a, E> = Box<dyn Fn(&E) -> String + 'a>;
+
+/// A [Display](fmt::Display)able adjacency listing of a graph, see [display_with].
+pub struct GraphDisplay<'a, G: GraphRef, L> {
+    graph: &'a G,
+    node_label: L,
+    edge_label: Option<EdgeLabel<'a, G::EdgeData>>,
+}
+
+/// Returns a [Display](fmt::Display)able adjacency listing of `graph`, labelling the nodes with `node_label`.
+///
+/// Every node gets one line with the labels of the targets of its outgoing edges, e.g. `A -> B, C`, or just `A` if it has none.
+pub fn display_with<G, L>(graph: &G, node_label: L) -> GraphDisplay<'_, G, L>
+where
+    G: GraphRef,
+    L: Fn(NodeID, &G::NodeData) -> String,
+{
+    GraphDisplay {
+        graph,
+        node_label,
+        edge_label: None,
+    }
+}
+
+impl<'a, G: GraphRef, L> GraphDisplay<'a, G, L> {
+    /// Also labels the edges, the label is shown in brackets after the target: `A -> B [label]`.
+    pub fn edge_labels(mut self, edge_label: impl Fn(&G::EdgeData) -> String + 'a) -> Self {
+        self.edge_label = Some(Box::new(edge_label));
+        self
+    }
+}
+
+impl<G, L> fmt::Display for GraphDisplay<'_, G, L>
+where
+    G: GraphRef,
+    L: Fn(NodeID, &G::NodeData) -> String,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = |id: NodeID| match self.graph.node(id) {
+            Ok(node) => (self.node_label)(id, &node.data),
+            Err(_) => format!("{:?}", id),
+        };
+        for id in self.graph.nodes() {
+            write!(f, "{}", label(id))?;
+            for (i, edge) in outgoing_edges(self.graph, id).enumerate() {
+                write!(
+                    f,
+                    "{}{}",
+                    if i == 0 { " -> " } else { ", " },
+                    label(edge.to)
+                )?;
+                if let Some(edge_label) = &self.edge_label {
+                    write!(f, " [{}]", edge_label(&edge.data))?;
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<N: fmt::Display, E> fmt::Display for Graph<N, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        display_with(self, |_, data| data.to_string()).fmt(f)
+    }
+}
+
+#[cfg(feature = "categories")]
+impl<N: fmt::Display, E, K> fmt::Display for crate::CategorizedGraph<N, E, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        display_with(self, |_, data| data.to_string()).fmt(f)
+    }
+}
+
+/// Draws the nodes reachable from `root` over outgoing edges as a tree, labelling them with `node_label`.
+///
+/// A node reachable in several ways (a DAG or a cycle) is only expanded the first time, later occurrences are marked with `(*)`.
+/// Returns an empty string if `root` doesn't exist.
+pub fn ascii_tree<G, L>(graph: &G, root: NodeID, node_label: L) -> String
+where
+    G: GraphRef,
+    L: Fn(NodeID, &G::NodeData) -> String,
+{
+    let Ok(node) = graph.node(root) else {
+        return String::new();
+    };
+    let mut out = node_label(root, &node.data);
+    out.push('\n');
+    let mut visited = graph.visit_map();
+    visited.visit(root);
+
+    let children =
+        |id: NodeID| -> Vec<NodeID> { outgoing_edges(graph, id).map(|edge| edge.to).collect() };
+    // The children left to draw on every level and the prefix of their lines. An explicit stack, so long chains don't overflow the call stack.
+    let mut stack: Vec<(Vec<NodeID>, usize, String)> = vec![(children(root), 0, String::new())];
+    while let Some((siblings, next, prefix)) = stack.last_mut() {
+        let Some(child) = siblings.get(*next).copied() else {
+            stack.pop();
+            continue;
+        };
+        *next += 1;
+        let last = *next == siblings.len();
+        let Ok(child_node) = graph.node(child) else {
+            continue;
+        };
+        out.push_str(prefix);
+        out.push_str(if last { "└── " } else { "├── " });
+        out.push_str(&node_label(child, &child_node.data));
+        if visited.visit(child) {
+            out.push('\n');
+            let prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+            stack.push((children(child), 0, prefix));
+        } else {
+            out.push_str(" (*)\n");
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GraphInterface;
+
+    #[test]
+    fn test_ascii_tree_marks_repeated_nodes() {
+        let mut graph: Graph<&str, ()> = Graph::new();
+        let [a, b, c, d] = graph.add_nodes(&["a", "b", "c", "d"])[..] else {
+            unreachable!()
+        };
+        graph.add_edges(&[(a, b), (a, c), (b, d), (c, d), (d, a)]);
+
+        let label = |_, data: &&str| data.to_string();
+        assert_eq!(
+            ascii_tree(&graph, a, label),
+            "a\n├── b\n│   └── d\n│       └── a (*)\n└── c\n    └── d (*)\n"
+        );
+        graph.remove_node(d).unwrap();
+        assert_eq!(graph.to_string(), "a -> b, c\nb\nc\n");
+        assert_eq!(ascii_tree(&graph, d, label), "");
+    }
+
+    #[test]
+    fn test_self_loop_is_drawn_once() {
+        let mut graph: Graph<&str, ()> = Graph::new();
+        let [a, b] = graph.add_nodes(&["a", "b"])[..] else {
+            unreachable!()
+        };
+        graph.add_edges(&[(a, a), (a, b)]);
+
+        assert_eq!(graph.to_string(), "a -> a, b\nb\n");
+        assert_eq!(
+            ascii_tree(&graph, a, |_, data| data.to_string()),
+            "a\n├── a (*)\n└── b\n"
+        );
+    }
+
+    #[test]
+    fn test_ascii_tree_of_long_chain() {
+        let mut graph: Graph<(), ()> = Graph::new();
+        let nodes = graph.add_nodes(&[(); 3000]);
+        for pair in nodes.windows(2) {
+            graph.add_edge(pair[0], pair[1], ());
+        }
+        // A recursive walk needs a frame per level, which doesn't fit into this stack.
+        let tree = std::thread::Builder::new()
+            .stack_size(128 * 1024)
+            .spawn(move || ascii_tree(&graph, nodes[0], |_, _| "n".to_string()))
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(tree.lines().count(), 3000);
+    }
+}
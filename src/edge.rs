@@ -21,9 +21,26 @@ impl EdgeID {
     pub fn to_u64(&self) -> u64 {
         self.0.as_ffi()
     }
+
+    /// Converts an integer from [EdgeID::to_u64] back to an ID without checking it, so the ID may not resolve in any graph.
+    /// Hidden with the `strict_ids` feature, use [EdgeID::try_from_u64] instead.
+    #[cfg(not(feature = "strict_ids"))]
     pub fn from_u64(id: u64) -> Self {
         EdgeID::from(KeyData::from_ffi(id))
     }
+
+    /// Converts an integer from [EdgeID::to_u64] back to an ID, checking that it round-trips and that the edge exists in `graph`.
+    ///
+    /// Returns [GraphError::InvalidId] if the integer can't be an ID, and [GraphError::EdgeNotFound] if the edge doesn't exist
+    /// (see [Graph::diagnose_edge] for why).
+    pub fn try_from_u64<G: GraphRef + ?Sized>(graph: &G, id: u64) -> Result<Self, GraphError> {
+        let key = EdgeID::from(KeyData::from_ffi(id));
+        if key.to_u64() != id {
+            return Err(GraphError::InvalidId(id));
+        }
+        graph.edge(key)?;
+        Ok(key)
+    }
 }
 
 /// # A struct representing an edge in the graph.
@@ -0,0 +1,135 @@
+//! # Random graphs for fuzzing and property tests (requires the `arbitrary` and/or `proptest` feature).
+//!
+//! With the `arbitrary` feature [Graph] implements [arbitrary::Arbitrary], so it can be used as (part of) the input of a
+//! [cargo fuzz](https://github.com/rust-fuzz/cargo-fuzz) target. With the `proptest` feature [arb_graph] returns a
+//! [proptest] strategy for graphs.
+//!
+//! The generated graphs are valid: every edge connects two existing nodes. Self-loops and parallel edges do occur.
+//! Graphs generated by [arbitrary] also have some nodes removed again, so they contain reused slots and connections to removed edges
+//! (see [Graph::vacuum]), like graphs that have been edited for a while.
+//!
+//! # Example
+//! ```
+//! # #[cfg(feature = "proptest")]
+//! # {
+//! use fast_graph::*;
+//! use fast_graph::fuzzing::arb_graph;
+//! use proptest::prelude::*;
+//!
+//! proptest!(|(graph in arb_graph(any::<u8>(), Just(()), 16, 32))| {
+//!     for edge in graph.edges.values() {
+//!         prop_assert!(graph.node(edge.from).is_ok() && graph.node(edge.to).is_ok());
+//!     }
+//! });
+//! # }
+//! ```
+
+use crate::{Graph, GraphInterface};
+
+#[cfg(feature = "arbitrary")]
+impl<'a, N: arbitrary::Arbitrary<'a>, E: arbitrary::Arbitrary<'a>> arbitrary::Arbitrary<'a>
+    for Graph<N, E>
+{
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut graph = Graph::new();
+        let mut nodes = Vec::new();
+        for _ in 0..u.arbitrary_len::<N>()? {
+            nodes.push(graph.add_node(N::arbitrary(u)?));
+        }
+        if nodes.is_empty() {
+            return Ok(graph);
+        }
+        for _ in 0..u.arbitrary_len::<(u32, u32, E)>()? {
+            let from = nodes[u.choose_index(nodes.len())?];
+            let to = nodes[u.choose_index(nodes.len())?];
+            graph.add_edge(from, to, E::arbitrary(u)?);
+        }
+        // Remove about one in eight nodes (with their edges), and reuse some of the freed slots.
+        let mut freed = 0;
+        for id in nodes {
+            if u.ratio(1, 8)? {
+                graph.remove_node(id).expect("node was just added");
+                freed += 1;
+            }
+        }
+        for _ in 0..u.int_in_range(0..=freed)? {
+            graph.add_node(N::arbitrary(u)?);
+        }
+        Ok(graph)
+    }
+}
+
+/// Returns a [proptest] strategy for graphs with up to `max_nodes` nodes and up to `max_edges` edges, with data from `node` and `edge`.
+///
+/// The graphs shrink towards fewer nodes and edges.
+#[cfg(feature = "proptest")]
+pub fn arb_graph<N, E>(
+    node: N,
+    edge: E,
+    max_nodes: usize,
+    max_edges: usize,
+) -> impl proptest::strategy::Strategy<Value = Graph<N::Value, E::Value>>
+where
+    N: proptest::strategy::Strategy,
+    E: proptest::strategy::Strategy + Clone,
+    N::Value: Clone + std::fmt::Debug,
+    E::Value: Clone + std::fmt::Debug,
+{
+    use proptest::collection::vec;
+    use proptest::strategy::{Just, Strategy};
+
+    vec(node, 0..=max_nodes)
+        .prop_flat_map(move |nodes| {
+            // With no nodes there's nothing to connect.
+            let max_edges = if nodes.is_empty() { 0 } else { max_edges };
+            let count = nodes.len().max(1);
+            let edges = vec((0..count, 0..count, edge.clone()), 0..=max_edges);
+            (Just(nodes), edges)
+        })
+        .prop_map(|(nodes, edges)| {
+            let mut graph = Graph::with_capacity(nodes.len(), edges.len());
+            let ids: Vec<_> = nodes.into_iter().map(|data| graph.add_node(data)).collect();
+            for (from, to, data) in edges {
+                graph.add_edge(ids[from], ids[to], data);
+            }
+            graph
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GraphRef;
+
+    /// Every edge connects existing nodes and is listed in the connections of both.
+    fn assert_valid<N, E>(graph: &Graph<N, E>) {
+        for (id, edge) in graph.edges.iter() {
+            for endpoint in [edge.from, edge.to] {
+                assert!(graph.node(endpoint).unwrap().connections.contains(&id));
+            }
+        }
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn test_arbitrary_graphs_are_valid() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        for seed in 0..64u32 {
+            let bytes: Vec<u8> = (0..512u32)
+                .map(|i| (i.wrapping_mul(2_654_435_761).wrapping_add(seed * 97) >> 13) as u8)
+                .collect();
+            let graph = Graph::<u8, u16>::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+            assert_valid(&graph);
+        }
+    }
+
+    #[cfg(feature = "proptest")]
+    proptest::proptest! {
+        #[test]
+        fn test_arb_graph_respects_limits(graph in arb_graph(proptest::prelude::any::<u8>(), proptest::strategy::Just(()), 10, 20)) {
+            proptest::prop_assert!(graph.node_count() <= 10 && graph.edges.len() <= 20);
+            assert_valid(&graph);
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! # Random graph generators (requires the `rand` feature).
+//!
+//! [configuration_model] generates a random graph with a given degree sequence, so synthetic benchmark graphs can have
+//! the same degree distribution as a real dataset.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::generators::{configuration_model, ConfigurationModel};
+//! use rand::{rngs::SmallRng, SeedableRng};
+//!
+//! let degrees = [3, 2, 2, 2, 1];
+//! let mut rng = SmallRng::seed_from_u64(1);
+//! let (graph, nodes): (Graph<(), ()>, _) =
+//!     configuration_model(&degrees, ConfigurationModel::default(), &mut rng).unwrap();
+//!
+//! assert_eq!(graph.edges.len(), 5);
+//! // Self-loops count twice, so every node has exactly the requested degree.
+//! assert_eq!(graph.node(nodes[0]).unwrap().connections.len(), 3);
+//! ```
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use thiserror::Error;
+
+use crate::hash::HashSet;
+use crate::{Graph, GraphInterface, NodeID};
+
+/// Returned by [configuration_model] when the degrees sum to an odd number, since every edge uses two stubs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Error)]
+#[error("The degree sequence sums to {0}, which is odd")]
+pub struct OddDegreeSum(pub usize);
+
+/// Options for [configuration_model].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConfigurationModel {
+    /// Whether edges from a node to itself are kept.
+    pub self_loops: bool,
+    /// Whether more than one edge between the same two nodes (in either direction) is kept.
+    pub multi_edges: bool,
+    /// How many random matchings are tried when self-loops or multi-edges are rejected, before the offending edges of the last one are dropped.
+    pub attempts: usize,
+}
+
+impl Default for ConfigurationModel {
+    /// Allows self-loops and multi-edges, so every node gets exactly its degree.
+    fn default() -> Self {
+        ConfigurationModel {
+            self_loops: true,
+            multi_edges: true,
+            attempts: 100,
+        }
+    }
+}
+
+impl ConfigurationModel {
+    /// Rejects self-loops and multi-edges, i.e. generates a simple graph.
+    pub fn simple() -> Self {
+        ConfigurationModel {
+            self_loops: false,
+            multi_edges: false,
+            ..Self::default()
+        }
+    }
+}
+
+/// Generates a random graph in which node `i` has degree `degrees[i]`, returning it and the node IDs in the order of `degrees`.
+///
+/// Every node gets `degrees[i]` "stubs" and the stubs are paired up uniformly at random, each pair becoming an edge (directed from the
+/// stub that comes first in the shuffled order). If `options` rejects self-loops or multi-edges, matchings are retried up to `options.attempts` times;
+/// if none of them is valid, the offending edges of the last one are dropped, so some nodes end up with a smaller degree.
+///
+/// Fails with [OddDegreeSum] if the degrees sum to an odd number.
+pub fn configuration_model<N: Default, E: Default, R: Rng + ?Sized>(
+    degrees: &[usize],
+    options: ConfigurationModel,
+    rng: &mut R,
+) -> Result<(Graph<N, E>, Vec<NodeID>), OddDegreeSum> {
+    let total: usize = degrees.iter().sum();
+    if total % 2 == 1 {
+        return Err(OddDegreeSum(total));
+    }
+    let mut stubs: Vec<usize> = degrees
+        .iter()
+        .enumerate()
+        .flat_map(|(node, degree)| (0..*degree).map(move |_| node))
+        .collect();
+
+    let mut pairs = Vec::with_capacity(total / 2);
+    for _ in 0..options.attempts.max(1) {
+        stubs.shuffle(rng);
+        pairs.clear();
+        let mut seen = HashSet::default();
+        let mut valid = true;
+        for pair in stubs.chunks_exact(2) {
+            let (a, b) = (pair[0], pair[1]);
+            let rejected = (a == b && !options.self_loops)
+                || (!options.multi_edges && !seen.insert((a.min(b), a.max(b))));
+            if rejected {
+                valid = false;
+            } else {
+                pairs.push((a, b));
+            }
+        }
+        if valid {
+            break;
+        }
+    }
+
+    let mut graph = Graph::with_capacity(degrees.len(), pairs.len());
+    let nodes: Vec<NodeID> = degrees
+        .iter()
+        .map(|_| graph.add_node(N::default()))
+        .collect();
+    for (a, b) in pairs {
+        graph.add_edge(nodes[a], nodes[b], E::default());
+    }
+    Ok((graph, nodes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GraphRef;
+    use rand::{rngs::SmallRng, SeedableRng};
+
+    #[test]
+    fn test_configuration_model() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let degrees = [4, 3, 3, 2, 2, 2, 1, 1];
+        let (graph, nodes): (Graph<(), ()>, _) =
+            configuration_model(&degrees, ConfigurationModel::simple(), &mut rng).unwrap();
+
+        let mut pairs = HashSet::default();
+        for edge in graph.edges.values() {
+            assert_ne!(edge.from, edge.to);
+            assert!(pairs.insert((edge.from.min(edge.to), edge.from.max(edge.to))));
+        }
+        // A simple matching exists for these degrees, so none of them has to be cut short.
+        for (node, degree) in nodes.iter().zip(degrees) {
+            assert_eq!(graph.node(*node).unwrap().connections.len(), degree);
+        }
+
+        // Self-loops are listed twice in the connections, so they count twice towards the degree.
+        let degrees = [5, 4, 1, 1, 1];
+        let mut self_loops = 0;
+        for _ in 0..20 {
+            let (graph, nodes): (Graph<(), ()>, _) =
+                configuration_model(&degrees, ConfigurationModel::default(), &mut rng).unwrap();
+            assert_eq!(graph.edges.len(), 6);
+            self_loops += graph
+                .edges
+                .values()
+                .filter(|edge| edge.from == edge.to)
+                .count();
+            for (node, degree) in nodes.iter().zip(degrees) {
+                assert_eq!(graph.node(*node).unwrap().connections.len(), degree);
+            }
+        }
+        assert!(self_loops > 0);
+
+        let odd =
+            configuration_model::<(), (), _>(&[1, 2], ConfigurationModel::default(), &mut rng);
+        assert_eq!(odd.unwrap_err(), OddDegreeSum(3));
+    }
+}
@@ -0,0 +1,40 @@
+//! # The hash maps and sets used throughout the crate.
+//!
+//! Hashing dominates the profiles of traversals on large graphs, so the maps and sets used internally (e.g. the visited set of
+//! [DepthFirstSearch](crate::algorithms::DepthFirstSearch) or [CategorizedGraph::categories](crate::CategorizedGraph::categories))
+//! all go through the aliases in this module, whose hasher is chosen with crate features:
+//!
+//! - `fxhash` - [FxHasher](rustc_hash::FxHasher), a very fast non-cryptographic hasher. Not resistant against HashDoS.
+//! - `hashbrown` (default) - the default hasher of [hashbrown] (aHash).
+//! - neither - the standard library's [RandomState](std::collections::hash_map::RandomState).
+//!
+//! Maps and sets are backed by [hashbrown] if the `hashbrown` feature is enabled, and by the standard library otherwise.
+//! Since the hasher isn't always `RandomState`, create them with `HashMap::default()` rather than `HashMap::new()`.
+
+/// The [BuildHasher](std::hash::BuildHasher) used by [HashMap] and [HashSet].
+#[cfg(feature = "fxhash")]
+pub type HashBuilder = std::hash::BuildHasherDefault<rustc_hash::FxHasher>;
+
+/// The [BuildHasher](std::hash::BuildHasher) used by [HashMap] and [HashSet].
+#[cfg(all(not(feature = "fxhash"), feature = "hashbrown"))]
+pub type HashBuilder = hashbrown::hash_map::DefaultHashBuilder;
+
+/// The [BuildHasher](std::hash::BuildHasher) used by [HashMap] and [HashSet].
+#[cfg(all(not(feature = "fxhash"), not(feature = "hashbrown")))]
+pub type HashBuilder = std::collections::hash_map::RandomState;
+
+/// The hash map used throughout the crate, see the [module documentation](self).
+#[cfg(feature = "hashbrown")]
+pub type HashMap<K, V> = hashbrown::HashMap<K, V, HashBuilder>;
+
+/// The hash map used throughout the crate, see the [module documentation](self).
+#[cfg(not(feature = "hashbrown"))]
+pub type HashMap<K, V> = std::collections::HashMap<K, V, HashBuilder>;
+
+/// The hash set used throughout the crate, see the [module documentation](self).
+#[cfg(feature = "hashbrown")]
+pub type HashSet<T> = hashbrown::HashSet<T, HashBuilder>;
+
+/// The hash set used throughout the crate, see the [module documentation](self).
+#[cfg(not(feature = "hashbrown"))]
+pub type HashSet<T> = std::collections::HashSet<T, HashBuilder>;
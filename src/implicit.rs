@@ -0,0 +1,286 @@
+//! # Implicit graphs.
+//!
+//! An [ImplicitGraph] has no stored nodes or edges: its nodes are states of any hashable type, and the neighbors of a state are
+//! generated on demand by a successor closure. This lets depth first search, breadth first search and Dijkstra's algorithm
+//! explore state spaces (puzzles, planners, game trees) that would be too large, or infinite, to build as a [Graph](crate::Graph).
+//!
+//! Only the states that a search reaches are kept (in its visited set), and every search only generates the successors of states
+//! it expands, so searches for a nearby goal stop early even in an infinite space.
+//!
+//! # Example
+//! ```
+//! use fast_graph::implicit::ImplicitGraph;
+//!
+//! // Reach 10 from 1 by doubling or adding one.
+//! let graph = ImplicitGraph::new(|n: &u32| [n * 2, n + 1].into_iter().filter(|n| *n <= 10));
+//!
+//! assert_eq!(graph.bfs_path(1, |n| *n == 10), Some(vec![1, 2, 4, 5, 10]));
+//! assert_eq!(graph.bfs(1).count(), 10);
+//!
+//! // Doubling is expensive.
+//! let (path, cost) = graph
+//!     .dijkstra(1, |n| *n == 10, |from, to| if *to == from * 2 { 3 } else { 1 })
+//!     .unwrap();
+//! assert_eq!(path, vec![1, 2, 3, 4, 5, 10]);
+//! assert_eq!(cost, 9);
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::ops::Add;
+
+use crate::hash::{HashMap, HashSet};
+
+/// A graph whose neighbors are generated by a closure, see the [module documentation](self).
+///
+/// `successors` returns the states reachable in one step from a state; it should return the same states every time it's called
+/// with the same state.
+pub struct ImplicitGraph<S, F> {
+    successors: F,
+    _state: PhantomData<fn(&S)>,
+}
+
+impl<S, F, I> ImplicitGraph<S, F>
+where
+    S: Hash + Eq + Clone,
+    F: Fn(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    pub fn new(successors: F) -> Self {
+        ImplicitGraph {
+            successors,
+            _state: PhantomData,
+        }
+    }
+
+    /// Returns the states reachable in one step from `state`.
+    pub fn successors(&self, state: &S) -> I {
+        (self.successors)(state)
+    }
+
+    /// Returns a *depth first search* iterator over the states reachable from `start` (including it).
+    ///
+    /// The successors of a state are explored in the order the closure returns them.
+    pub fn dfs(&self, start: S) -> ImplicitDepthFirst<'_, S, F> {
+        ImplicitDepthFirst {
+            graph: self,
+            stack: vec![start],
+            visited: HashSet::default(),
+        }
+    }
+
+    /// Returns a *breadth first search* iterator over the states reachable from `start` (including it), closest first.
+    pub fn bfs(&self, start: S) -> ImplicitBreadthFirst<'_, S, F> {
+        let mut visited = HashSet::default();
+        visited.insert(start.clone());
+        ImplicitBreadthFirst {
+            graph: self,
+            queue: VecDeque::from([start]),
+            visited,
+        }
+    }
+
+    /// Returns a path with the fewest steps from `start` to a state for which `is_goal` returns true (both included),
+    /// or `None` if no such state is reachable.
+    ///
+    /// Doesn't return if the reachable part of the space is infinite and contains no goal.
+    pub fn bfs_path(&self, start: S, mut is_goal: impl FnMut(&S) -> bool) -> Option<Vec<S>> {
+        let mut parents: HashMap<S, Option<S>> = HashMap::default();
+        parents.insert(start.clone(), None);
+        let mut queue = VecDeque::from([start]);
+        while let Some(state) = queue.pop_front() {
+            if is_goal(&state) {
+                return Some(trace(&parents, state));
+            }
+            for next in self.successors(&state) {
+                if !parents.contains_key(&next) {
+                    parents.insert(next.clone(), Some(state.clone()));
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns a cheapest path from `start` to a state for which `is_goal` returns true (both included) and its cost,
+    /// or `None` if no such state is reachable.
+    ///
+    /// `cost` returns the cost of a step between two states and must not be negative; `W::default()` is the cost of the empty path.
+    pub fn dijkstra<W>(
+        &self,
+        start: S,
+        mut is_goal: impl FnMut(&S) -> bool,
+        mut cost: impl FnMut(&S, &S) -> W,
+    ) -> Option<(Vec<S>, W)>
+    where
+        W: Ord + Copy + Default + Add<Output = W>,
+    {
+        // States are numbered in the order they're reached, so the heap and the parents only hold indices.
+        let mut states: Vec<S> = vec![start.clone()];
+        let mut index: HashMap<S, usize> = HashMap::default();
+        index.insert(start, 0);
+        let mut best: Vec<W> = vec![W::default()];
+        let mut parents: Vec<Option<usize>> = vec![None];
+        let mut settled: Vec<bool> = vec![false];
+        let mut heap = BinaryHeap::from([Reverse((W::default(), 0))]);
+
+        while let Some(Reverse((distance, i))) = heap.pop() {
+            if settled[i] {
+                continue;
+            }
+            settled[i] = true;
+            if is_goal(&states[i]) {
+                let mut path = vec![states[i].clone()];
+                let mut current = i;
+                while let Some(parent) = parents[current] {
+                    path.push(states[parent].clone());
+                    current = parent;
+                }
+                path.reverse();
+                return Some((path, distance));
+            }
+            for next in self.successors(&states[i]) {
+                let through = distance + cost(&states[i], &next);
+                let j = match index.get(&next) {
+                    Some(j) if settled[*j] || best[*j] <= through => continue,
+                    Some(j) => *j,
+                    None => {
+                        index.insert(next.clone(), states.len());
+                        states.push(next);
+                        best.push(through);
+                        parents.push(None);
+                        settled.push(false);
+                        states.len() - 1
+                    }
+                };
+                best[j] = through;
+                parents[j] = Some(i);
+                heap.push(Reverse((through, j)));
+            }
+        }
+        None
+    }
+}
+
+fn trace<S: Hash + Eq + Clone>(parents: &HashMap<S, Option<S>>, goal: S) -> Vec<S> {
+    let mut path = vec![goal];
+    while let Some(Some(parent)) = parents.get(path.last().expect("never empty")) {
+        path.push(parent.clone());
+    }
+    path.reverse();
+    path
+}
+
+/// Iterator returned by [ImplicitGraph::dfs].
+pub struct ImplicitDepthFirst<'a, S, F> {
+    graph: &'a ImplicitGraph<S, F>,
+    stack: Vec<S>,
+    visited: HashSet<S>,
+}
+
+impl<'a, S, F, I> Iterator for ImplicitDepthFirst<'a, S, F>
+where
+    S: Hash + Eq + Clone,
+    F: Fn(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    type Item = S;
+
+    fn next(&mut self) -> Option<S> {
+        while let Some(state) = self.stack.pop() {
+            if !self.visited.insert(state.clone()) {
+                continue;
+            }
+            let start = self.stack.len();
+            self.stack.extend(
+                self.graph
+                    .successors(&state)
+                    .into_iter()
+                    .filter(|next| !self.visited.contains(next)),
+            );
+            // The first successor is explored first.
+            self.stack[start..].reverse();
+            return Some(state);
+        }
+        None
+    }
+}
+
+/// Iterator returned by [ImplicitGraph::bfs].
+pub struct ImplicitBreadthFirst<'a, S, F> {
+    graph: &'a ImplicitGraph<S, F>,
+    queue: VecDeque<S>,
+    visited: HashSet<S>,
+}
+
+impl<'a, S, F, I> Iterator for ImplicitBreadthFirst<'a, S, F>
+where
+    S: Hash + Eq + Clone,
+    F: Fn(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    type Item = S;
+
+    fn next(&mut self) -> Option<S> {
+        let state = self.queue.pop_front()?;
+        for next in self.graph.successors(&state) {
+            if !self.visited.contains(&next) {
+                self.visited.insert(next.clone());
+                self.queue.push_back(next);
+            }
+        }
+        Some(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_implicit_grid_searches() {
+        // An unbounded grid with a wall at x == 2 from y == -1 to y == 2, explored from the origin.
+        let graph = ImplicitGraph::new(|&(x, y): &(i32, i32)| {
+            [(x + 1, y), (x, y + 1), (x - 1, y), (x, y - 1)]
+                .into_iter()
+                .filter(|&(x, y)| !(x == 2 && (-1..=2).contains(&y)))
+        });
+
+        let path = graph.bfs_path((0, 0), |state| *state == (4, 0)).unwrap();
+        assert_eq!(path.len(), 9);
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert!(path
+            .windows(2)
+            .all(|step| { (step[0].0 - step[1].0).abs() + (step[0].1 - step[1].1).abs() == 1 }));
+
+        // Breadth first order visits by increasing distance, and stops being polled lazily.
+        let distances: Vec<i32> = graph
+            .bfs((0, 0))
+            .take(13)
+            .map(|(x, y)| x.abs() + y.abs())
+            .collect();
+        assert!(distances.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(graph.dfs((0, 0)).take(5).count(), 5);
+        assert_eq!(graph.dfs((0, 0)).nth(1), Some((1, 0)));
+
+        // Moving up is expensive, so the cheapest way around the wall goes down.
+        let (path, cost) = graph
+            .dijkstra(
+                (0, 0),
+                |state| *state == (4, 0),
+                |from, to| if to.1 > from.1 { 5 } else { 1 },
+            )
+            .unwrap();
+        assert_eq!(cost, 16);
+        assert_eq!(path.last(), Some(&(4, 0)));
+        assert!(path.contains(&(2, -2)));
+
+        // A finite space without the goal.
+        let finite = ImplicitGraph::new(|n: &u8| n.checked_add(1));
+        assert_eq!(finite.bfs(250).count(), 6);
+        assert!(finite.bfs_path(250, |n| *n == 0).is_none());
+        assert!(finite.dijkstra(250, |n| *n == 0, |_, _| 1).is_none());
+    }
+}
@@ -1,10 +1,32 @@
-//! # Contains [GraphInterface]
-//! GraphInterface is a trait for basic "read and write" operations on a graph; core operations needed to change a graph and some derived helper functions.
+//! # Contains [GraphRef] and [GraphInterface]
+//! GraphRef is a trait for read-only access to a graph, and GraphInterface extends it with the operations needed to change a graph and some derived helper functions.
+//!
+//! Algorithms only need [GraphRef], so they also work on views and snapshots that can't be mutated, and on `&G` for any graph `G`.
+//!
+//! Both traits return `impl Iterator`, so they can't be used as trait objects. [DynGraph] is an object-safe companion with boxed iterators,
+//! implemented for every [GraphInterface], and [BoxedGraph] implements [GraphInterface] again so boxed graphs work with every algorithm.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::algorithms::IterDepthFirst;
+//!
+//! let mut backends: Vec<BoxedGraph<u32, ()>> = vec![
+//!     Box::new(Graph::new()),
+//!     Box::new(PersistentGraph::new()),
+//! ];
+//! for graph in backends.iter_mut() {
+//!     let a = graph.add_node(1);
+//!     let b = graph.add_node(2);
+//!     graph.add_edge(a, b, ());
+//!     assert_eq!(graph.iter_depth_first(a).count(), 2);
+//! }
+//! ```
 
-use crate::{Edge, EdgeID, GraphError, Node, NodeID};
+use crate::{Edge, EdgeID, GraphError, GraphOperation, Node, NodeID};
 
-/// GraphInterface is a trait for basic "read and write" operations on a graph; core operations needed to change a graph and some derived helper functions.
-pub trait GraphInterface {
+/// GraphRef is a trait for read-only operations on a graph: counting, iterating and looking up nodes and edges.
+pub trait GraphRef {
     type NodeData;
     type EdgeData;
 
@@ -13,9 +35,91 @@ pub trait GraphInterface {
     fn nodes(&self) -> impl Iterator<Item = NodeID>;
 
     fn node(&self, id: NodeID) -> Result<&Node<Self::NodeData>, GraphError>;
-    fn node_mut(&mut self, id: NodeID) -> Result<&mut Node<Self::NodeData>, GraphError>;
 
     fn edge(&self, id: EdgeID) -> Result<&Edge<Self::EdgeData>, GraphError>;
+
+    /// Returns true if the node exists.
+    fn contains_node(&self, id: NodeID) -> bool {
+        self.node(id).is_ok()
+    }
+
+    /// Returns true if the edge exists.
+    fn contains_edge(&self, id: EdgeID) -> bool {
+        self.edge(id).is_ok()
+    }
+
+    /// Returns the outgoing edges of `node` whose data matches `predicate`, in connection order (nothing if the node doesn't exist).
+    ///
+    /// Connections to removed edges are skipped and a self-loop is returned once.
+    fn edges_where<'a, F>(
+        &'a self,
+        node: NodeID,
+        mut predicate: F,
+    ) -> impl Iterator<Item = &'a Edge<Self::EdgeData>> + 'a
+    where
+        F: FnMut(&Self::EdgeData) -> bool + 'a,
+    {
+        outgoing_edges(self, node).filter(move |edge| predicate(&edge.data))
+    }
+
+    /// Returns the targets of the outgoing edges of `node` whose data matches `predicate`, see [GraphRef::edges_where].
+    ///
+    /// A target is returned once per matching edge, so parallel edges return it repeatedly.
+    fn neighbors_where<'a, F>(
+        &'a self,
+        node: NodeID,
+        predicate: F,
+    ) -> impl Iterator<Item = NodeID> + 'a
+    where
+        F: FnMut(&Self::EdgeData) -> bool + 'a,
+    {
+        self.edges_where(node, predicate).map(|edge| edge.to)
+    }
+}
+
+/// Returns the outgoing edges of `node` in connection order (nothing if the node doesn't exist). This is how the crate follows edges.
+///
+/// Connections to removed edges (see [Graph::vacuum](crate::Graph::vacuum)) are skipped, and a self-loop, which is listed twice
+/// in the connections, is returned once.
+pub(crate) fn outgoing_edges<'a, G: GraphRef + ?Sized>(
+    graph: &'a G,
+    node: NodeID,
+) -> impl Iterator<Item = &'a Edge<G::EdgeData>> + Clone + 'a {
+    let connections: &[EdgeID] = graph
+        .node(node)
+        .map_or(&[][..], |node| &node.connections[..]);
+    connections.iter().enumerate().filter_map(move |(i, id)| {
+        let edge = graph.edge(*id).ok()?;
+        let repeated = edge.from == node && edge.to == node && connections[..i].contains(id);
+        (edge.from == node && !repeated).then_some(edge)
+    })
+}
+
+impl<G: GraphRef> GraphRef for &G {
+    type NodeData = G::NodeData;
+    type EdgeData = G::EdgeData;
+
+    fn node_count(&self) -> usize {
+        (**self).node_count()
+    }
+
+    fn nodes(&self) -> impl Iterator<Item = NodeID> {
+        (**self).nodes()
+    }
+
+    fn node(&self, id: NodeID) -> Result<&Node<Self::NodeData>, GraphError> {
+        (**self).node(id)
+    }
+
+    fn edge(&self, id: EdgeID) -> Result<&Edge<Self::EdgeData>, GraphError> {
+        (**self).edge(id)
+    }
+}
+
+/// GraphInterface is a trait for basic "write" operations on a graph (on top of the reads of [GraphRef]); core operations needed to change a graph and some derived helper functions.
+pub trait GraphInterface: GraphRef {
+    fn node_mut(&mut self, id: NodeID) -> Result<&mut Node<Self::NodeData>, GraphError>;
+
     fn edge_mut(&mut self, id: EdgeID) -> Result<&mut Edge<Self::EdgeData>, GraphError>;
 
     fn add_node(&mut self, data: Self::NodeData) -> NodeID;
@@ -25,6 +129,17 @@ pub trait GraphInterface {
 
     fn add_edge(&mut self, from: NodeID, to: NodeID, data: Self::EdgeData) -> EdgeID;
 
+    /// Adds an edge like [GraphInterface::add_edge], but returns [GraphError::NodeNotFound] instead of adding a dangling edge if `from` or `to` doesn't exist.
+    fn try_add_edge(
+        &mut self,
+        from: NodeID,
+        to: NodeID,
+        data: Self::EdgeData,
+    ) -> Result<EdgeID, GraphError> {
+        check_endpoints(self, from, to)?;
+        Ok(self.add_edge(from, to, data))
+    }
+
     fn remove_node(&mut self, id: NodeID) -> Result<(), GraphError>;
     fn remove_edge(&mut self, id: EdgeID) -> Result<(), GraphError>;
 
@@ -52,6 +167,7 @@ pub trait GraphInterface {
         edges
     }
 
+    #[allow(clippy::type_complexity)]
     fn add_nodes_and_edges(
         &mut self,
         data: Vec<(Self::NodeData, Vec<NodeID>)>,
@@ -75,6 +191,7 @@ pub trait GraphInterface {
         self.add_nodes_and_edges_with_data(with_data)
     }
 
+    #[allow(clippy::type_complexity)]
     fn add_nodes_and_edges_with_data(
         &mut self,
         node_data: Vec<(Self::NodeData, Vec<(NodeID, Self::EdgeData)>)>,
@@ -100,3 +217,159 @@ pub trait GraphInterface {
         (added_nodes, added_edges)
     }
 }
+
+/* -------------------------------------------------------------------------- */
+/*                                  DynGraph                                  */
+/* -------------------------------------------------------------------------- */
+
+/// An object-safe version of [GraphInterface], e.g. for storing different graph backends as `Box<dyn DynGraph<NodeData = N, EdgeData = E>>`.
+///
+/// It's implemented for every [GraphInterface]. The methods have a `dyn_` prefix so they don't clash with the ones of [GraphInterface];
+/// usually they aren't called directly, since [BoxedGraph] implements [GraphInterface] by forwarding to them.
+pub trait DynGraph {
+    type NodeData;
+    type EdgeData;
+
+    fn dyn_node_count(&self) -> usize;
+    fn dyn_nodes(&self) -> Box<dyn Iterator<Item = NodeID> + '_>;
+
+    fn dyn_node(&self, id: NodeID) -> Result<&Node<Self::NodeData>, GraphError>;
+    fn dyn_node_mut(&mut self, id: NodeID) -> Result<&mut Node<Self::NodeData>, GraphError>;
+
+    fn dyn_edge(&self, id: EdgeID) -> Result<&Edge<Self::EdgeData>, GraphError>;
+    fn dyn_edge_mut(&mut self, id: EdgeID) -> Result<&mut Edge<Self::EdgeData>, GraphError>;
+
+    fn dyn_add_node(&mut self, data: Self::NodeData) -> NodeID;
+    fn dyn_add_edge(&mut self, from: NodeID, to: NodeID, data: Self::EdgeData) -> EdgeID;
+
+    fn dyn_remove_node(&mut self, id: NodeID) -> Result<(), GraphError>;
+    fn dyn_remove_edge(&mut self, id: EdgeID) -> Result<(), GraphError>;
+}
+
+impl<G: GraphInterface> DynGraph for G {
+    type NodeData = G::NodeData;
+    type EdgeData = G::EdgeData;
+
+    fn dyn_node_count(&self) -> usize {
+        self.node_count()
+    }
+
+    fn dyn_nodes(&self) -> Box<dyn Iterator<Item = NodeID> + '_> {
+        Box::new(self.nodes())
+    }
+
+    fn dyn_node(&self, id: NodeID) -> Result<&Node<Self::NodeData>, GraphError> {
+        self.node(id)
+    }
+
+    fn dyn_node_mut(&mut self, id: NodeID) -> Result<&mut Node<Self::NodeData>, GraphError> {
+        self.node_mut(id)
+    }
+
+    fn dyn_edge(&self, id: EdgeID) -> Result<&Edge<Self::EdgeData>, GraphError> {
+        self.edge(id)
+    }
+
+    fn dyn_edge_mut(&mut self, id: EdgeID) -> Result<&mut Edge<Self::EdgeData>, GraphError> {
+        self.edge_mut(id)
+    }
+
+    fn dyn_add_node(&mut self, data: Self::NodeData) -> NodeID {
+        self.add_node(data)
+    }
+
+    fn dyn_add_edge(&mut self, from: NodeID, to: NodeID, data: Self::EdgeData) -> EdgeID {
+        self.add_edge(from, to, data)
+    }
+
+    fn dyn_remove_node(&mut self, id: NodeID) -> Result<(), GraphError> {
+        self.remove_node(id)
+    }
+
+    fn dyn_remove_edge(&mut self, id: EdgeID) -> Result<(), GraphError> {
+        self.remove_edge(id)
+    }
+}
+
+/// A boxed [DynGraph] trait object, which implements [GraphRef] and [GraphInterface] again.
+pub type BoxedGraph<'a, N, E> = Box<dyn DynGraph<NodeData = N, EdgeData = E> + 'a>;
+
+impl<'a, N, E> GraphRef for BoxedGraph<'a, N, E> {
+    type NodeData = N;
+    type EdgeData = E;
+
+    fn node_count(&self) -> usize {
+        (**self).dyn_node_count()
+    }
+
+    fn nodes(&self) -> impl Iterator<Item = NodeID> {
+        (**self).dyn_nodes()
+    }
+
+    fn node(&self, id: NodeID) -> Result<&Node<N>, GraphError> {
+        (**self).dyn_node(id)
+    }
+
+    fn edge(&self, id: EdgeID) -> Result<&Edge<E>, GraphError> {
+        (**self).dyn_edge(id)
+    }
+}
+
+impl<'a, N, E> GraphInterface for BoxedGraph<'a, N, E> {
+    fn node_mut(&mut self, id: NodeID) -> Result<&mut Node<N>, GraphError> {
+        (**self).dyn_node_mut(id)
+    }
+
+    fn edge_mut(&mut self, id: EdgeID) -> Result<&mut Edge<E>, GraphError> {
+        (**self).dyn_edge_mut(id)
+    }
+
+    fn add_node(&mut self, data: N) -> NodeID {
+        (**self).dyn_add_node(data)
+    }
+
+    fn add_nodes(&mut self, data: &[N]) -> Vec<NodeID>
+    where
+        N: Clone,
+    {
+        data.iter()
+            .map(|data| (**self).dyn_add_node(data.clone()))
+            .collect()
+    }
+
+    fn add_edge(&mut self, from: NodeID, to: NodeID, data: E) -> EdgeID {
+        (**self).dyn_add_edge(from, to, data)
+    }
+
+    fn remove_node(&mut self, id: NodeID) -> Result<(), GraphError> {
+        (**self).dyn_remove_node(id)
+    }
+
+    fn remove_edge(&mut self, id: EdgeID) -> Result<(), GraphError> {
+        (**self).dyn_remove_edge(id)
+    }
+
+    fn add_edges(&mut self, data: &[(NodeID, NodeID)]) -> Vec<EdgeID>
+    where
+        E: Default + Clone,
+        N: Clone,
+    {
+        data.iter()
+            .map(|(from, to)| (**self).dyn_add_edge(*from, *to, E::default()))
+            .collect()
+    }
+}
+
+/// Returns an error if `from` or `to` isn't a node of `graph`.
+pub(crate) fn check_endpoints<G: GraphRef + ?Sized>(
+    graph: &G,
+    from: NodeID,
+    to: NodeID,
+) -> Result<(), GraphError> {
+    for id in [from, to] {
+        graph
+            .node(id)
+            .map_err(|e| e.during(GraphOperation::AddEdge { from, to }))?;
+    }
+    Ok(())
+}
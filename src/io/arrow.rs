@@ -0,0 +1,124 @@
+//! # Columnar export to Arrow record batches.
+//!
+//! Requires the `arrow` feature. See [parquet](super::parquet) (feature `parquet`) for writing the batches to Parquet files.
+//!
+//! Nodes are exported as `id: UInt64, data: Utf8` and edges as `id: UInt64, from: UInt64, to: UInt64, data: Utf8`,
+//! where IDs are [NodeID::to_u64]/[EdgeID::to_u64] and the data columns hold whatever the given closure serializes the node/edge data to.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//!
+//! let mut graph: Graph<&str, u32> = Graph::new();
+//! let a = graph.add_node("A");
+//! let b = graph.add_node("B");
+//! graph.add_edge(a, b, 3);
+//!
+//! let nodes = graph.nodes_to_record_batch(|data| data.to_string()).unwrap();
+//! let edges = graph.edges_to_record_batch(|data| data.to_string()).unwrap();
+//!
+//! assert_eq!(nodes.num_rows(), 2);
+//! assert_eq!(edges.num_columns(), 4);
+//! ```
+
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, RecordBatch, StringArray, UInt64Array};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef};
+
+use crate::Graph;
+
+/// The schema of [Graph::nodes_to_record_batch].
+pub fn node_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("data", DataType::Utf8, false),
+    ]))
+}
+
+/// The schema of [Graph::edges_to_record_batch].
+pub fn edge_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("from", DataType::UInt64, false),
+        Field::new("to", DataType::UInt64, false),
+        Field::new("data", DataType::Utf8, false),
+    ]))
+}
+
+impl<N, E> Graph<N, E> {
+    /// Exports all nodes as one record batch with the [node_schema].
+    pub fn nodes_to_record_batch(
+        &self,
+        data_fn: impl Fn(&N) -> String,
+    ) -> Result<RecordBatch, ArrowError> {
+        let ids: UInt64Array = self.nodes.keys().map(|id| id.to_u64()).collect();
+        let data: StringArray = self
+            .nodes
+            .values()
+            .map(|node| Some(data_fn(&node.data)))
+            .collect();
+        RecordBatch::try_new(
+            node_schema(),
+            vec![Arc::new(ids) as ArrayRef, Arc::new(data) as ArrayRef],
+        )
+    }
+
+    /// Exports all edges as one record batch with the [edge_schema].
+    pub fn edges_to_record_batch(
+        &self,
+        data_fn: impl Fn(&E) -> String,
+    ) -> Result<RecordBatch, ArrowError> {
+        let edges = self.edge_references();
+        let ids: UInt64Array = edges.clone().map(|edge| edge.id.to_u64()).collect();
+        let from: UInt64Array = edges.clone().map(|edge| edge.from.to_u64()).collect();
+        let to: UInt64Array = edges.clone().map(|edge| edge.to.to_u64()).collect();
+        let data: StringArray = edges.map(|edge| Some(data_fn(&edge.data))).collect();
+        RecordBatch::try_new(
+            edge_schema(),
+            vec![
+                Arc::new(ids) as ArrayRef,
+                Arc::new(from) as ArrayRef,
+                Arc::new(to) as ArrayRef,
+                Arc::new(data) as ArrayRef,
+            ],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GraphInterface, NodeID};
+    use arrow_array::Array;
+
+    #[test]
+    fn test_edge_columns_reference_node_ids() {
+        let mut graph: Graph<u8, (u8, u8)> = Graph::new();
+        let nodes: Vec<NodeID> = (0..4).map(|i| graph.add_node(i)).collect();
+        graph.add_edge(nodes[0], nodes[3], (0, 3));
+        graph.add_edge(nodes[2], nodes[1], (2, 1));
+
+        let batch = graph
+            .edges_to_record_batch(|(from, to)| format!("{from}-{to}"))
+            .unwrap();
+        let from = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .unwrap();
+        let data = batch
+            .column(3)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+
+        assert_eq!(batch.schema(), edge_schema());
+        assert_eq!(from.len(), 2);
+        assert_eq!(
+            NodeID::try_from_u64(&graph, from.value(0)).unwrap(),
+            nodes[0]
+        );
+        assert_eq!(data.value(1), "2-1");
+    }
+}
@@ -0,0 +1,453 @@
+//! # Compact, versioned binary serialization.
+//!
+//! [Graph::to_bytes] remaps the slotmap keys to dense indices (`0..node_count`) on write, and [Graph::from_bytes] rebuilds the slotmaps and adjacency on read.
+//! The payload therefore doesn't depend on slotmap slot versions and is much smaller than serializing the raw slotmaps.
+//!
+//! Node and edge data are encoded with the [BinaryData] trait, which is implemented for the primitive types, [String], [Vec], [Option] and tuples.
+//!
+//! # Format (version 1)
+//! All integers are unsigned LEB128 varints unless noted otherwise. Every value takes at least one byte (`()` is a single zero byte),
+//! so a count larger than the rest of the input is rejected before anything is allocated or decoded.
+//! ```text
+//! magic    b"FGRB"
+//! version  u8
+//! nodes    count, then `count` × node data
+//! edges    count, then `count` × (from index, to index, edge data)
+//! ```
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//!
+//! let mut graph: Graph<String, f32> = Graph::new();
+//! let a = graph.add_node("A".to_string());
+//! let b = graph.add_node("B".to_string());
+//! graph.add_edge(a, b, 0.5);
+//!
+//! let bytes = graph.to_bytes();
+//! let restored: Graph<String, f32> = Graph::from_bytes(&bytes).unwrap();
+//!
+//! assert_eq!(restored.node_count(), 2);
+//! let edge = restored.edges.values().next().unwrap();
+//! assert_eq!(restored.node(edge.from).unwrap().data, "A");
+//! assert_eq!(edge.data, 0.5);
+//! ```
+
+use crate::hash::HashMap;
+
+use thiserror::Error;
+
+use crate::{Graph, GraphInterface, NodeID};
+
+const MAGIC: &[u8; 4] = b"FGRB";
+const VERSION: u8 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum BinaryError {
+    #[error("Input is not a fast-graph binary payload")]
+    InvalidMagic,
+    #[error("Unsupported format version {0}")]
+    UnsupportedVersion(u8),
+    #[error("Unexpected end of input")]
+    UnexpectedEof,
+    #[error("Invalid data: {0}")]
+    InvalidData(String),
+}
+
+/// A value that can be written to and read from the binary format.
+pub trait BinaryData: Sized {
+    /// Appends the encoded value to `out`. Implementations should write at least one byte, see the [module documentation](self).
+    fn encode(&self, out: &mut Vec<u8>);
+
+    /// Decodes a value from the front of `input` and advances it past the consumed bytes.
+    fn decode(input: &mut &[u8]) -> Result<Self, BinaryError>;
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                    Graph                                   */
+/* -------------------------------------------------------------------------- */
+
+impl<N: BinaryData, E: BinaryData> Graph<N, E> {
+    /// Encodes the graph in the compact binary format.
+    ///
+    /// Edges whose endpoints don't exist are skipped, since they can't be remapped.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+
+        let mut indices: HashMap<NodeID, u64> =
+            HashMap::with_capacity_and_hasher(self.nodes.len(), Default::default());
+        (self.nodes.len() as u64).encode(&mut out);
+        for (index, (id, node)) in self.nodes.iter().enumerate() {
+            indices.insert(id, index as u64);
+            node.data.encode(&mut out);
+        }
+
+        let edges: Vec<_> = self
+            .edges
+            .values()
+            .filter_map(|edge| Some((indices.get(&edge.from)?, indices.get(&edge.to)?, edge)))
+            .collect();
+        (edges.len() as u64).encode(&mut out);
+        for (from, to, edge) in edges {
+            from.encode(&mut out);
+            to.encode(&mut out);
+            edge.data.encode(&mut out);
+        }
+        out
+    }
+
+    /// Decodes a graph written by [Graph::to_bytes]. Node and edge IDs are freshly allocated.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Graph<N, E>, BinaryError> {
+        let mut input = bytes;
+        if take(&mut input, MAGIC.len())? != MAGIC {
+            return Err(BinaryError::InvalidMagic);
+        }
+        let version = u8::decode(&mut input)?;
+        if version != VERSION {
+            return Err(BinaryError::UnsupportedVersion(version));
+        }
+
+        let node_count = decode_count(&mut input)?;
+        let mut nodes = Vec::with_capacity(node_count);
+        let mut graph = Graph::with_capacity(node_count, 0);
+        for _ in 0..node_count {
+            nodes.push(graph.add_node(N::decode(&mut input)?));
+        }
+
+        let edge_count = decode_count(&mut input)?;
+        graph.edges.reserve(edge_count);
+        for _ in 0..edge_count {
+            let from = decode_node(&mut input, &nodes)?;
+            let to = decode_node(&mut input, &nodes)?;
+            let data = E::decode(&mut input)?;
+            graph.add_edge(from, to, data);
+        }
+
+        if !input.is_empty() {
+            return Err(BinaryError::InvalidData(format!(
+                "{} trailing bytes",
+                input.len()
+            )));
+        }
+        Ok(graph)
+    }
+}
+
+fn decode_len(input: &mut &[u8]) -> Result<usize, BinaryError> {
+    let len = u64::decode(input)?;
+    usize::try_from(len).map_err(|_| BinaryError::InvalidData(format!("length {} too large", len)))
+}
+
+/// Decodes the number of the following items, which can't be more than the remaining bytes since every item takes at least one.
+fn decode_count(input: &mut &[u8]) -> Result<usize, BinaryError> {
+    let count = decode_len(input)?;
+    if count > input.len() {
+        return Err(BinaryError::UnexpectedEof);
+    }
+    Ok(count)
+}
+
+fn decode_node(input: &mut &[u8], nodes: &[NodeID]) -> Result<NodeID, BinaryError> {
+    let index = decode_len(input)?;
+    nodes
+        .get(index)
+        .copied()
+        .ok_or_else(|| BinaryError::InvalidData(format!("node index {} out of bounds", index)))
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8], BinaryError> {
+    if input.len() < len {
+        return Err(BinaryError::UnexpectedEof);
+    }
+    let (head, tail) = input.split_at(len);
+    *input = tail;
+    Ok(head)
+}
+
+/* -------------------------------------------------------------------------- */
+/*                             BinaryData impls                               */
+/* -------------------------------------------------------------------------- */
+
+impl BinaryData for u64 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let mut value = *self;
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(byte);
+                return;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, BinaryError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = take(input, 1)?[0];
+            // The 10th byte only has room for the highest bit.
+            if shift == 63 && byte > 1 {
+                break;
+            }
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(BinaryError::InvalidData("varint overflow".into()))
+    }
+}
+
+impl BinaryData for i64 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        // Zigzag encoding keeps small negative numbers small.
+        (((*self << 1) ^ (*self >> 63)) as u64).encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, BinaryError> {
+        let value = u64::decode(input)?;
+        Ok(((value >> 1) as i64) ^ -((value & 1) as i64))
+    }
+}
+
+macro_rules! impl_binary_data_via {
+    ($via:ty => $($ty:ty),*) => {
+        $(
+            impl BinaryData for $ty {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    (*self as $via).encode(out);
+                }
+
+                fn decode(input: &mut &[u8]) -> Result<Self, BinaryError> {
+                    let value = <$via>::decode(input)?;
+                    <$ty>::try_from(value).map_err(|_| {
+                        BinaryError::InvalidData(format!(
+                            "{} out of range for {}",
+                            value,
+                            stringify!($ty)
+                        ))
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_binary_data_via!(u64 => u16, u32, usize);
+impl_binary_data_via!(i64 => i8, i16, i32, isize);
+
+impl BinaryData for u8 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, BinaryError> {
+        Ok(take(input, 1)?[0])
+    }
+}
+
+impl BinaryData for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, BinaryError> {
+        match u8::decode(input)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            other => Err(BinaryError::InvalidData(format!("invalid bool {}", other))),
+        }
+    }
+}
+
+macro_rules! impl_binary_data_float {
+    ($($ty:ty),*) => {
+        $(
+            impl BinaryData for $ty {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn decode(input: &mut &[u8]) -> Result<Self, BinaryError> {
+                    let bytes = take(input, std::mem::size_of::<$ty>())?;
+                    Ok(<$ty>::from_le_bytes(bytes.try_into().unwrap()))
+                }
+            }
+        )*
+    };
+}
+
+impl_binary_data_float!(f32, f64);
+
+impl BinaryData for () {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(0);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, BinaryError> {
+        match u8::decode(input)? {
+            0 => Ok(()),
+            other => Err(BinaryError::InvalidData(format!("invalid unit {}", other))),
+        }
+    }
+}
+
+impl BinaryData for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.len().encode(out);
+        out.extend_from_slice(self.as_bytes());
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, BinaryError> {
+        let len = decode_len(input)?;
+        let bytes = take(input, len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|e| BinaryError::InvalidData(e.to_string()))
+    }
+}
+
+impl<T: BinaryData> BinaryData for Vec<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.len().encode(out);
+        for item in self {
+            item.encode(out);
+        }
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, BinaryError> {
+        let len = decode_count(input)?;
+        let mut items = Vec::with_capacity(len);
+        for _ in 0..len {
+            items.push(T::decode(input)?);
+        }
+        Ok(items)
+    }
+}
+
+impl<T: BinaryData> BinaryData for Option<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Some(value) => {
+                true.encode(out);
+                value.encode(out);
+            }
+            None => false.encode(out),
+        }
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, BinaryError> {
+        Ok(if bool::decode(input)? {
+            Some(T::decode(input)?)
+        } else {
+            None
+        })
+    }
+}
+
+macro_rules! impl_binary_data_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: BinaryData),+> BinaryData for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn encode(&self, out: &mut Vec<u8>) {
+                let ($($name,)+) = self;
+                $($name.encode(out);)+
+            }
+
+            fn decode(input: &mut &[u8]) -> Result<Self, BinaryError> {
+                Ok(($($name::decode(input)?,)+))
+            }
+        }
+    };
+}
+
+impl_binary_data_tuple!(A);
+impl_binary_data_tuple!(A, B);
+impl_binary_data_tuple!(A, B, C);
+impl_binary_data_tuple!(A, B, C, D);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GraphRef;
+
+    #[test]
+    fn test_round_trip_after_churn() {
+        let mut graph: Graph<(i32, Option<String>), Vec<u16>> = Graph::new();
+        let nodes: Vec<NodeID> = (0..10)
+            .map(|i| graph.add_node((-i, Some(i.to_string()))))
+            .collect();
+        for window in nodes.windows(2) {
+            graph.add_edge(window[0], window[1], vec![1, 300]);
+        }
+        // Removing and re-adding bumps slot versions, which must not leak into the payload.
+        graph.remove_node(nodes[0]).unwrap();
+        let readded = graph.add_node((42, None));
+        graph.add_edge(readded, nodes[9], vec![]);
+
+        let bytes = graph.to_bytes();
+        let restored: Graph<(i32, Option<String>), Vec<u16>> = Graph::from_bytes(&bytes).unwrap();
+
+        assert_eq!(restored.node_count(), graph.node_count());
+        assert_eq!(restored.edges.len(), graph.edges.len());
+        let mut data: Vec<_> = restored.nodes.values().map(|n| n.data.0).collect();
+        data.sort();
+        assert_eq!(data, vec![-9, -8, -7, -6, -5, -4, -3, -2, -1, 42]);
+        for edge in restored.edges.values() {
+            assert!(restored
+                .node(edge.from)
+                .unwrap()
+                .connections
+                .contains(&edge.id));
+            assert!(restored
+                .node(edge.to)
+                .unwrap()
+                .connections
+                .contains(&edge.id));
+        }
+    }
+
+    #[test]
+    fn test_invalid_payloads() {
+        let graph: Graph<u8, ()> = Graph::new();
+        let mut bytes = graph.to_bytes();
+        assert!(Graph::<u8, ()>::from_bytes(&bytes).is_ok());
+
+        bytes[4] = 99;
+        assert_eq!(
+            Graph::<u8, ()>::from_bytes(&bytes).unwrap_err(),
+            BinaryError::UnsupportedVersion(99)
+        );
+        assert_eq!(
+            Graph::<u8, ()>::from_bytes(b"JSON").unwrap_err(),
+            BinaryError::InvalidMagic
+        );
+        assert_eq!(
+            Graph::<u8, ()>::from_bytes(b"FGRB\x01\x02\x07").unwrap_err(),
+            BinaryError::UnexpectedEof
+        );
+
+        // A huge count of zero-sized nodes is rejected instead of looping.
+        assert_eq!(
+            Graph::<(), ()>::from_bytes(b"FGRB\x01\xff\xff\xff\xff\xff\xff\xff\xff\x7f\x00")
+                .unwrap_err(),
+            BinaryError::UnexpectedEof
+        );
+        let mut units: Graph<(), ()> = Graph::new();
+        let nodes = units.add_nodes(&[(); 3]);
+        units.add_edge(nodes[0], nodes[2], ());
+        let restored = Graph::<(), ()>::from_bytes(&units.to_bytes()).unwrap();
+        assert_eq!((restored.node_count(), restored.edges.len()), (3, 1));
+
+        // The 10th byte of a varint may only carry the highest bit.
+        let mut max = Vec::new();
+        u64::MAX.encode(&mut max);
+        assert_eq!(u64::decode(&mut &max[..]), Ok(u64::MAX));
+        *max.last_mut().unwrap() = 0x03;
+        assert!(matches!(
+            u64::decode(&mut &max[..]),
+            Err(BinaryError::InvalidData(_))
+        ));
+    }
+}
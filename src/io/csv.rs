@@ -0,0 +1,332 @@
+//! # CSV edge-list and node-list import/export.
+//!
+//! An edge list has one edge per line: `from<delimiter>to[<delimiter>weight]`.
+//! A node list has one node per line: `label[<delimiter>data]`.
+//!
+//! Empty lines and lines starting with `#` or `%` are skipped. Quoted fields are not supported.
+//!
+//! Imports identify nodes by their label and return the label → [NodeID] mapping next to the graph.
+//! Exports label nodes with dense indices (`0..node_count`) in node iteration order, so a node list and an edge list written from the same graph line up.
+//!
+//! # Example
+//! ```
+//! use fast_graph::io::csv::{self, CsvOptions};
+//! use fast_graph::*;
+//!
+//! let input = "a;b;1.5\nb;c;2.0\n";
+//! let options = CsvOptions::new().delimiter(';').weighted(true);
+//! let (graph, labels) = csv::from_edge_list::<f64, _>(input.as_bytes(), &options).unwrap();
+//!
+//! assert_eq!(graph.node_count(), 3);
+//! assert_eq!(graph.node(labels["a"]).unwrap().data, "a");
+//!
+//! let mut output = Vec::new();
+//! csv::to_edge_list(&graph, &mut output, &options).unwrap();
+//! assert_eq!(String::from_utf8(output).unwrap(), "0;1;1.5\n1;2;2\n");
+//! ```
+
+use std::fmt::Display;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::str::FromStr;
+
+use crate::hash::HashMap;
+
+use thiserror::Error;
+
+use crate::{EdgeID, Graph, GraphInterface, NodeID};
+
+/// Maps the node labels found in a file to the nodes created for them.
+pub type Labels = HashMap<String, NodeID>;
+
+/// Options for reading and writing CSV files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// Field separator, `,` by default.
+    pub delimiter: char,
+    /// Whether the first line is a header. It is skipped on import and written on export.
+    pub has_header: bool,
+    /// Whether edge lists have a third (weight) column.
+    pub weighted: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: ',',
+            has_header: false,
+            weighted: false,
+        }
+    }
+}
+
+impl CsvOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn has_header(mut self, has_header: bool) -> Self {
+        self.has_header = has_header;
+        self
+    }
+
+    pub fn weighted(mut self, weighted: bool) -> Self {
+        self.weighted = weighted;
+        self
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CsvError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Parse error on line {line}: {message}")]
+    Parse { line: usize, message: String },
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                   Import                                   */
+/* -------------------------------------------------------------------------- */
+
+/// Reads an edge list into a new graph where every node's data is its label.
+///
+/// Edge data is parsed from the weight column if [CsvOptions::weighted] is set, otherwise it is `E::default()`.
+pub fn from_edge_list<E, R>(
+    reader: R,
+    options: &CsvOptions,
+) -> Result<(Graph<String, E>, Labels), CsvError>
+where
+    E: FromStr + Default,
+    E::Err: Display,
+    R: Read,
+{
+    let mut graph = Graph::new();
+    let mut labels = HashMap::default();
+    extend_from_edge_list(&mut graph, &mut labels, reader, options, |label| {
+        label.to_string()
+    })?;
+    Ok((graph, labels))
+}
+
+/// Reads an edge list into an existing graph.
+///
+/// Labels found in `labels` are resolved to the existing nodes, unknown labels create a new node with the data returned by `new_node` and are added to `labels`.
+///
+/// Returns the IDs of the added edges in file order.
+pub fn extend_from_edge_list<N, E, R>(
+    graph: &mut Graph<N, E>,
+    labels: &mut Labels,
+    reader: R,
+    options: &CsvOptions,
+    mut new_node: impl FnMut(&str) -> N,
+) -> Result<Vec<EdgeID>, CsvError>
+where
+    E: FromStr + Default,
+    E::Err: Display,
+    R: Read,
+{
+    let mut edges = Vec::new();
+    for_each_record(reader, options, |line, fields| {
+        if fields.len() < 2 {
+            return Err(parse_error(line, "expected at least two columns"));
+        }
+        let data = if options.weighted {
+            let weight = fields
+                .get(2)
+                .ok_or_else(|| parse_error(line, "missing weight column"))?;
+            weight
+                .parse::<E>()
+                .map_err(|e| parse_error(line, format!("invalid weight `{}`: {}", weight, e)))?
+        } else {
+            E::default()
+        };
+
+        let mut resolve = |label: &str| match labels.get(label) {
+            Some(id) => *id,
+            None => {
+                let id = graph.add_node(new_node(label));
+                labels.insert(label.to_string(), id);
+                id
+            }
+        };
+        let from = resolve(fields[0]);
+        let to = resolve(fields[1]);
+        edges.push(graph.add_edge(from, to, data));
+        Ok(())
+    })?;
+    Ok(edges)
+}
+
+/// Reads a node list into a new graph. The data column is parsed as `N`; if a line has no data column, the label itself is parsed.
+pub fn from_node_list<N, E, R>(
+    reader: R,
+    options: &CsvOptions,
+) -> Result<(Graph<N, E>, Labels), CsvError>
+where
+    N: FromStr,
+    N::Err: Display,
+    R: Read,
+{
+    let mut graph = Graph::new();
+    let mut labels = HashMap::default();
+    for_each_record(reader, options, |line, fields| {
+        let label = fields[0];
+        if labels.contains_key(label) {
+            return Err(parse_error(line, format!("duplicate node `{}`", label)));
+        }
+        let raw = fields.get(1).copied().unwrap_or(label);
+        let data = raw
+            .parse::<N>()
+            .map_err(|e| parse_error(line, format!("invalid node data `{}`: {}", raw, e)))?;
+        labels.insert(label.to_string(), graph.add_node(data));
+        Ok(())
+    })?;
+    Ok((graph, labels))
+}
+
+fn for_each_record<R: Read>(
+    reader: R,
+    options: &CsvOptions,
+    mut f: impl FnMut(usize, &[&str]) -> Result<(), CsvError>,
+) -> Result<(), CsvError> {
+    for (index, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line?;
+        if index == 0 && options.has_header {
+            continue;
+        }
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with('%') {
+            continue;
+        }
+        let fields: Vec<&str> = trimmed.split(options.delimiter).map(str::trim).collect();
+        f(index + 1, &fields)?;
+    }
+    Ok(())
+}
+
+fn parse_error(line: usize, message: impl Into<String>) -> CsvError {
+    CsvError::Parse {
+        line,
+        message: message.into(),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                   Export                                   */
+/* -------------------------------------------------------------------------- */
+
+/// Writes the edges of the graph as `from,to[,weight]`, labelling nodes with dense indices.
+///
+/// The weight column (the [Display] of the edge data) is only written if [CsvOptions::weighted] is set.
+pub fn to_edge_list<N, E, W>(
+    graph: &Graph<N, E>,
+    writer: W,
+    options: &CsvOptions,
+) -> Result<(), CsvError>
+where
+    E: Display,
+    W: Write,
+{
+    let mut writer = writer;
+    let indices = dense_indices(graph);
+    let d = options.delimiter;
+    if options.has_header {
+        if options.weighted {
+            writeln!(writer, "from{d}to{d}weight")?;
+        } else {
+            writeln!(writer, "from{d}to")?;
+        }
+    }
+    for (from, to, data) in graph.edge_triples() {
+        let (Some(from), Some(to)) = (indices.get(&from), indices.get(&to)) else {
+            // Edges with dangling endpoints can't be represented.
+            continue;
+        };
+        if options.weighted {
+            writeln!(writer, "{from}{d}{to}{d}{}", data)?;
+        } else {
+            writeln!(writer, "{from}{d}{to}")?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes the nodes of the graph as `index,data`, using the same dense indices as [to_edge_list].
+pub fn to_node_list<N, E, W>(
+    graph: &Graph<N, E>,
+    writer: W,
+    options: &CsvOptions,
+) -> Result<(), CsvError>
+where
+    N: Display,
+    W: Write,
+{
+    let mut writer = writer;
+    let d = options.delimiter;
+    if options.has_header {
+        writeln!(writer, "id{d}data")?;
+    }
+    for (index, node) in graph.nodes.values().enumerate() {
+        writeln!(writer, "{index}{d}{}", node.data)?;
+    }
+    Ok(())
+}
+
+fn dense_indices<N, E>(graph: &Graph<N, E>) -> HashMap<NodeID, usize> {
+    graph
+        .nodes
+        .keys()
+        .enumerate()
+        .map(|(index, id)| (id, index))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GraphRef;
+
+    #[test]
+    fn test_edge_list_round_trip() {
+        let input = "from\tto\tweight\n# comment\n1\t2\t3\n2\t3\t4\n\n3\t1\t5\n";
+        let options = CsvOptions::new()
+            .delimiter('\t')
+            .has_header(true)
+            .weighted(true);
+        let (graph, labels) = from_edge_list::<u32, _>(input.as_bytes(), &options).unwrap();
+
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edges.len(), 3);
+        let first = graph.node(labels["1"]).unwrap();
+        let edge = graph.edge(first.connections[0]).unwrap();
+        assert_eq!((edge.to, edge.data), (labels["2"], 3));
+
+        let mut nodes = Vec::new();
+        let mut edges = Vec::new();
+        to_node_list(&graph, &mut nodes, &options).unwrap();
+        to_edge_list(&graph, &mut edges, &options).unwrap();
+
+        let (reloaded, _) = from_node_list::<String, (), _>(&nodes[..], &options).unwrap();
+        assert_eq!(reloaded.node_count(), 3);
+        let (reloaded, _) = from_edge_list::<u32, _>(&edges[..], &options).unwrap();
+        assert_eq!(reloaded.edges.len(), 3);
+    }
+
+    #[test]
+    fn test_edge_list_errors() {
+        let options = CsvOptions::new().weighted(true);
+        let missing = from_edge_list::<u32, _>("1,2,3\n1,2\n".as_bytes(), &options);
+        assert!(matches!(missing, Err(CsvError::Parse { line: 2, .. })));
+
+        let invalid = from_edge_list::<u32, _>("1,2,x\n".as_bytes(), &options);
+        assert!(matches!(invalid, Err(CsvError::Parse { line: 1, .. })));
+
+        let (unweighted, _) =
+            from_edge_list::<u32, _>("1,2\n".as_bytes(), &CsvOptions::new()).unwrap();
+        assert_eq!(unweighted.edges.values().next().unwrap().data, 0);
+    }
+}
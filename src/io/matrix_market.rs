@@ -0,0 +1,278 @@
+//! # Matrix Market (`.mtx`) import.
+//!
+//! Reads sparse matrices in the [Matrix Market coordinate format] (as used by e.g. the SuiteSparse matrix collection) into a [Graph].
+//! Every row/column index becomes a node and every stored entry `(i, j, value)` becomes an edge `i -> j` with the value as edge data.
+//!
+//! Supported headers are `matrix coordinate` with the `real`, `integer` or `pattern` field (pattern entries get the weight `1.0`),
+//! and the `general`, `symmetric` or `skew-symmetric` symmetry. Symmetric matrices only store one triangle, so the mirrored edge is added for every off-diagonal entry.
+//!
+//! [Matrix Market coordinate format]: https://math.nist.gov/MatrixMarket/formats.html
+//!
+//! # Example
+//! ```
+//! use fast_graph::io::matrix_market::from_matrix_market;
+//! use fast_graph::*;
+//!
+//! let mtx = "%%MatrixMarket matrix coordinate real symmetric
+//! % a comment
+//! 3 3 2
+//! 2 1 0.5
+//! 3 3 2.0
+//! ";
+//! let (graph, nodes) = from_matrix_market(mtx.as_bytes()).unwrap();
+//!
+//! assert_eq!(graph.node_count(), 3);
+//! // The off-diagonal entry is mirrored, the diagonal one is not.
+//! assert_eq!(graph.edges.len(), 3);
+//! assert_eq!(graph.node(nodes[0]).unwrap().connections.len(), 2);
+//! ```
+
+use std::io::{BufRead, BufReader, Read};
+
+use thiserror::Error;
+
+use crate::{Graph, GraphInterface, NodeID};
+
+#[derive(Debug, Error)]
+pub enum MatrixMarketError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Parse error on line {line}: {message}")]
+    Parse { line: usize, message: String },
+    #[error("Unsupported Matrix Market format: {0}")]
+    Unsupported(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Real,
+    Pattern,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Symmetry {
+    General,
+    Symmetric,
+    SkewSymmetric,
+}
+
+/// Reads a Matrix Market coordinate file into a graph.
+///
+/// Returns the graph and the created nodes, where `nodes[i]` is the node of the (1-based) row/column index `i + 1`.
+///
+/// The sizes in the header aren't trusted for allocations: the entries are read and checked against them first, and the graph
+/// is only built once the number of entries matches. Nodes are only created up to the largest index that has an entry, so
+/// trailing rows/columns without entries (e.g. of a header declaring far more rows than the file holds) get no node.
+pub fn from_matrix_market<R: Read>(
+    reader: R,
+) -> Result<(Graph<(), f64>, Vec<NodeID>), MatrixMarketError> {
+    let mut lines = BufReader::new(reader).lines().enumerate();
+
+    let (field, symmetry) = match lines.next() {
+        Some((_, header)) => parse_header(&header?)?,
+        None => return Err(parse_error(1, "missing header")),
+    };
+
+    // (rows or columns, entries)
+    let mut size: Option<(usize, usize)> = None;
+    // (row index, column index, value), 0-based.
+    let mut entries: Vec<(usize, usize, f64)> = Vec::new();
+    let mut last_line = 1;
+
+    for (index, line) in lines {
+        let line = line?;
+        let line_number = index + 1;
+        last_line = line_number;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('%') {
+            continue;
+        }
+        let fields: Vec<&str> = trimmed.split_whitespace().collect();
+
+        let Some((node_count, _)) = size else {
+            // The first non-comment line holds the dimensions.
+            if fields.len() != 3 {
+                return Err(parse_error(line_number, "expected `rows columns entries`"));
+            }
+            let rows = parse_index(fields[0], line_number)?;
+            let columns = parse_index(fields[1], line_number)?;
+            let expected_entries = parse_index(fields[2], line_number)?;
+            size = Some((rows.max(columns), expected_entries));
+            continue;
+        };
+
+        let expected_fields = if field == Field::Pattern { 2 } else { 3 };
+        if fields.len() < expected_fields {
+            return Err(parse_error(line_number, "too few columns"));
+        }
+        let row = parse_index(fields[0], line_number)?;
+        let column = parse_index(fields[1], line_number)?;
+        let (Some(from), Some(to)) = (
+            row.checked_sub(1).filter(|i| *i < node_count),
+            column.checked_sub(1).filter(|i| *i < node_count),
+        ) else {
+            return Err(parse_error(
+                line_number,
+                format!("index ({}, {}) is out of bounds", row, column),
+            ));
+        };
+        let value = match field {
+            Field::Pattern => 1.0,
+            Field::Real => fields[2].parse::<f64>().map_err(|e| {
+                parse_error(line_number, format!("invalid value `{}`: {}", fields[2], e))
+            })?,
+        };
+        entries.push((from, to, value));
+    }
+
+    let Some((_, expected_entries)) = size else {
+        return Err(parse_error(last_line, "missing size line"));
+    };
+    if entries.len() != expected_entries {
+        return Err(parse_error(
+            last_line,
+            format!(
+                "expected {} entries, found {}",
+                expected_entries,
+                entries.len()
+            ),
+        ));
+    }
+
+    let mirrored = match symmetry {
+        Symmetry::General => 0,
+        _ => entries.iter().filter(|(from, to, _)| from != to).count(),
+    };
+    // Bounded by the indices in the file rather than by the header.
+    let node_count = entries
+        .iter()
+        .map(|(from, to, _)| from.max(to) + 1)
+        .max()
+        .unwrap_or(0);
+    let mut graph = Graph::with_capacity(node_count, entries.len() + mirrored);
+    let nodes: Vec<NodeID> = (0..node_count).map(|_| graph.add_node(())).collect();
+    for (from, to, value) in entries {
+        let (from, to) = (nodes[from], nodes[to]);
+        graph.add_edge(from, to, value);
+        if from != to {
+            match symmetry {
+                Symmetry::General => {}
+                Symmetry::Symmetric => {
+                    graph.add_edge(to, from, value);
+                }
+                Symmetry::SkewSymmetric => {
+                    graph.add_edge(to, from, -value);
+                }
+            }
+        }
+    }
+    Ok((graph, nodes))
+}
+
+fn parse_header(header: &str) -> Result<(Field, Symmetry), MatrixMarketError> {
+    let tokens: Vec<String> = header
+        .split_whitespace()
+        .map(|token| token.to_lowercase())
+        .collect();
+    if tokens.len() != 5 || tokens[0] != "%%matrixmarket" {
+        return Err(parse_error(1, "expected `%%MatrixMarket` header"));
+    }
+    if tokens[1] != "matrix" || tokens[2] != "coordinate" {
+        return Err(MatrixMarketError::Unsupported(format!(
+            "{} {}",
+            tokens[1], tokens[2]
+        )));
+    }
+    let field = match tokens[3].as_str() {
+        "real" | "integer" | "double" => Field::Real,
+        "pattern" => Field::Pattern,
+        other => return Err(MatrixMarketError::Unsupported(other.to_string())),
+    };
+    let symmetry = match tokens[4].as_str() {
+        "general" => Symmetry::General,
+        "symmetric" => Symmetry::Symmetric,
+        "skew-symmetric" => Symmetry::SkewSymmetric,
+        other => return Err(MatrixMarketError::Unsupported(other.to_string())),
+    };
+    Ok((field, symmetry))
+}
+
+fn parse_index(value: &str, line: usize) -> Result<usize, MatrixMarketError> {
+    value
+        .parse::<usize>()
+        .map_err(|e| parse_error(line, format!("invalid integer `{}`: {}", value, e)))
+}
+
+fn parse_error(line: usize, message: impl Into<String>) -> MatrixMarketError {
+    MatrixMarketError::Parse {
+        line,
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_general_and_pattern() {
+        let mtx = "%%MatrixMarket matrix coordinate pattern general\n2 3 2\n1 2\n2 3\n";
+        let (graph, nodes) = from_matrix_market(mtx.as_bytes()).unwrap();
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(graph.edges.len(), 2);
+        assert!(graph.edges.values().all(|edge| edge.data == 1.0));
+
+        let skew = "%%MatrixMarket matrix coordinate integer skew-symmetric\n2 2 1\n2 1 4\n";
+        let (graph, nodes) = from_matrix_market(skew.as_bytes()).unwrap();
+        let mirrored = graph.edges.values().find(|edge| edge.from == nodes[0]);
+        assert_eq!(mirrored.unwrap().data, -4.0);
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let dense = "%%MatrixMarket matrix array real general\n1 1\n1.0\n";
+        assert!(matches!(
+            from_matrix_market(dense.as_bytes()),
+            Err(MatrixMarketError::Unsupported(_))
+        ));
+
+        let out_of_bounds = "%%MatrixMarket matrix coordinate real general\n2 2 1\n3 1 1.0\n";
+        assert!(matches!(
+            from_matrix_market(out_of_bounds.as_bytes()),
+            Err(MatrixMarketError::Parse { line: 3, .. })
+        ));
+
+        let truncated = "%%MatrixMarket matrix coordinate real general\n2 2 2\n1 1 1.0\n";
+        assert!(matches!(
+            from_matrix_market(truncated.as_bytes()),
+            Err(MatrixMarketError::Parse { line: 3, .. })
+        ));
+
+        // A huge header with a truncated body fails before anything is allocated for it.
+        let huge = format!(
+            "%%MatrixMarket matrix coordinate real symmetric\n{0} {0} {0}\n1 1 1.0\n",
+            usize::MAX
+        );
+        assert!(matches!(
+            from_matrix_market(huge.as_bytes()),
+            Err(MatrixMarketError::Parse { line: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn test_huge_declared_size() {
+        // Only the indices that have entries become nodes, not the declared rows and columns.
+        let empty = format!(
+            "%%MatrixMarket matrix coordinate real general\n{0} {0} 0\n",
+            usize::MAX
+        );
+        let (graph, nodes) = from_matrix_market(empty.as_bytes()).unwrap();
+        assert!(nodes.is_empty() && graph.nodes.is_empty());
+
+        let sparse =
+            "%%MatrixMarket matrix coordinate real symmetric\n1000000000 1000000000 1\n3 1 2.0\n";
+        let (graph, nodes) = from_matrix_market(sparse.as_bytes()).unwrap();
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(graph.edges.len(), 2);
+    }
+}
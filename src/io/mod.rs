@@ -0,0 +1,11 @@
+//! # Import & export of graphs to common interchange formats.
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod binary;
+pub mod csv;
+pub mod matrix_market;
+#[cfg(feature = "ndarray")]
+pub mod ndarray;
+#[cfg(feature = "parquet")]
+pub mod parquet;
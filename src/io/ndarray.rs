@@ -0,0 +1,106 @@
+//! # Adjacency matrix export to [ndarray].
+//!
+//! Requires the `ndarray` feature.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//!
+//! let mut graph: Graph<(), f64> = Graph::new();
+//! let a = graph.add_node(());
+//! let b = graph.add_node(());
+//! graph.add_edge(a, b, 2.5);
+//!
+//! let adjacency = graph.to_adjacency_matrix(|edge| edge.data);
+//! let (row, column) = (adjacency.rows[&a], adjacency.rows[&b]);
+//! assert_eq!(adjacency.matrix[[row, column]], 2.5);
+//! assert_eq!(adjacency.matrix[[column, row]], 0.0);
+//! assert_eq!(adjacency.nodes[row], a);
+//! ```
+
+use std::ops::AddAssign;
+
+use crate::hash::HashMap;
+
+use ndarray::Array2;
+
+use crate::{Edge, Graph, NodeID};
+
+/// A dense adjacency matrix together with the mapping between matrix rows/columns and nodes.
+#[derive(Debug, Clone)]
+pub struct AdjacencyMatrix<W> {
+    /// `matrix[[i, j]]` is the combined weight of all edges from `nodes[i]` to `nodes[j]`.
+    pub matrix: Array2<W>,
+    /// The node of every row/column.
+    pub nodes: Vec<NodeID>,
+    /// The row/column of every node.
+    pub rows: HashMap<NodeID, usize>,
+}
+
+impl<N, E> Graph<N, E> {
+    /// Builds the directed adjacency matrix of the graph, with rows/columns in node iteration order.
+    ///
+    /// `weight_fn` maps every edge to its weight. Entries without an edge are `W::default()`, and parallel edges are summed.
+    pub fn to_adjacency_matrix<W>(&self, weight_fn: impl Fn(&Edge<E>) -> W) -> AdjacencyMatrix<W>
+    where
+        W: Clone + Default + AddAssign,
+    {
+        let nodes: Vec<NodeID> = self.nodes.keys().collect();
+        self.to_adjacency_matrix_ordered(nodes, weight_fn)
+    }
+
+    /// Like [Graph::to_adjacency_matrix], but with the rows/columns in the given order.
+    ///
+    /// Edges touching nodes that aren't in `order` are left out.
+    pub fn to_adjacency_matrix_ordered<W>(
+        &self,
+        order: Vec<NodeID>,
+        weight_fn: impl Fn(&Edge<E>) -> W,
+    ) -> AdjacencyMatrix<W>
+    where
+        W: Clone + Default + AddAssign,
+    {
+        let rows: HashMap<NodeID, usize> = order
+            .iter()
+            .enumerate()
+            .map(|(index, id)| (*id, index))
+            .collect();
+        let mut matrix = Array2::default((order.len(), order.len()));
+        for edge in self.edge_references() {
+            if let (Some(&from), Some(&to)) = (rows.get(&edge.from), rows.get(&edge.to)) {
+                matrix[[from, to]] += weight_fn(edge);
+            }
+        }
+        AdjacencyMatrix {
+            matrix,
+            nodes: order,
+            rows,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GraphInterface;
+
+    #[test]
+    fn test_parallel_edges_and_order() {
+        let mut graph: Graph<(), u32> = Graph::new();
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        graph.add_edge(a, b, 1);
+        graph.add_edge(a, b, 2);
+        graph.add_edge(c, c, 5);
+
+        let adjacency = graph.to_adjacency_matrix_ordered(vec![c, a, b], |_| 1u32);
+        assert_eq!(adjacency.matrix.dim(), (3, 3));
+        assert_eq!(adjacency.matrix[[1, 2]], 2);
+        assert_eq!(adjacency.matrix[[0, 0]], 1);
+        assert_eq!(adjacency.matrix.sum(), 3);
+
+        let partial = graph.to_adjacency_matrix_ordered(vec![a, c], |edge| edge.data);
+        assert_eq!(partial.matrix.sum(), 5);
+    }
+}
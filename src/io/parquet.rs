@@ -0,0 +1,55 @@
+//! # Parquet export.
+//!
+//! Requires the `parquet` feature. Writes the record batches from [arrow](super::arrow) to Parquet files, one file for nodes and one for edges.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//!
+//! let mut graph: Graph<&str, ()> = Graph::new();
+//! let a = graph.add_node("A");
+//! let b = graph.add_node("B");
+//! graph.add_edge(a, b, ());
+//!
+//! let mut nodes = Vec::new();
+//! let mut edges = Vec::new();
+//! graph.write_nodes_parquet(&mut nodes, |data| data.to_string()).unwrap();
+//! graph.write_edges_parquet(&mut edges, |_| String::new()).unwrap();
+//!
+//! assert!(nodes.starts_with(b"PAR1"));
+//! ```
+
+use std::io::Write;
+
+use parquet::arrow::ArrowWriter;
+use parquet::errors::ParquetError;
+
+use crate::Graph;
+
+impl<N, E> Graph<N, E> {
+    /// Writes all nodes to `writer` as a Parquet file with the [node schema](super::arrow::node_schema).
+    pub fn write_nodes_parquet<W: Write + Send>(
+        &self,
+        writer: W,
+        data_fn: impl Fn(&N) -> String,
+    ) -> Result<(), ParquetError> {
+        let batch = self.nodes_to_record_batch(data_fn)?;
+        let mut writer = ArrowWriter::try_new(writer, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(())
+    }
+
+    /// Writes all edges to `writer` as a Parquet file with the [edge schema](super::arrow::edge_schema).
+    pub fn write_edges_parquet<W: Write + Send>(
+        &self,
+        writer: W,
+        data_fn: impl Fn(&E) -> String,
+    ) -> Result<(), ParquetError> {
+        let batch = self.edges_to_record_batch(data_fn)?;
+        let mut writer = ArrowWriter::try_new(writer, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(())
+    }
+}
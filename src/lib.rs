@@ -30,7 +30,9 @@
 //!
 //! [Edge] - Struct representing an edge in the graph. Contains an [EdgeID] which is a key to the edge in the slotmap, and two [NodeID]s which are the nodes the edge connects (from & to). An edge can also have "data", which could be anything or nothing; for example the weight of the connection or a struct or enum representing something else.
 //!
-//! [GraphInterface] - Trait defining methods to alter a graph, i.e. adding, removing, and editing nodes and edges.
+//! [GraphRef] - Trait defining read-only methods of a graph, i.e. counting, iterating and looking up nodes and edges. Algorithms only require this trait.
+//!
+//! [GraphInterface] - Trait extending [GraphRef] with methods to alter a graph, i.e. adding, removing, and editing nodes and edges.
 //!
 //!
 //! [Graph] - The default graph struct which implements [GraphInterface]. It only contains two slotmaps, one for nodes and one for edges.
@@ -39,6 +41,7 @@
 //!
 //! [CategorizedGraph] - A graph with categories. Categories are normal nodes (which can contain edges & data), but the graph also contains a hashmap that maps category names to category nodes for easy access.
 //!
+//! [PersistentGraph] - A copy-on-write [Graph] that can take cheap point-in-time [snapshots](GraphSnapshot), restore them and [diff](GraphDiff) against them.
 //!
 //! # Examples
 //!
@@ -47,7 +50,7 @@
 //! ```
 //! use fast_graph::{Graph, Node, Edge};
 //! /* We need to have this trait in scope: */
-//! use fast_graph::{GraphInterface};
+//! use fast_graph::{GraphInterface, GraphRef};
 //!
 //! #[derive(Debug, Clone)]
 //! struct EdgeData(String);
@@ -78,6 +81,8 @@
 //! ## [CategorizedGraph] example
 //! ```
 //! use fast_graph::*;
+//! # #[cfg(not(feature = "categories"))] fn main() {}
+//! # #[cfg(feature = "categories")] fn main() {
 //!
 //! #[derive(Clone, Debug, Default, PartialEq)]
 //! #[cfg_attr(feature = "serde", derive(serde::Serialize))]
@@ -134,11 +139,13 @@
 //!         vec![node3], NodeData::CategoryData("Category 1".into())
 //!     ).is_err()
 //! );
+//! # }
 //! ```
 
 use core::fmt;
 use std::fmt::Formatter;
 
+use hash::HashMap;
 pub use slotmap::SlotMap;
 use thiserror::Error;
 
@@ -147,18 +154,63 @@ pub mod categories;
 
 #[cfg(feature = "categories")]
 pub use categories::*;
+#[cfg(feature = "categories")]
+pub mod query;
+#[cfg(feature = "categories")]
+pub mod tags;
 
+pub mod adjacency;
 pub mod algorithms;
+pub mod branded;
+pub mod builder;
+pub mod commands;
+pub mod compare;
+pub mod constructions;
+pub mod diagnostics;
+pub mod display;
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+pub mod fuzzing;
+#[cfg(feature = "rand")]
+pub mod generators;
+pub mod hash;
+pub mod implicit;
+pub mod io;
+pub mod locality;
+pub mod metrics;
+pub mod path_cache;
+pub mod persistent;
+pub mod plain;
+#[cfg(feature = "properties")]
+pub mod properties;
+pub mod rewrite;
+#[cfg(feature = "rand")]
+pub mod sampling;
+pub mod signature;
+pub mod spatial;
+pub mod stats;
+#[cfg(feature = "async")]
+pub mod stream;
+pub mod svg;
+pub mod view;
 
 mod edge;
 mod interface;
 mod node;
+#[cfg(feature = "ordered")]
+mod order;
+#[cfg(feature = "serde")]
+mod serde_impls;
 #[cfg(feature = "specta")]
 mod specta_derives;
 
+pub use branded::{Branded, BrandedGraph};
+pub use builder::{BuildError, GraphBuilder, IdMap};
 pub use edge::{Edge, EdgeID};
-pub use interface::GraphInterface;
-pub use node::{Node, NodeID};
+pub(crate) use interface::outgoing_edges;
+pub use interface::{BoxedGraph, DynGraph, GraphInterface, GraphRef};
+pub use node::{Connections, Node, NodeID};
+pub use persistent::{GraphDiff, GraphSnapshot, PersistentGraph};
+pub use spatial::{InvalidRadius, SpatialGraph};
 
 /* -------------------------------------------------------------------------- */
 /*                 Simple very performant graph implementation                */
@@ -167,12 +219,14 @@ pub use node::{Node, NodeID};
 /* ---------------------------------- Graph --------------------------------- */
 /// The default Graph struct which implements the [GraphInterface] trait.
 ///
+/// Cloning a graph copies its slotmaps as they are, so every [NodeID] and [EdgeID] of the original refers to the same node or edge
+/// in the clone (and stays valid there until it is removed from the clone). [Graph::clone_with_mapping] makes a compacted copy instead.
 ///
 /// # Examples
 /// ```
 /// use fast_graph::{Graph, Node, Edge};
 /// /* We need to have this trait in scope: */
-/// use fast_graph::{GraphInterface};
+/// use fast_graph::{GraphInterface, GraphRef};
 ///
 /// #[derive(Clone, Debug)]
 /// struct EdgeData(String);
@@ -196,12 +250,19 @@ pub use node::{Node, NodeID};
 /// println!("{:#?}", graph);
 ///
 /// ```
+#[derive(Clone)]
 pub struct Graph<N, E> {
     pub nodes: SlotMap<NodeID, Node<N>>,
     pub edges: SlotMap<EdgeID, Edge<E>>,
+    /// If true, [GraphInterface::add_edge] panics when `from` or `to` doesn't exist instead of adding a dangling edge.
+    /// Use [GraphInterface::try_add_edge] to get an error instead.
+    pub strict: bool,
+    stats: stats::GraphStats,
+    #[cfg(feature = "ordered")]
+    order: order::InsertionOrder,
 }
 
-impl<N, E> GraphInterface for Graph<N, E> {
+impl<N, E> GraphRef for Graph<N, E> {
     type NodeData = N;
     type EdgeData = E;
 
@@ -214,43 +275,60 @@ impl<N, E> GraphInterface for Graph<N, E> {
     }
 
     fn node(&self, id: NodeID) -> Result<&Node<N>, GraphError> {
-        self.nodes.get(id).ok_or(GraphError::NodeNotFound)
+        self.nodes
+            .get(id)
+            .ok_or_else(|| GraphError::node_not_found(id))
     }
 
-    fn node_mut(&mut self, id: NodeID) -> Result<&mut Node<N>, GraphError> {
-        self.nodes.get_mut(id).ok_or(GraphError::NodeNotFound)
+    fn edge(&self, id: EdgeID) -> Result<&Edge<E>, GraphError> {
+        self.edges
+            .get(id)
+            .ok_or_else(|| GraphError::edge_not_found(id))
     }
+}
 
-    fn edge(&self, id: EdgeID) -> Result<&Edge<E>, GraphError> {
-        self.edges.get(id).ok_or(GraphError::EdgeNotFound)
+impl<N, E> GraphInterface for Graph<N, E> {
+    fn node_mut(&mut self, id: NodeID) -> Result<&mut Node<N>, GraphError> {
+        self.nodes
+            .get_mut(id)
+            .ok_or_else(|| GraphError::node_not_found(id))
     }
 
     fn edge_mut(&mut self, id: EdgeID) -> Result<&mut Edge<E>, GraphError> {
-        self.edges.get_mut(id).ok_or(GraphError::EdgeNotFound)
+        self.edges
+            .get_mut(id)
+            .ok_or_else(|| GraphError::edge_not_found(id))
     }
 
     fn remove_node(&mut self, id: NodeID) -> Result<(), GraphError> {
-        let node = self
-            .nodes
-            .remove(id)
-            .map_or(Err(GraphError::NodeNotFound), |n| Ok(n))?;
+        let node = self.nodes.remove(id).ok_or(GraphError::NodeNotFound {
+            id,
+            during: GraphOperation::RemoveNode(id),
+        })?;
+        self.stats.nodes_removed += 1;
         for edge_id in node.connections.iter() {
-            self.edges
-                .remove(*edge_id)
-                .map_or(Err(GraphError::EdgeNotFound), |_| Ok(()))?;
+            // The edge may already be gone (e.g. removed earlier, or a self-loop listed twice).
+            if self.edges.remove(*edge_id).is_some() {
+                self.stats.edges_removed += 1;
+            }
         }
         Ok(())
     }
 
     fn remove_edge(&mut self, id: EdgeID) -> Result<(), GraphError> {
-        self.edges
-            .remove(id)
-            .map_or(Err(GraphError::EdgeNotFound), |_| Ok(()))?;
+        self.edges.remove(id).ok_or(GraphError::EdgeNotFound {
+            id,
+            during: GraphOperation::RemoveEdge(id),
+        })?;
+        self.stats.edges_removed += 1;
         Ok(())
     }
 
     fn add_node(&mut self, data: N) -> NodeID {
         let id = self.nodes.insert_with_key(|id| Node::new(id, data));
+        #[cfg(feature = "ordered")]
+        self.order.add_node(id);
+        self.stats.add_node(self.nodes.len());
         id
     }
 
@@ -280,9 +358,17 @@ impl<N, E> GraphInterface for Graph<N, E> {
     }
 
     fn add_edge(&mut self, from: NodeID, to: NodeID, data: E) -> EdgeID {
+        if self.strict {
+            if let Err(error) = interface::check_endpoints(self, from, to) {
+                panic!("{}", error);
+            }
+        }
         let id = self
             .edges
             .insert_with_key(|id| Edge::new(id, from, to, data));
+        #[cfg(feature = "ordered")]
+        self.order.add_edge(id);
+        self.stats.add_edge(self.edges.len());
         if let Some(node) = self.nodes.get_mut(from) {
             node.add_connection(id);
         }
@@ -293,6 +379,34 @@ impl<N, E> GraphInterface for Graph<N, E> {
     }
 }
 
+/// The rows of [Graph::rows], shared with [CategorizedGraph::rows].
+#[allow(clippy::type_complexity)]
+pub(crate) fn rows<'a, N, E>(
+    nodes: &'a SlotMap<NodeID, Node<N>>,
+    edges: &'a SlotMap<EdgeID, Edge<E>>,
+) -> impl Iterator<
+    Item = (
+        NodeID,
+        &'a N,
+        impl Iterator<Item = (EdgeID, NodeID, &'a E)> + Clone,
+    ),
+> + Clone {
+    nodes.iter().map(move |(id, node)| {
+        let outgoing = node
+            .connections
+            .iter()
+            .enumerate()
+            .filter_map(move |(i, edge_id)| {
+                let edge = edges.get(*edge_id)?;
+                // Self-loops are listed twice in the connections, only they need the (linear) duplicate check.
+                let repeated =
+                    edge.from == id && edge.to == id && node.connections[..i].contains(edge_id);
+                (edge.from == id && !repeated).then_some((edge.id, edge.to, &edge.data))
+            });
+        (id, &node.data, outgoing)
+    })
+}
+
 impl<N: fmt::Debug + Clone, E: fmt::Debug + Clone> fmt::Debug for Graph<N, E> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -303,20 +417,459 @@ impl<N: fmt::Debug + Clone, E: fmt::Debug + Clone> fmt::Debug for Graph<N, E> {
     }
 }
 
+impl<N, E> Default for Graph<N, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<N, E> Graph<N, E> {
     pub fn new() -> Graph<N, E> {
         Graph {
             nodes: SlotMap::with_key(),
             edges: SlotMap::with_key(),
+            strict: false,
+            stats: Default::default(),
+            #[cfg(feature = "ordered")]
+            order: Default::default(),
+        }
+    }
+
+    /// Creates an empty graph with room for at least `nodes` nodes and `edges` edges without reallocating.
+    pub fn with_capacity(nodes: usize, edges: usize) -> Graph<N, E> {
+        Graph {
+            nodes: SlotMap::with_capacity_and_key(nodes),
+            edges: SlotMap::with_capacity_and_key(edges),
+            strict: false,
+            stats: Default::default(),
+            #[cfg(feature = "ordered")]
+            order: Default::default(),
         }
     }
+
+    /// Removes the connections to edges that no longer exist from every node in one pass. Returns the number of connections removed.
+    ///
+    /// [Graph] deletes lazily: [GraphInterface::remove_edge] and [GraphInterface::remove_node] only remove from the slotmaps
+    /// and leave the removed [EdgeID]s in the connections of the nodes on the other end, which is what makes mass deletions cheap.
+    /// Those stale connections are skipped by lookups and traversals, and this cleans them up in bulk.
+    pub fn vacuum(&mut self) -> usize {
+        vacuum_connections(&mut self.nodes, &self.edges)
+    }
+
+    /// Removes the edges whose `from` or `to` node no longer exists, returning their IDs (e.g. for logging).
+    ///
+    /// Such edges are left behind by [GraphInterface::add_edge] with a missing endpoint (outside of [strict](Graph::strict) mode), or when a node
+    /// is removed while its connections are out of date. The removed edges are also removed from the connections of the endpoint that still exists.
+    pub fn collect_dangling_edges(&mut self) -> Vec<EdgeID> {
+        let removed = collect_dangling_edges(&mut self.nodes, &mut self.edges);
+        self.stats.edges_removed += removed.len() as u64;
+        removed
+    }
+
+    /// Swaps the `from` and `to` of an edge in place, keeping its ID and data.
+    pub fn reverse_edge(&mut self, id: EdgeID) -> Result<(), GraphError> {
+        let edge = self.edge_mut(id)?;
+        std::mem::swap(&mut edge.from, &mut edge.to);
+        Ok(())
+    }
+
+    /// Moves an edge to new endpoints in place, keeping its ID and data, and updates the connections of the old and new endpoints.
+    ///
+    /// Fails without changing anything if the edge or one of the new endpoints doesn't exist.
+    pub fn redirect_edge(
+        &mut self,
+        id: EdgeID,
+        new_from: NodeID,
+        new_to: NodeID,
+    ) -> Result<(), GraphError> {
+        let during = GraphOperation::RedirectEdge {
+            id,
+            from: new_from,
+            to: new_to,
+        };
+        let edge = self.edge(id).map_err(|e| e.during(during))?;
+        let (old_from, old_to) = (edge.from, edge.to);
+        for endpoint in [new_from, new_to] {
+            self.node(endpoint).map_err(|e| e.during(during))?;
+        }
+
+        // A self-loop is listed twice in the connections of its node, so one entry is removed (and added) per endpoint.
+        for endpoint in [old_from, old_to] {
+            if let Some(node) = self.nodes.get_mut(endpoint) {
+                if let Some(index) = node.connections.iter().position(|edge| *edge == id) {
+                    node.connections.remove(index);
+                }
+            }
+        }
+        for endpoint in [new_from, new_to] {
+            self.nodes[endpoint].add_connection(id);
+        }
+        let edge = &mut self.edges[id];
+        edge.from = new_from;
+        edge.to = new_to;
+        Ok(())
+    }
+
+    /// Returns the groups of parallel edges, i.e. edges with the same `from` and `to` (in that order), that have more than one edge.
+    ///
+    /// Within a group the edges are in slot order, which is the order [Graph::dedup_edges] merges them in.
+    pub fn parallel_edge_groups(&self) -> Vec<Vec<EdgeID>> {
+        let mut groups: Vec<Vec<EdgeID>> = Vec::new();
+        let mut group_of: HashMap<(NodeID, NodeID), usize> = HashMap::default();
+        for (id, edge) in self.edges.iter() {
+            let index = *group_of.entry((edge.from, edge.to)).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[index].push(id);
+        }
+        groups.retain(|group| group.len() > 1);
+        groups
+    }
+
+    /// Collapses every group of parallel edges (see [Graph::parallel_edge_groups]) into its first edge, returning the number of edges removed.
+    ///
+    /// The data of every other edge in the group is passed to `merge` together with the data of the kept edge, e.g. to sum weights.
+    /// The removed edges are also removed from the connections of their endpoints.
+    pub fn dedup_edges(&mut self, mut merge: impl FnMut(&mut E, E)) -> usize {
+        let mut removed = 0;
+        for group in self.parallel_edge_groups() {
+            let (keep, rest) = group.split_first().expect("groups have at least two edges");
+            for id in rest {
+                let edge = self.edges.remove(*id).expect("grouped edges exist");
+                for endpoint in [edge.from, edge.to] {
+                    if let Some(node) = self.nodes.get_mut(endpoint) {
+                        node.connections.retain(|connection| connection != id);
+                    }
+                }
+                merge(&mut self.edges[*keep].data, edge.data);
+                removed += 1;
+            }
+        }
+        self.stats.edges_removed += removed as u64;
+        removed
+    }
+
+    /// Returns every edge as `(from, to, &data)`, in slot order. This is the simplest way to consume the edge list, e.g. in an exporter.
+    pub fn edge_triples(&self) -> impl Iterator<Item = (NodeID, NodeID, &E)> + Clone {
+        self.edges
+            .values()
+            .map(|edge| (edge.from, edge.to, &edge.data))
+    }
+
+    /// Returns a reference to every edge (with its ID, endpoints and data), in slot order.
+    pub fn edge_references(&self) -> impl Iterator<Item = &Edge<E>> + Clone {
+        self.edges.values()
+    }
+
+    /// Returns every node with its data and its outgoing edges as `(edge, target, &data)`, in slot order and connection order.
+    ///
+    /// The edges are looked up directly in the slotmap, so this is the cheapest single pass over the whole adjacency, e.g. in an exporter.
+    /// Connections to removed edges are skipped and a self-loop is listed once.
+    #[allow(clippy::type_complexity)]
+    pub fn rows(
+        &self,
+    ) -> impl Iterator<
+        Item = (
+            NodeID,
+            &N,
+            impl Iterator<Item = (EdgeID, NodeID, &E)> + Clone,
+        ),
+    > + Clone {
+        rows(&self.nodes, &self.edges)
+    }
+
+    /// Returns the node IDs in the order the nodes were added (requires the `ordered` feature).
+    ///
+    /// Unlike [GraphInterface::nodes], which follows the slot order and so depends on which slots were freed and reused, this is reproducible.
+    /// A deserialized graph starts out in slot order, and the nodes added afterwards follow it. This sorts, so it's O(n log n).
+    #[cfg(feature = "ordered")]
+    pub fn nodes_ordered(&self) -> Vec<NodeID> {
+        self.order.sort_nodes(self.nodes.keys())
+    }
+
+    /// Returns the edge IDs in the order the edges were added (requires the `ordered` feature), see [Graph::nodes_ordered].
+    #[cfg(feature = "ordered")]
+    pub fn edges_ordered(&self) -> Vec<EdgeID> {
+        self.order.sort_edges(self.edges.keys())
+    }
+
+    /// Rebuilds the graph into fresh, densely packed slotmaps, dropping the free slots and version counters left behind by removals.
+    ///
+    /// Nodes and edges keep their relative order, so iteration afterwards touches contiguous memory.
+    /// Connections to edges that no longer exist and edges whose endpoints no longer exist are dropped.
+    ///
+    /// Every [NodeID] and [EdgeID] changes, so the mapping from old to new node IDs is returned.
+    /// [Graph::compact_with_mapping] also returns the mapping of the edge IDs.
+    pub fn compact(&mut self) -> HashMap<NodeID, NodeID> {
+        self.compact_with_mapping().nodes
+    }
+
+    /// Returns a compacted copy of the graph (see [Graph::compact]) together with the mapping from the IDs of this graph to the IDs of the copy.
+    ///
+    /// Use [Clone] instead to keep the IDs.
+    pub fn clone_with_mapping(&self) -> (Self, IdMapping)
+    where
+        N: Clone,
+        E: Clone,
+    {
+        let mut copy = self.clone();
+        let mapping = copy.compact_with_mapping();
+        (copy, mapping)
+    }
+
+    /// Like [Graph::compact], but returns the mapping of both node and edge IDs.
+    pub fn compact_with_mapping(&mut self) -> IdMapping {
+        let (node_count, edge_count) = (self.nodes.len(), self.edges.len());
+        let old_nodes =
+            std::mem::replace(&mut self.nodes, SlotMap::with_capacity_and_key(node_count));
+        let old_edges =
+            std::mem::replace(&mut self.edges, SlotMap::with_capacity_and_key(edge_count));
+
+        // With the `ordered` feature the new slots follow the insertion order, which is then renumbered.
+        #[cfg(feature = "ordered")]
+        let (old_nodes, old_edges) = {
+            let (mut old_nodes, mut old_edges) = (old_nodes, old_edges);
+            let order = std::mem::take(&mut self.order);
+            let nodes = order.sort_nodes(old_nodes.keys());
+            let edges = order.sort_edges(old_edges.keys());
+            (
+                nodes
+                    .into_iter()
+                    .filter_map(|id| Some((id, old_nodes.remove(id)?)))
+                    .collect::<Vec<_>>(),
+                edges
+                    .into_iter()
+                    .filter_map(|id| Some((id, old_edges.remove(id)?)))
+                    .collect::<Vec<_>>(),
+            )
+        };
+
+        let mut node_ids: HashMap<NodeID, NodeID> =
+            HashMap::with_capacity_and_hasher(old_nodes.len(), Default::default());
+        let mut connections = Vec::with_capacity(old_nodes.len());
+        for (old_id, node) in old_nodes {
+            let id = self.nodes.insert_with_key(|id| Node::new(id, node.data));
+            #[cfg(feature = "ordered")]
+            self.order.add_node(id);
+            node_ids.insert(old_id, id);
+            connections.push((id, node.connections));
+        }
+
+        let mut edge_ids: HashMap<EdgeID, EdgeID> =
+            HashMap::with_capacity_and_hasher(old_edges.len(), Default::default());
+        for (old_id, edge) in old_edges {
+            if let (Some(from), Some(to)) = (node_ids.get(&edge.from), node_ids.get(&edge.to)) {
+                let id = self
+                    .edges
+                    .insert_with_key(|id| Edge::new(id, *from, *to, edge.data));
+                #[cfg(feature = "ordered")]
+                self.order.add_edge(id);
+                edge_ids.insert(old_id, id);
+            }
+        }
+
+        for (id, old_connections) in connections {
+            self.nodes[id].connections = old_connections
+                .iter()
+                .filter_map(|edge| edge_ids.get(edge).copied())
+                .collect();
+        }
+        // Only dangling edges are dropped.
+        self.stats.edges_removed += (edge_count - self.edges.len()) as u64;
+
+        IdMapping {
+            nodes: node_ids,
+            edges: edge_ids,
+        }
+    }
+
+    /// Checks that the node and edge slotmaps are consistent with each other:
+    /// every node and edge is stored under its own ID, every edge's endpoints exist and list the edge in their connections,
+    /// and every connection that still resolves to an edge is incident to the node.
+    ///
+    /// Returns [GraphError::InvalidGraph] describing the first inconsistency found.
+    pub fn validate(&self) -> Result<(), GraphError> {
+        self.check_consistency(false)
+    }
+
+    /// Like [Graph::validate], but accepts dangling edges (see [Graph::collect_dangling_edges]), which the graph itself can produce.
+    #[cfg(feature = "serde")]
+    pub(crate) fn validate_allowing_dangling(&self) -> Result<(), GraphError> {
+        self.check_consistency(true)
+    }
+
+    fn check_consistency(&self, allow_dangling: bool) -> Result<(), GraphError> {
+        for (id, node) in self.nodes.iter() {
+            if node.id != id {
+                return Err(GraphError::InvalidGraph(format!(
+                    "node stored under {:?} has id {:?}",
+                    id, node.id
+                )));
+            }
+            for edge_id in &node.connections {
+                if let Some(edge) = self.edges.get(*edge_id) {
+                    if edge.from != id && edge.to != id {
+                        return Err(GraphError::InvalidGraph(format!(
+                            "node {:?} lists edge {:?} which isn't incident to it",
+                            id, edge_id
+                        )));
+                    }
+                }
+            }
+        }
+        for (id, edge) in self.edges.iter() {
+            if edge.id != id {
+                return Err(GraphError::InvalidGraph(format!(
+                    "edge stored under {:?} has id {:?}",
+                    id, edge.id
+                )));
+            }
+            for endpoint in [edge.from, edge.to] {
+                let Some(node) = self.nodes.get(endpoint) else {
+                    if allow_dangling {
+                        continue;
+                    }
+                    return Err(GraphError::InvalidGraph(format!(
+                        "edge {:?} references missing node {:?}",
+                        id, endpoint
+                    )));
+                };
+                if !node.connections.contains(&id) {
+                    return Err(GraphError::InvalidGraph(format!(
+                        "edge {:?} is missing from the connections of node {:?}",
+                        id, endpoint
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Removes the edges with a missing endpoint and their connections, returning their IDs.
+pub(crate) fn collect_dangling_edges<N, E>(
+    nodes: &mut SlotMap<NodeID, Node<N>>,
+    edges: &mut SlotMap<EdgeID, Edge<E>>,
+) -> Vec<EdgeID> {
+    let dangling: Vec<EdgeID> = edges
+        .iter()
+        .filter(|(_, edge)| !nodes.contains_key(edge.from) || !nodes.contains_key(edge.to))
+        .map(|(id, _)| id)
+        .collect();
+    for id in &dangling {
+        let edge = edges.remove(*id).expect("dangling edges exist");
+        for endpoint in [edge.from, edge.to] {
+            if let Some(node) = nodes.get_mut(endpoint) {
+                node.connections.retain(|connection| connection != id);
+            }
+        }
+    }
+    dangling
+}
+
+/// Removes the connections to edges that aren't in `edges` anymore, returning how many were removed.
+pub(crate) fn vacuum_connections<N, E>(
+    nodes: &mut SlotMap<NodeID, Node<N>>,
+    edges: &SlotMap<EdgeID, Edge<E>>,
+) -> usize {
+    let mut removed = 0;
+    for node in nodes.values_mut() {
+        let before = node.connections.len();
+        node.connections.retain(|edge| edges.contains_key(*edge));
+        removed += before - node.connections.len();
+    }
+    removed
+}
+
+/// The mapping from old to new IDs returned by [Graph::compact_with_mapping] and [Graph::clone_with_mapping].
+///
+/// Nodes and edges that were dropped (e.g. edges with missing endpoints) have no entry.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IdMapping {
+    pub nodes: HashMap<NodeID, NodeID>,
+    pub edges: HashMap<EdgeID, EdgeID>,
 }
 
 #[derive(Debug, Clone, Error)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GraphError {
-    #[error("Edge not found")]
-    EdgeNotFound,
-    #[error("Node not found")]
-    NodeNotFound,
+    #[error("Edge {id:?} not found{during}")]
+    EdgeNotFound { id: EdgeID, during: GraphOperation },
+    #[error("Node {id:?} not found{during}")]
+    NodeNotFound { id: NodeID, during: GraphOperation },
+    #[error("Invalid graph: {0}")]
+    InvalidGraph(String),
+    #[error("Node {0:?} is a category node")]
+    IsCategoryNode(NodeID),
+    #[error("ID belongs to graph {found}, not to graph {expected}")]
+    ForeignId { expected: u64, found: u64 },
+    /// The integer can't be an ID: converting it to an ID and back doesn't give the same integer (slotmap versions are always odd).
+    #[error("{0:#x} is not a valid ID")]
+    InvalidId(u64),
+}
+
+impl GraphError {
+    /// A [GraphError::NodeNotFound] for a plain lookup.
+    pub fn node_not_found(id: NodeID) -> Self {
+        GraphError::NodeNotFound {
+            id,
+            during: GraphOperation::Lookup,
+        }
+    }
+
+    /// A [GraphError::EdgeNotFound] for a plain lookup.
+    pub fn edge_not_found(id: EdgeID) -> Self {
+        GraphError::EdgeNotFound {
+            id,
+            during: GraphOperation::Lookup,
+        }
+    }
+
+    /// Sets the operation during which a node or edge was missing. Other errors are returned unchanged.
+    pub fn during(mut self, operation: GraphOperation) -> Self {
+        if let GraphError::NodeNotFound { during, .. } | GraphError::EdgeNotFound { during, .. } =
+            &mut self
+        {
+            *during = operation;
+        }
+        self
+    }
+}
+
+/// The operation that failed with a [GraphError], for context in error messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GraphOperation {
+    /// Looking up a node or edge by ID.
+    Lookup,
+    RemoveNode(NodeID),
+    RemoveEdge(EdgeID),
+    AddEdge {
+        from: NodeID,
+        to: NodeID,
+    },
+    RedirectEdge {
+        id: EdgeID,
+        from: NodeID,
+        to: NodeID,
+    },
+}
+
+impl fmt::Display for GraphOperation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphOperation::Lookup => Ok(()),
+            GraphOperation::RemoveNode(id) => write!(f, " while removing node {:?}", id),
+            GraphOperation::RemoveEdge(id) => write!(f, " while removing edge {:?}", id),
+            GraphOperation::AddEdge { from, to } => {
+                write!(f, " while adding an edge from {:?} to {:?}", from, to)
+            }
+            GraphOperation::RedirectEdge { id, from, to } => write!(
+                f,
+                " while redirecting edge {:?} to go from {:?} to {:?}",
+                id, from, to
+            ),
+        }
+    }
 }
@@ -0,0 +1,258 @@
+//! # Summary statistics of a graph.
+//!
+//! [summary] walks the graph once and returns a [GraphSummary] with the node and edge counts, density, degree statistics
+//! and the distribution of (weakly) connected component sizes. The summary is serializable with the `serde` feature, e.g. for dashboards.
+//!
+//! To find the hubs of a graph, [nodes_by_degree], [top_k_by], [max_degree_node] and [min_degree_node] return the nodes with the
+//! highest (or lowest) degree or score without sorting the whole graph up front.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//!
+//! let mut graph: Graph<(), ()> = Graph::new();
+//! let nodes = graph.add_nodes(&[(); 4]);
+//! graph.add_edges(&[(nodes[0], nodes[1]), (nodes[1], nodes[2])]);
+//!
+//! let summary = metrics::summary(&graph);
+//! assert_eq!(summary.edge_count, 2);
+//! assert_eq!(summary.max_degree, 2);
+//! assert_eq!(summary.component_count, 2);
+//! assert_eq!(metrics::max_degree_node(&graph), Some((nodes[1], 2)));
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+
+use crate::algorithms::{VisitMap, Visitable};
+use crate::{outgoing_edges, GraphRef, Node, NodeID};
+
+/// Summary statistics of a graph, see [summary].
+///
+/// The degree of a node is the number of edges connected to it in either direction, a self-loop counts twice.
+/// Components are weakly connected, i.e. edge directions are ignored.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GraphSummary {
+    pub node_count: usize,
+    pub edge_count: usize,
+    /// The number of edges divided by the number of possible directed edges (without self-loops), or 0 with fewer than 2 nodes.
+    pub density: f64,
+    pub min_degree: usize,
+    pub avg_degree: f64,
+    pub max_degree: usize,
+    /// The number of nodes with each degree, indexed by degree.
+    pub degree_histogram: Vec<usize>,
+    pub component_count: usize,
+    /// The number of components of each size, keyed by size.
+    pub component_sizes: BTreeMap<usize, usize>,
+}
+
+/// Computes a [GraphSummary] in a single traversal of the graph.
+pub fn summary<G: GraphRef>(graph: &G) -> GraphSummary {
+    let mut summary = GraphSummary {
+        node_count: graph.node_count(),
+        ..Default::default()
+    };
+    let mut visited: VisitMap = graph.visit_map();
+    let mut stack: Vec<NodeID> = Vec::new();
+    let mut degree_sum = 0;
+    let mut min_degree = usize::MAX;
+
+    for start in graph.nodes() {
+        if !visited.visit(start) {
+            continue;
+        }
+        stack.push(start);
+        let mut size = 0;
+
+        while let Some(id) = stack.pop() {
+            let Ok(node) = graph.node(id) else {
+                continue;
+            };
+            size += 1;
+            summary.edge_count += outgoing_edges(graph, id).count();
+
+            let mut degree = 0;
+            for edge in node
+                .connections
+                .iter()
+                .filter_map(|edge| graph.edge(*edge).ok())
+            {
+                degree += 1;
+                let other = if edge.from == id { edge.to } else { edge.from };
+                if visited.visit(other) {
+                    stack.push(other);
+                }
+            }
+
+            degree_sum += degree;
+            min_degree = min_degree.min(degree);
+            summary.max_degree = summary.max_degree.max(degree);
+            if summary.degree_histogram.len() <= degree {
+                summary.degree_histogram.resize(degree + 1, 0);
+            }
+            summary.degree_histogram[degree] += 1;
+        }
+
+        summary.component_count += 1;
+        *summary.component_sizes.entry(size).or_default() += 1;
+    }
+
+    let nodes = summary.node_count;
+    if nodes > 0 {
+        summary.min_degree = min_degree;
+        summary.avg_degree = degree_sum as f64 / nodes as f64;
+    }
+    if nodes > 1 {
+        summary.density = summary.edge_count as f64 / (nodes * (nodes - 1)) as f64;
+    }
+    summary
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                   Degrees                                  */
+/* -------------------------------------------------------------------------- */
+
+/// Returns the number of edges connected to a node in either direction (a self-loop counts twice), like in [GraphSummary].
+///
+/// Connections to removed edges (see `Graph::vacuum`) aren't counted. A node that doesn't exist has degree 0.
+pub fn degree<G: GraphRef>(graph: &G, id: NodeID) -> usize {
+    graph.node(id).map_or(0, |node| {
+        node.connections
+            .iter()
+            .filter(|edge| graph.edge(**edge).is_ok())
+            .count()
+    })
+}
+
+/// Returns the nodes with their [degree], highest degree first (ties in slot order).
+///
+/// The degrees are computed in one pass and kept in a heap, so taking only the first few nodes doesn't sort the rest.
+pub fn nodes_by_degree<G: GraphRef>(graph: &G) -> impl Iterator<Item = (NodeID, usize)> {
+    let mut heap: BinaryHeap<(usize, Reverse<NodeID>)> = graph
+        .nodes()
+        .map(|id| (degree(graph, id), Reverse(id)))
+        .collect();
+    std::iter::from_fn(move || heap.pop().map(|(degree, Reverse(id))| (id, degree)))
+}
+
+/// Returns the `k` nodes with the highest score, highest first (ties by [NodeID]), e.g. `top_k_by(&graph, |node| node.data.weight, 10)`.
+///
+/// Runs in a single pass, keeping the best `k` nodes so far in a heap.
+pub fn top_k_by<G, S, F>(graph: &G, mut score: F, k: usize) -> Vec<(NodeID, S)>
+where
+    G: GraphRef,
+    S: Ord,
+    F: FnMut(&Node<G::NodeData>) -> S,
+{
+    let k = k.min(graph.node_count());
+    if k == 0 {
+        return Vec::new();
+    }
+    // A min-heap of the best `k`, so the worst of them is on top and can be replaced.
+    let mut heap: BinaryHeap<Reverse<(S, Reverse<NodeID>)>> = BinaryHeap::with_capacity(k + 1);
+    for node in graph.nodes().filter_map(|id| graph.node(id).ok()) {
+        heap.push(Reverse((score(node), Reverse(node.id))));
+        if heap.len() > k {
+            heap.pop();
+        }
+    }
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse((score, Reverse(id)))| (id, score))
+        .collect()
+}
+
+/// Returns a node with the highest [degree] and its degree (the first one in slot order on ties), or `None` if the graph is empty.
+pub fn max_degree_node<G: GraphRef>(graph: &G) -> Option<(NodeID, usize)> {
+    graph
+        .nodes()
+        .map(|id| (id, degree(graph, id)))
+        .reduce(|best, next| if next.1 > best.1 { next } else { best })
+}
+
+/// Returns a node with the lowest [degree] and its degree (the first one in slot order on ties), or `None` if the graph is empty.
+pub fn min_degree_node<G: GraphRef>(graph: &G) -> Option<(NodeID, usize)> {
+    graph
+        .nodes()
+        .map(|id| (id, degree(graph, id)))
+        .reduce(|best, next| if next.1 < best.1 { next } else { best })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Graph, GraphInterface};
+
+    #[test]
+    fn test_summary() {
+        let mut graph: Graph<(), ()> = Graph::new();
+        assert_eq!(summary(&graph), GraphSummary::default());
+
+        let nodes = graph.add_nodes(&[(); 5]);
+        graph.add_edges(&[
+            (nodes[0], nodes[1]),
+            (nodes[1], nodes[2]),
+            (nodes[2], nodes[0]),
+            (nodes[3], nodes[3]),
+        ]);
+
+        let summary = summary(&graph);
+        assert_eq!(summary.node_count, 5);
+        assert_eq!(summary.edge_count, 4);
+        assert_eq!(summary.density, 0.2);
+        assert_eq!(summary.min_degree, 0);
+        assert_eq!(summary.max_degree, 2);
+        assert_eq!(summary.avg_degree, 1.6);
+        assert_eq!(summary.degree_histogram, vec![1, 0, 4]);
+        assert_eq!(summary.component_count, 3);
+        assert_eq!(summary.component_sizes, BTreeMap::from([(1, 2), (3, 1)]));
+    }
+
+    #[test]
+    fn test_summary_counts_dangling_edges_once() {
+        let mut graph: Graph<(), ()> = Graph::new();
+        let nodes = graph.add_nodes(&[(); 3]);
+        graph.add_edge(nodes[0], nodes[1], ());
+        graph.remove_node(nodes[2]).unwrap();
+        // Only listed in the connections of `nodes[0]`.
+        graph.add_edge(nodes[0], nodes[2], ());
+
+        let summary = summary(&graph);
+        assert_eq!(summary.edge_count, 2);
+        assert_eq!(summary.max_degree, 2);
+    }
+
+    #[test]
+    fn test_degree_queries() {
+        let mut graph: Graph<u32, ()> = Graph::new();
+        assert_eq!(max_degree_node(&graph), None);
+        assert!(top_k_by(&graph, |node| node.data, 3).is_empty());
+
+        let nodes = graph.add_nodes(&[7, 3, 9, 1]);
+        graph.add_edges(&[
+            (nodes[0], nodes[1]),
+            (nodes[0], nodes[2]),
+            (nodes[2], nodes[2]),
+        ]);
+        let stale = graph.add_edge(nodes[3], nodes[0], ());
+        graph.remove_edge(stale).unwrap();
+
+        assert_eq!(degree(&graph, nodes[2]), 3);
+        assert_eq!(degree(&graph, nodes[3]), 0);
+        assert_eq!(
+            nodes_by_degree(&graph).collect::<Vec<_>>(),
+            vec![(nodes[2], 3), (nodes[0], 2), (nodes[1], 1), (nodes[3], 0)]
+        );
+        assert_eq!(max_degree_node(&graph), Some((nodes[2], 3)));
+        assert_eq!(min_degree_node(&graph), Some((nodes[3], 0)));
+
+        assert_eq!(
+            top_k_by(&graph, |node| node.data, 2),
+            vec![(nodes[2], 9), (nodes[0], 7)]
+        );
+        assert_eq!(top_k_by(&graph, |node| Reverse(node.data), 10).len(), 4);
+        assert_eq!(top_k_by(&graph, |node| node.data, usize::MAX).len(), 4);
+    }
+}
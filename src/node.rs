@@ -20,11 +20,48 @@ impl NodeID {
     pub fn to_u64(&self) -> u64 {
         self.0.as_ffi()
     }
+
+    /// Converts an integer from [NodeID::to_u64] back to an ID without checking it, so the ID may not resolve in any graph.
+    /// Hidden with the `strict_ids` feature, use [NodeID::try_from_u64] instead.
+    #[cfg(not(feature = "strict_ids"))]
     pub fn from_u64(id: u64) -> Self {
         NodeID::from(KeyData::from_ffi(id))
     }
+
+    /// Converts an integer from [NodeID::to_u64] back to an ID, checking that it round-trips and that the node exists in `graph`.
+    ///
+    /// Returns [GraphError::InvalidId] if the integer can't be an ID, and [GraphError::NodeNotFound] if the node doesn't exist
+    /// (see [Graph::diagnose_node] for why).
+    pub fn try_from_u64<G: GraphRef + ?Sized>(graph: &G, id: u64) -> Result<Self, GraphError> {
+        let key = NodeID::from(KeyData::from_ffi(id));
+        if key.to_u64() != id {
+            return Err(GraphError::InvalidId(id));
+        }
+        graph.node(key)?;
+        Ok(key)
+    }
 }
 
+/* -------------------------------------------------------------------------- */
+/*                                 Connections                                */
+/* -------------------------------------------------------------------------- */
+
+/// The number of [EdgeID]s a node stores inline (without a heap allocation) when the `smallvec` feature is enabled.
+#[cfg(feature = "smallvec")]
+pub const INLINE_CONNECTIONS: usize = 4;
+
+/// The list of [EdgeID]s stored in every [Node].
+///
+/// With the `smallvec` feature this is a [SmallVec](smallvec::SmallVec) that keeps up to [INLINE_CONNECTIONS] edges inline, so low-degree nodes don't need a heap allocation.
+#[cfg(feature = "smallvec")]
+pub type Connections = smallvec::SmallVec<[EdgeID; INLINE_CONNECTIONS]>;
+
+/// The list of [EdgeID]s stored in every [Node].
+///
+/// With the `smallvec` feature this is a `SmallVec` that keeps the first few edges inline, so low-degree nodes don't need a heap allocation.
+#[cfg(not(feature = "smallvec"))]
+pub type Connections = Vec<EdgeID>;
+
 /* -------------------------------------------------------------------------- */
 /*                                    Node                                    */
 /* -------------------------------------------------------------------------- */
@@ -38,12 +75,14 @@ impl NodeID {
 ///
 /// The [Node] struct is very simple and doesn't need a trait.
 /// If you want to add more functionality or data to the Node you can probably just add it to the data field, or add a node as a field to your custom type.
+#[derive(Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct Node<T> {
     pub id: NodeID,
     pub data: T,
-    pub connections: Vec<EdgeID>,
+    #[cfg_attr(feature = "specta", specta(type = Vec<EdgeID>))]
+    pub connections: Connections,
 }
 
 /// Implements PartialEQ for Node<T> so only the ID is used for comparison.
@@ -77,7 +116,7 @@ impl<T> Node<T> {
         Node {
             id,
             data,
-            connections: Vec::new(),
+            connections: Connections::new(),
         }
     }
 
@@ -0,0 +1,87 @@
+//! Insertion order bookkeeping for the `ordered` feature.
+//!
+//! Slotmaps iterate in slot order, which depends on the history of insertions and removals (freed slots are reused).
+//! With the `ordered` feature [Graph](crate::Graph) and [CategorizedGraph](crate::CategorizedGraph) record a sequence number for every
+//! node and edge they add, so `nodes_ordered()`/`edges_ordered()` can return them in insertion order.
+
+use slotmap::{Key, SecondaryMap};
+
+use crate::{EdgeID, NodeID};
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct InsertionOrder {
+    next: u64,
+    nodes: SecondaryMap<NodeID, u64>,
+    edges: SecondaryMap<EdgeID, u64>,
+}
+
+impl InsertionOrder {
+    /// Numbers the nodes and edges in the order given, e.g. the slot order of a deserialized graph.
+    #[cfg(feature = "serde")]
+    pub(crate) fn from_ids(
+        nodes: impl Iterator<Item = NodeID>,
+        edges: impl Iterator<Item = EdgeID>,
+    ) -> Self {
+        let mut order = InsertionOrder::default();
+        nodes.for_each(|id| order.add_node(id));
+        edges.for_each(|id| order.add_edge(id));
+        order
+    }
+
+    pub(crate) fn add_node(&mut self, id: NodeID) {
+        self.nodes.insert(id, self.next);
+        self.next += 1;
+    }
+
+    pub(crate) fn add_edge(&mut self, id: EdgeID) {
+        self.edges.insert(id, self.next);
+        self.next += 1;
+    }
+
+    pub(crate) fn sort_nodes(&self, ids: impl Iterator<Item = NodeID>) -> Vec<NodeID> {
+        sort(&self.nodes, ids)
+    }
+
+    pub(crate) fn sort_edges(&self, ids: impl Iterator<Item = EdgeID>) -> Vec<EdgeID> {
+        sort(&self.edges, ids)
+    }
+}
+
+/// Sorts by sequence number. IDs without one (e.g. inserted into the slotmaps directly) go last, in slot order.
+fn sort<K: Key + Ord>(order: &SecondaryMap<K, u64>, ids: impl Iterator<Item = K>) -> Vec<K> {
+    let mut ids: Vec<K> = ids.collect();
+    ids.sort_by_key(|id| (order.get(*id).copied().unwrap_or(u64::MAX), *id));
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_order_survives_slot_reuse() {
+        let mut graph: Graph<&str, ()> = Graph::new();
+        let a = graph.add_node("a");
+        let b = graph.add_node("b");
+        let c = graph.add_node("c");
+        let ab = graph.add_edge(a, b, ());
+        graph.remove_node(a).unwrap();
+        // Reuses the slot of `a`, so slot order would put it first.
+        let d = graph.add_node("d");
+        let cd = graph.add_edge(c, d, ());
+        let bc = graph.add_edge(b, c, ());
+
+        assert_eq!(graph.nodes().next(), Some(d));
+        assert_eq!(graph.nodes_ordered(), vec![b, c, d]);
+        assert!(!graph.edges_ordered().contains(&ab));
+        assert_eq!(graph.edges_ordered(), vec![cd, bc]);
+
+        graph.compact();
+        let data: Vec<&str> = graph
+            .nodes_ordered()
+            .into_iter()
+            .map(|id| graph.node(id).unwrap().data)
+            .collect();
+        assert_eq!(data, vec!["b", "c", "d"]);
+    }
+}
@@ -0,0 +1,293 @@
+//! # Memoized shortest paths.
+//!
+//! [ShortestPathCache] owns a [Graph] and answers shortest path queries with Dijkstra's algorithm (using a [NodePriorityQueue]),
+//! caching every result by `(from, to)`, so repeated routing queries between the same nodes are a hash lookup.
+//!
+//! The cache implements [GraphInterface], and mutations through it invalidate the cached results they can affect:
+//!
+//! - Removing an edge or a node only makes paths longer, so only the cached paths that use the edge or node are dropped.
+//!   Cached "no path" results stay valid.
+//! - Adding an edge can make any path shorter (or create one), so it clears the cache.
+//! - [GraphInterface::node_mut] and [GraphInterface::edge_mut] clear the cache, since the caller can change anything through them.
+//!
+//! Edges are followed from `edge.from` to `edge.to` and weights must not be negative.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::path_cache::{CacheStats, ShortestPathCache};
+//!
+//! let mut graph: Graph<(), u32> = Graph::new();
+//! let nodes = graph.add_nodes(&[(); 3]);
+//! let direct = graph.add_edge(nodes[0], nodes[2], 10);
+//! graph.add_edge(nodes[0], nodes[1], 1);
+//! let detour = graph.add_edge(nodes[1], nodes[2], 1);
+//!
+//! let mut cache = ShortestPathCache::new(graph, |edge: &Edge<u32>| edge.data);
+//! assert_eq!(cache.shortest_path(nodes[0], nodes[2]).unwrap().cost, 2);
+//! assert_eq!(cache.shortest_path(nodes[0], nodes[2]).unwrap().edges.len(), 2);
+//! assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 1 });
+//!
+//! // Removing an edge of the cached path invalidates it.
+//! cache.remove_edge(detour).unwrap();
+//! assert_eq!(cache.shortest_path(nodes[0], nodes[2]).unwrap().edges, vec![direct]);
+//! ```
+
+use std::ops::Add;
+
+use crate::algorithms::{NodePriorityQueue, NodeSet};
+use crate::hash::HashMap;
+use crate::*;
+
+/// A shortest path found by [ShortestPathCache::shortest_path].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CachedPath<W> {
+    /// The nodes of the path, from the start to the end (both included).
+    pub nodes: Vec<NodeID>,
+    /// The edges of the path, one less than the nodes.
+    pub edges: Vec<EdgeID>,
+    /// The sum of the weights of the edges.
+    pub cost: W,
+}
+
+/// How many queries of a [ShortestPathCache] were answered from the cache.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+/// A [Graph] that caches shortest paths between pairs of nodes, see the [module documentation](self).
+///
+/// `weight` returns the weight of an edge; `W::default()` is used as the cost of the empty path.
+pub struct ShortestPathCache<N, E, W, F> {
+    graph: Graph<N, E>,
+    weight: F,
+    paths: HashMap<(NodeID, NodeID), Option<CachedPath<W>>>,
+    stats: CacheStats,
+}
+
+impl<N, E, W, F> ShortestPathCache<N, E, W, F>
+where
+    W: Ord + Copy + Default + Add<Output = W>,
+    F: Fn(&Edge<E>) -> W,
+{
+    pub fn new(graph: Graph<N, E>, weight: F) -> Self {
+        ShortestPathCache {
+            graph,
+            weight,
+            paths: HashMap::default(),
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// Returns the underlying graph. Mutate it through the cache, so cached paths are invalidated.
+    pub fn graph(&self) -> &Graph<N, E> {
+        &self.graph
+    }
+
+    pub fn into_graph(self) -> Graph<N, E> {
+        self.graph
+    }
+
+    /// Returns a shortest path from `from` to `to`, or `None` if there is none (or either node doesn't exist).
+    ///
+    /// The result is computed with Dijkstra's algorithm, which stops as soon as `to` is reached, and cached until a mutation invalidates it.
+    pub fn shortest_path(&mut self, from: NodeID, to: NodeID) -> Option<&CachedPath<W>> {
+        if self.paths.contains_key(&(from, to)) {
+            self.stats.hits += 1;
+        } else {
+            self.stats.misses += 1;
+            let path = self.dijkstra(from, to);
+            self.paths.insert((from, to), path);
+        }
+        self.paths[&(from, to)].as_ref()
+    }
+
+    /// Returns how many queries were answered from the cache and how many had to be computed.
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    /// The number of cached results (including cached "no path" results).
+    pub fn cached_len(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// Drops all cached results.
+    pub fn clear(&mut self) {
+        self.paths.clear();
+    }
+
+    fn dijkstra(&self, from: NodeID, to: NodeID) -> Option<CachedPath<W>> {
+        if self.graph.node(from).is_err() || self.graph.node(to).is_err() {
+            return None;
+        }
+        let mut queue = NodePriorityQueue::new();
+        let mut settled = NodeSet::with_capacity(self.graph.node_count());
+        // The edge every reached node was reached over.
+        let mut previous: HashMap<NodeID, EdgeID> = HashMap::default();
+        queue.push(from, W::default());
+
+        while let Some((id, cost)) = queue.pop() {
+            settled.insert(id);
+            if id == to {
+                return Some(self.trace(from, to, cost, &previous));
+            }
+            for edge in outgoing_edges(&self.graph, id) {
+                if settled.contains(edge.to) || self.graph.node(edge.to).is_err() {
+                    continue;
+                }
+                if queue.push_decrease(edge.to, cost + (self.weight)(edge)) {
+                    previous.insert(edge.to, edge.id);
+                }
+            }
+        }
+        None
+    }
+
+    fn trace(
+        &self,
+        from: NodeID,
+        to: NodeID,
+        cost: W,
+        previous: &HashMap<NodeID, EdgeID>,
+    ) -> CachedPath<W> {
+        let (mut nodes, mut edges) = (vec![to], Vec::new());
+        let mut current = to;
+        while current != from {
+            let edge = previous[&current];
+            edges.push(edge);
+            current = self.graph.edges[edge].from;
+            nodes.push(current);
+        }
+        nodes.reverse();
+        edges.reverse();
+        CachedPath { nodes, edges, cost }
+    }
+
+    /// Drops the cached paths that pass through the node.
+    fn invalidate_node(&mut self, id: NodeID) {
+        self.paths
+            .retain(|_, path| !matches!(path, Some(path) if path.nodes.contains(&id)));
+    }
+}
+
+impl<N, E, W, F> GraphRef for ShortestPathCache<N, E, W, F> {
+    type NodeData = N;
+    type EdgeData = E;
+
+    fn nodes(&self) -> impl Iterator<Item = NodeID> {
+        self.graph.nodes()
+    }
+
+    fn node_count(&self) -> usize {
+        self.graph.node_count()
+    }
+
+    fn node(&self, id: NodeID) -> Result<&Node<N>, GraphError> {
+        self.graph.node(id)
+    }
+
+    fn edge(&self, id: EdgeID) -> Result<&Edge<E>, GraphError> {
+        self.graph.edge(id)
+    }
+}
+
+impl<N, E, W, F> GraphInterface for ShortestPathCache<N, E, W, F>
+where
+    W: Ord + Copy + Default + Add<Output = W>,
+    F: Fn(&Edge<E>) -> W,
+{
+    fn node_mut(&mut self, id: NodeID) -> Result<&mut Node<N>, GraphError> {
+        self.paths.clear();
+        self.graph.node_mut(id)
+    }
+
+    fn edge_mut(&mut self, id: EdgeID) -> Result<&mut Edge<E>, GraphError> {
+        self.paths.clear();
+        self.graph.edge_mut(id)
+    }
+
+    fn remove_node(&mut self, id: NodeID) -> Result<(), GraphError> {
+        self.graph.remove_node(id)?;
+        self.invalidate_node(id);
+        Ok(())
+    }
+
+    fn remove_edge(&mut self, id: EdgeID) -> Result<(), GraphError> {
+        self.graph.remove_edge(id)?;
+        self.paths
+            .retain(|_, path| !matches!(path, Some(path) if path.edges.contains(&id)));
+        Ok(())
+    }
+
+    fn add_node(&mut self, data: N) -> NodeID {
+        // A new node has no edges, so no path changes.
+        self.graph.add_node(data)
+    }
+
+    fn add_nodes(&mut self, data: &[N]) -> Vec<NodeID>
+    where
+        N: Clone,
+    {
+        self.graph.add_nodes(data)
+    }
+
+    fn add_edges(&mut self, data: &[(NodeID, NodeID)]) -> Vec<EdgeID>
+    where
+        E: Default + Clone,
+        N: Clone,
+    {
+        self.paths.clear();
+        self.graph.add_edges(data)
+    }
+
+    fn add_edge(&mut self, from: NodeID, to: NodeID, data: E) -> EdgeID {
+        self.paths.clear();
+        self.graph.add_edge(from, to, data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortest_path_cache_invalidation() {
+        let mut graph: Graph<(), u32> = Graph::new();
+        let nodes = graph.add_nodes(&[(); 5]);
+        graph.add_edges_with_data(&[
+            (nodes[0], nodes[1], 1),
+            (nodes[1], nodes[2], 1),
+            (nodes[0], nodes[2], 5),
+            (nodes[2], nodes[3], 1),
+        ]);
+        let mut cache = ShortestPathCache::new(graph, |edge: &Edge<u32>| edge.data);
+
+        let path = cache.shortest_path(nodes[0], nodes[3]).unwrap();
+        assert_eq!(path.nodes, vec![nodes[0], nodes[1], nodes[2], nodes[3]]);
+        assert_eq!(path.cost, 3);
+        assert_eq!(cache.shortest_path(nodes[0], nodes[0]).unwrap().cost, 0);
+        assert!(cache.shortest_path(nodes[3], nodes[0]).is_none());
+        assert!(cache.shortest_path(nodes[2], nodes[4]).is_none());
+        assert!(cache.shortest_path(nodes[0], nodes[2]).is_some());
+        assert_eq!(cache.cached_len(), 5);
+
+        // Only the paths through the removed node are dropped.
+        cache.remove_node(nodes[1]).unwrap();
+        assert_eq!(cache.cached_len(), 3);
+        assert_eq!(cache.shortest_path(nodes[0], nodes[3]).unwrap().cost, 6);
+        assert!(cache.shortest_path(nodes[3], nodes[0]).is_none());
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 6 });
+
+        // Adding an edge can create paths.
+        cache.add_edge(nodes[3], nodes[0], 1);
+        assert_eq!(cache.cached_len(), 0);
+        assert_eq!(cache.shortest_path(nodes[3], nodes[0]).unwrap().cost, 1);
+
+        let edge = cache.graph().node(nodes[3]).unwrap().connections[1];
+        cache.edge_mut(edge).unwrap().data = 7;
+        assert_eq!(cache.shortest_path(nodes[3], nodes[0]).unwrap().cost, 7);
+    }
+}
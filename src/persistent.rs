@@ -0,0 +1,392 @@
+//! # A graph that can take cheap point-in-time snapshots.
+//!
+//! [PersistentGraph] wraps a [Graph] behind an [Arc] and uses copy-on-write: taking a [GraphSnapshot] is O(1) (it only bumps a reference count),
+//! and the first mutation after a snapshot copies the underlying slotmaps once so the snapshot stays untouched.
+//!
+//! The copy is of the whole graph, there is no structural sharing between the copies: the first mutation after a snapshot costs
+//! O(nodes + edges) time, and every snapshot that outlives later mutations keeps a full copy of the graph in memory. Snapshots are
+//! cheap to take, not to keep; taking several without mutating in between shares a single copy.
+//!
+//! Because the slotmaps are copied as a whole, every [NodeID] and [EdgeID] stays valid across snapshots, restores and comparisons.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//!
+//! let mut graph: PersistentGraph<&str, ()> = PersistentGraph::new();
+//! let a = graph.add_node("A");
+//! let b = graph.add_node("B");
+//!
+//! let before = graph.snapshot();
+//!
+//! let c = graph.add_node("C");
+//! graph.add_edge(a, c, ());
+//! graph.remove_node(b).unwrap();
+//!
+//! // The snapshot still sees the old graph.
+//! assert!(before.node(b).is_ok());
+//! assert!(before.node(c).is_err());
+//!
+//! let diff = graph.diff(&before);
+//! assert_eq!(diff.added_nodes, vec![c]);
+//! assert_eq!(diff.removed_nodes, vec![b]);
+//! assert!(diff.changed_nodes.is_empty());
+//!
+//! graph.restore(&before);
+//! assert!(graph.node(b).is_ok());
+//! assert!(graph.node(c).is_err());
+//! ```
+
+use std::ops::Deref;
+use std::sync::Arc;
+
+use crate::*;
+
+/* -------------------------------------------------------------------------- */
+/*                               PersistentGraph                              */
+/* -------------------------------------------------------------------------- */
+
+/// A [Graph] with copy-on-write storage that supports O(1) snapshots and restores.
+///
+/// The first mutation after a snapshot copies the whole graph, see the [module documentation](self).
+/// Mutating methods require `N: Clone` and `E: Clone`, since a shared graph has to be copied before it can be changed.
+pub struct PersistentGraph<N, E> {
+    graph: Arc<Graph<N, E>>,
+}
+
+impl<N, E> PersistentGraph<N, E> {
+    pub fn new() -> Self {
+        PersistentGraph {
+            graph: Arc::new(Graph::new()),
+        }
+    }
+
+    /// Returns a read-only reference to the current state of the graph.
+    pub fn graph(&self) -> &Graph<N, E> {
+        &self.graph
+    }
+
+    /// Takes a point-in-time snapshot of the graph. This is O(1).
+    pub fn snapshot(&self) -> GraphSnapshot<N, E> {
+        GraphSnapshot {
+            graph: Arc::clone(&self.graph),
+        }
+    }
+
+    /// Resets the graph to the state captured by `snapshot`. This is O(1).
+    pub fn restore(&mut self, snapshot: &GraphSnapshot<N, E>) {
+        self.graph = Arc::clone(&snapshot.graph);
+    }
+
+    /// Returns the nodes and edges that were added, removed or changed since `snapshot` was taken, see [GraphDiff::between].
+    pub fn diff(&self, snapshot: &GraphSnapshot<N, E>) -> GraphDiff
+    where
+        N: PartialEq,
+        E: PartialEq,
+    {
+        GraphDiff::between(&snapshot.graph, &self.graph)
+    }
+
+    /// Returns true if no mutation has happened since `snapshot` was taken (or since it was restored).
+    pub fn is_unchanged_since(&self, snapshot: &GraphSnapshot<N, E>) -> bool {
+        Arc::ptr_eq(&self.graph, &snapshot.graph)
+    }
+}
+
+impl<N: Clone, E: Clone> PersistentGraph<N, E> {
+    /// Returns a mutable reference to the current graph, copying it first if a snapshot still shares it.
+    pub fn graph_mut(&mut self) -> &mut Graph<N, E> {
+        Arc::make_mut(&mut self.graph)
+    }
+
+    /// Unwraps the current graph, copying it only if a snapshot still shares it.
+    pub fn into_graph(self) -> Graph<N, E> {
+        Arc::try_unwrap(self.graph).unwrap_or_else(|shared| (*shared).clone())
+    }
+}
+
+impl<N, E> Default for PersistentGraph<N, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N, E> From<Graph<N, E>> for PersistentGraph<N, E> {
+    fn from(graph: Graph<N, E>) -> Self {
+        PersistentGraph {
+            graph: Arc::new(graph),
+        }
+    }
+}
+
+impl<N: fmt::Debug + Clone, E: fmt::Debug + Clone> fmt::Debug for PersistentGraph<N, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PersistentGraph {{ graph: {:#?} }}", self.graph)
+    }
+}
+
+impl<N, E> GraphRef for PersistentGraph<N, E> {
+    type NodeData = N;
+    type EdgeData = E;
+
+    fn nodes(&self) -> impl Iterator<Item = NodeID> {
+        self.graph.nodes()
+    }
+
+    fn node_count(&self) -> usize {
+        self.graph.node_count()
+    }
+
+    fn node(&self, id: NodeID) -> Result<&Node<N>, GraphError> {
+        self.graph.node(id)
+    }
+
+    fn edge(&self, id: EdgeID) -> Result<&Edge<E>, GraphError> {
+        self.graph.edge(id)
+    }
+}
+
+impl<N: Clone, E: Clone> GraphInterface for PersistentGraph<N, E> {
+    fn node_mut(&mut self, id: NodeID) -> Result<&mut Node<N>, GraphError> {
+        self.graph_mut().node_mut(id)
+    }
+
+    fn edge_mut(&mut self, id: EdgeID) -> Result<&mut Edge<E>, GraphError> {
+        self.graph_mut().edge_mut(id)
+    }
+
+    fn remove_node(&mut self, id: NodeID) -> Result<(), GraphError> {
+        self.graph_mut().remove_node(id)
+    }
+
+    fn remove_edge(&mut self, id: EdgeID) -> Result<(), GraphError> {
+        self.graph_mut().remove_edge(id)
+    }
+
+    fn add_node(&mut self, data: N) -> NodeID {
+        self.graph_mut().add_node(data)
+    }
+
+    fn add_nodes(&mut self, data: &[N]) -> Vec<NodeID> {
+        self.graph_mut().add_nodes(data)
+    }
+
+    fn add_edges(&mut self, data: &[(NodeID, NodeID)]) -> Vec<EdgeID>
+    where
+        E: Default,
+    {
+        self.graph_mut().add_edges(data)
+    }
+
+    fn add_edge(&mut self, from: NodeID, to: NodeID, data: E) -> EdgeID {
+        self.graph_mut().add_edge(from, to, data)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                GraphSnapshot                               */
+/* -------------------------------------------------------------------------- */
+
+/// An immutable point-in-time view of a [PersistentGraph], created with [PersistentGraph::snapshot].
+///
+/// Dereferences to [Graph], so all read-only methods (e.g. [GraphInterface::node]) can be used directly.
+pub struct GraphSnapshot<N, E> {
+    graph: Arc<Graph<N, E>>,
+}
+
+impl<N: Clone, E: Clone> GraphSnapshot<N, E> {
+    /// Copies the snapshot into a standalone, mutable [Graph].
+    pub fn to_graph(&self) -> Graph<N, E> {
+        (*self.graph).clone()
+    }
+}
+
+impl<N, E> Clone for GraphSnapshot<N, E> {
+    fn clone(&self) -> Self {
+        GraphSnapshot {
+            graph: Arc::clone(&self.graph),
+        }
+    }
+}
+
+impl<N, E> Deref for GraphSnapshot<N, E> {
+    type Target = Graph<N, E>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.graph
+    }
+}
+
+/// Snapshots are read-only, so they only implement [GraphRef] (which is all algorithms need).
+impl<N, E> GraphRef for GraphSnapshot<N, E> {
+    type NodeData = N;
+    type EdgeData = E;
+
+    fn node_count(&self) -> usize {
+        self.graph.node_count()
+    }
+
+    fn nodes(&self) -> impl Iterator<Item = NodeID> {
+        self.graph.nodes()
+    }
+
+    fn node(&self, id: NodeID) -> Result<&Node<N>, GraphError> {
+        self.graph.node(id)
+    }
+
+    fn edge(&self, id: EdgeID) -> Result<&Edge<E>, GraphError> {
+        self.graph.edge(id)
+    }
+}
+
+impl<N: fmt::Debug + Clone, E: fmt::Debug + Clone> fmt::Debug for GraphSnapshot<N, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GraphSnapshot {{ graph: {:#?} }}", self.graph)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                  GraphDiff                                 */
+/* -------------------------------------------------------------------------- */
+
+/// The difference between two states of a graph, see [PersistentGraph::diff].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GraphDiff {
+    pub added_nodes: Vec<NodeID>,
+    pub removed_nodes: Vec<NodeID>,
+    /// Nodes in both states whose data differs.
+    pub changed_nodes: Vec<NodeID>,
+    pub added_edges: Vec<EdgeID>,
+    pub removed_edges: Vec<EdgeID>,
+    /// Edges in both states whose data or endpoints differ.
+    pub changed_edges: Vec<EdgeID>,
+}
+
+impl GraphDiff {
+    /// Compares two graphs that share ID space (e.g. a graph and one of its snapshots): nodes and edges are matched by ID,
+    /// and the ones present in both are compared by data (and edges also by endpoints).
+    pub fn between<N: PartialEq, E: PartialEq>(old: &Graph<N, E>, new: &Graph<N, E>) -> GraphDiff {
+        GraphDiff {
+            changed_nodes: new
+                .nodes
+                .iter()
+                .filter(|(id, node)| old.nodes.get(*id).is_some_and(|old| old.data != node.data))
+                .map(|(id, _)| id)
+                .collect(),
+            changed_edges: new
+                .edges
+                .iter()
+                .filter(|(id, edge)| {
+                    old.edges.get(*id).is_some_and(|old| {
+                        old.data != edge.data || (old.from, old.to) != (edge.from, edge.to)
+                    })
+                })
+                .map(|(id, _)| id)
+                .collect(),
+            added_nodes: new
+                .nodes
+                .keys()
+                .filter(|id| !old.nodes.contains_key(*id))
+                .collect(),
+            removed_nodes: old
+                .nodes
+                .keys()
+                .filter(|id| !new.nodes.contains_key(*id))
+                .collect(),
+            added_edges: new
+                .edges
+                .keys()
+                .filter(|id| !old.edges.contains_key(*id))
+                .collect(),
+            removed_edges: old
+                .edges
+                .keys()
+                .filter(|id| !new.edges.contains_key(*id))
+                .collect(),
+        }
+    }
+
+    /// Returns true if no nodes or edges were added, removed or changed.
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.changed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+            && self.changed_edges.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_is_isolated_from_mutations() {
+        let mut graph: PersistentGraph<u32, u8> = PersistentGraph::new();
+        let a = graph.add_node(1);
+        let c = graph.add_node(4);
+        let old_edge = graph.add_edge(a, c, 1);
+        let snapshot = graph.snapshot();
+        assert!(graph.is_unchanged_since(&snapshot));
+
+        graph.node_mut(a).unwrap().data = 2;
+        graph.edge_mut(old_edge).unwrap().data = 2;
+        let b = graph.add_node(3);
+        let edge = graph.add_edge(a, b, 0);
+
+        assert!(!graph.is_unchanged_since(&snapshot));
+        assert_eq!(snapshot.node(a).unwrap().data, 1);
+        assert_eq!(graph.node(a).unwrap().data, 2);
+        assert!(snapshot.edge(edge).is_err());
+
+        let diff = graph.diff(&snapshot);
+        assert_eq!(diff.added_nodes, vec![b]);
+        assert_eq!(diff.added_edges, vec![edge]);
+        assert!(diff.removed_nodes.is_empty());
+        assert_eq!(diff.changed_nodes, vec![a]);
+        assert_eq!(diff.changed_edges, vec![old_edge]);
+
+        // Writing the same data back is no change.
+        graph.node_mut(a).unwrap().data = 1;
+        assert!(graph.diff(&snapshot).changed_nodes.is_empty());
+    }
+
+    #[test]
+    fn test_algorithms_on_read_only_graphs() {
+        use crate::algorithms::IterDepthFirst;
+
+        let mut graph: PersistentGraph<u32, ()> = PersistentGraph::new();
+        let a = graph.add_node(1);
+        let b = graph.add_node(2);
+        graph.add_edge(a, b, ());
+        let snapshot = graph.snapshot();
+        let c = graph.add_node(3);
+        graph.add_edge(b, c, ());
+
+        // Snapshots and shared references only implement the read side.
+        assert_eq!(snapshot.iter_depth_first(a).count(), 2);
+        let shared: &PersistentGraph<u32, ()> = &graph;
+        assert_eq!(crate::algorithms::bfs_layers(&shared, a).len(), 3);
+        assert_eq!(crate::metrics::summary(&snapshot).edge_count, 1);
+    }
+
+    #[test]
+    fn test_restore_keeps_ids_valid() {
+        let mut graph: PersistentGraph<u32, ()> = PersistentGraph::new();
+        let a = graph.add_node(1);
+        let b = graph.add_node(2);
+        let edge = graph.add_edge(a, b, ());
+        let snapshot = graph.snapshot();
+
+        graph.remove_node(a).unwrap();
+        assert!(graph.edge(edge).is_err());
+
+        graph.restore(&snapshot);
+        assert!(graph.diff(&snapshot).is_empty());
+        assert_eq!(graph.edge(edge).unwrap().from, a);
+
+        let restored = graph.into_graph();
+        assert_eq!(restored.node(b).unwrap().data, 2);
+    }
+}
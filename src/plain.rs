@@ -0,0 +1,153 @@
+//! # A plain representation with dense integer IDs.
+//!
+//! The serde and specta representations of [Graph] expose the slotmaps, so every [NodeID] and [EdgeID] shows up as an
+//! `{ idx, version }` pair. That's needed to restore a graph exactly, but it leaks an implementation detail into e.g. a JSON API.
+//! [PlainGraph] is the alternative for such boundaries: a list of node data and a list of `(from, to, data)` edges, where
+//! `from` and `to` are indices into the node list. With the `serde` feature it serializes as
+//!
+//! ```json
+//! { "nodes": ["a", "b"], "edges": [[0, 1, 5]] }
+//! ```
+//!
+//! [Graph::to_plain] lists the nodes and edges in slot order (in insertion order with the `ordered` feature) and drops edges
+//! whose endpoints no longer exist. [Graph::from_plain] adds them to a new graph in list order, so the `i`th node of the new graph's
+//! [nodes](GraphRef::nodes) is `nodes[i]`.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::plain::PlainGraph;
+//!
+//! let mut graph: Graph<&str, u32> = Graph::new();
+//! let temp = graph.add_node("temp");
+//! let [a, b] = graph.add_nodes(&["a", "b"])[..] else { unreachable!() };
+//! graph.add_edge(a, b, 5);
+//! graph.remove_node(temp).unwrap();
+//!
+//! let plain = graph.to_plain();
+//! assert_eq!(plain, PlainGraph { nodes: vec!["a", "b"], edges: vec![(0, 1, 5)] });
+//!
+//! let restored = Graph::from_plain(plain).unwrap();
+//! assert_graph_eq!(graph, restored);
+//! ```
+
+use crate::hash::HashMap;
+use crate::*;
+
+/// A graph with its nodes and edges in lists, referring to nodes by index, see the [module documentation](self).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct PlainGraph<N, E> {
+    pub nodes: Vec<N>,
+    /// `(from, to, data)`, where `from` and `to` are indices into `nodes`.
+    pub edges: Vec<(usize, usize, E)>,
+}
+
+impl<N: Clone, E: Clone> Graph<N, E> {
+    /// Returns the [PlainGraph] of this graph, see the [module documentation](self).
+    pub fn to_plain(&self) -> PlainGraph<N, E> {
+        #[cfg(feature = "ordered")]
+        let (node_ids, edge_ids) = (self.nodes_ordered(), self.edges_ordered());
+        #[cfg(not(feature = "ordered"))]
+        let (node_ids, edge_ids): (Vec<NodeID>, Vec<EdgeID>) =
+            (self.nodes.keys().collect(), self.edges.keys().collect());
+
+        let index: HashMap<NodeID, usize> = node_ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i))
+            .collect();
+        PlainGraph {
+            nodes: node_ids
+                .iter()
+                .map(|id| self.nodes[*id].data.clone())
+                .collect(),
+            edges: edge_ids
+                .iter()
+                .map(|id| &self.edges[*id])
+                .filter_map(|edge| {
+                    Some((
+                        *index.get(&edge.from)?,
+                        *index.get(&edge.to)?,
+                        edge.data.clone(),
+                    ))
+                })
+                .collect(),
+        }
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    /// Builds a graph from a [PlainGraph], see the [module documentation](self).
+    ///
+    /// Returns [GraphError::InvalidGraph] if an edge refers to a node index that is out of range.
+    pub fn from_plain(plain: PlainGraph<N, E>) -> Result<Self, GraphError> {
+        let node_count = plain.nodes.len();
+        if let Some((from, to, _)) = plain
+            .edges
+            .iter()
+            .find(|(from, to, _)| *from >= node_count || *to >= node_count)
+        {
+            return Err(GraphError::InvalidGraph(format!(
+                "edge ({}, {}) is out of range for {} nodes",
+                from, to, node_count
+            )));
+        }
+
+        let mut graph = Graph::with_capacity(node_count, plain.edges.len());
+        let ids: Vec<NodeID> = plain
+            .nodes
+            .into_iter()
+            .map(|data| graph.add_node(data))
+            .collect();
+        for (from, to, data) in plain.edges {
+            graph.add_edge(ids[from], ids[to], data);
+        }
+        Ok(graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_round_trip() {
+        let mut graph: Graph<String, u32> = Graph::new();
+        let nodes = graph.add_nodes(&["a".to_string(), "b".to_string(), "c".to_string()]);
+        graph.add_edges_with_data(&[
+            (nodes[0], nodes[2], 1),
+            (nodes[2], nodes[2], 2),
+            (nodes[1], nodes[0], 3),
+        ]);
+        graph.remove_node(nodes[1]).unwrap();
+        graph.add_edge(nodes[0], nodes[1], 4);
+
+        let plain = graph.to_plain();
+        assert_eq!(plain.nodes, vec!["a", "c"]);
+        assert_eq!(plain.edges, vec![(0, 1, 1), (1, 1, 2)]);
+        let restored = Graph::from_plain(plain.clone()).unwrap();
+        restored.validate().unwrap();
+        assert_eq!(restored.to_plain(), plain);
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_string(&plain).unwrap();
+            assert_eq!(json, r#"{"nodes":["a","c"],"edges":[[0,1,1],[1,1,2]]}"#);
+            assert_eq!(
+                serde_json::from_str::<PlainGraph<String, u32>>(&json).unwrap(),
+                plain
+            );
+        }
+
+        let invalid = PlainGraph {
+            nodes: vec![()],
+            edges: vec![(0, 1, ())],
+        };
+        assert!(matches!(
+            Graph::from_plain(invalid),
+            Err(GraphError::InvalidGraph(_))
+        ));
+    }
+}
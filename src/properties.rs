@@ -0,0 +1,271 @@
+//! # Schemaless properties on nodes and edges (requires the `properties` feature).
+//!
+//! [Properties] attaches key → value attributes ([PropertyValue]: string, number or bool) to nodes and edges, next to their typed data.
+//! This is handy for mirroring property-graph databases (Neo4j style) without adding every attribute to `N` and `E`.
+//!
+//! The properties are stored in [SecondaryMap]s keyed by [NodeID] and [EdgeID], so they work with any graph and never match a reused slot.
+//! The properties of removed nodes and edges stay around until [Properties::retain_existing] is called, and are returned by the queries until then.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::properties::{Properties, PropertyValue};
+//!
+//! let mut graph: Graph<(), ()> = Graph::new();
+//! let [alice, bob] = graph.add_nodes(&[(), ()])[..] else { unreachable!() };
+//! let knows = graph.add_edge(alice, bob, ());
+//!
+//! let mut properties = Properties::new();
+//! properties.set_node(alice, "name", "Alice");
+//! properties.set_node(alice, "age", 31);
+//! properties.set_node(bob, "age", 25);
+//! properties.set_edge(knows, "since", 2015);
+//!
+//! assert_eq!(properties.node(alice, "name").and_then(PropertyValue::as_str), Some("Alice"));
+//! assert_eq!(properties.nodes_where("age", |age| age.as_number() > Some(30.0)), vec![alice]);
+//! assert_eq!(properties.edges_with("since", &2015.into()), vec![knows]);
+//! ```
+
+use slotmap::SecondaryMap;
+
+use crate::hash::HashMap;
+use crate::{EdgeID, GraphRef, NodeID};
+
+/// The value of a property.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+pub enum PropertyValue {
+    String(String),
+    Number(f64),
+    Bool(bool),
+}
+
+impl PropertyValue {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            PropertyValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            PropertyValue::Number(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            PropertyValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+impl From<&str> for PropertyValue {
+    fn from(value: &str) -> Self {
+        PropertyValue::String(value.to_string())
+    }
+}
+
+impl From<String> for PropertyValue {
+    fn from(value: String) -> Self {
+        PropertyValue::String(value)
+    }
+}
+
+impl From<bool> for PropertyValue {
+    fn from(value: bool) -> Self {
+        PropertyValue::Bool(value)
+    }
+}
+
+macro_rules! impl_from_number {
+    ($($ty:ty),*) => {
+        $(impl From<$ty> for PropertyValue {
+            fn from(value: $ty) -> Self {
+                PropertyValue::Number(value as f64)
+            }
+        })*
+    };
+}
+
+impl_from_number!(f64, f32, i64, i32, u64, u32, usize);
+
+/// The properties of one node or edge.
+pub type PropertyMap = HashMap<String, PropertyValue>;
+
+/// Key → value properties of the nodes and edges of a graph, see the [module documentation](self).
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Properties {
+    nodes: SecondaryMap<NodeID, PropertyMap>,
+    edges: SecondaryMap<EdgeID, PropertyMap>,
+}
+
+impl Properties {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a property of a node, returning the previous value.
+    pub fn set_node(
+        &mut self,
+        id: NodeID,
+        key: &str,
+        value: impl Into<PropertyValue>,
+    ) -> Option<PropertyValue> {
+        set(&mut self.nodes, id, key, value.into())
+    }
+
+    /// Returns a property of a node.
+    pub fn node(&self, id: NodeID, key: &str) -> Option<&PropertyValue> {
+        self.nodes.get(id)?.get(key)
+    }
+
+    /// Returns all properties of a node.
+    pub fn node_properties(&self, id: NodeID) -> Option<&PropertyMap> {
+        self.nodes.get(id)
+    }
+
+    /// Removes a property of a node, returning its value.
+    pub fn remove_node_property(&mut self, id: NodeID, key: &str) -> Option<PropertyValue> {
+        self.nodes.get_mut(id)?.remove(key)
+    }
+
+    /// Sets a property of an edge, returning the previous value.
+    pub fn set_edge(
+        &mut self,
+        id: EdgeID,
+        key: &str,
+        value: impl Into<PropertyValue>,
+    ) -> Option<PropertyValue> {
+        set(&mut self.edges, id, key, value.into())
+    }
+
+    /// Returns a property of an edge.
+    pub fn edge(&self, id: EdgeID, key: &str) -> Option<&PropertyValue> {
+        self.edges.get(id)?.get(key)
+    }
+
+    /// Returns all properties of an edge.
+    pub fn edge_properties(&self, id: EdgeID) -> Option<&PropertyMap> {
+        self.edges.get(id)
+    }
+
+    /// Removes a property of an edge, returning its value.
+    pub fn remove_edge_property(&mut self, id: EdgeID, key: &str) -> Option<PropertyValue> {
+        self.edges.get_mut(id)?.remove(key)
+    }
+
+    /// Returns the nodes that have the property `key` and for which `predicate` returns true for its value.
+    pub fn nodes_where(
+        &self,
+        key: &str,
+        predicate: impl Fn(&PropertyValue) -> bool,
+    ) -> Vec<NodeID> {
+        matching(&self.nodes, key, predicate)
+    }
+
+    /// Returns the nodes whose property `key` equals `value`.
+    pub fn nodes_with(&self, key: &str, value: &PropertyValue) -> Vec<NodeID> {
+        self.nodes_where(key, |other| other == value)
+    }
+
+    /// Returns the edges that have the property `key` and for which `predicate` returns true for its value.
+    pub fn edges_where(
+        &self,
+        key: &str,
+        predicate: impl Fn(&PropertyValue) -> bool,
+    ) -> Vec<EdgeID> {
+        matching(&self.edges, key, predicate)
+    }
+
+    /// Returns the edges whose property `key` equals `value`.
+    pub fn edges_with(&self, key: &str, value: &PropertyValue) -> Vec<EdgeID> {
+        self.edges_where(key, |other| other == value)
+    }
+
+    /// Drops the properties of nodes and edges that no longer exist in `graph`.
+    pub fn retain_existing<G: GraphRef>(&mut self, graph: &G) {
+        self.nodes.retain(|id, _| graph.node(id).is_ok());
+        self.edges.retain(|id, _| graph.edge(id).is_ok());
+    }
+}
+
+fn set<K: slotmap::Key>(
+    map: &mut SecondaryMap<K, PropertyMap>,
+    id: K,
+    key: &str,
+    value: PropertyValue,
+) -> Option<PropertyValue> {
+    // `entry` only fails for the null key.
+    map.entry(id)?
+        .or_insert_with(PropertyMap::default)
+        .insert(key.to_string(), value)
+}
+
+fn matching<K: slotmap::Key>(
+    map: &SecondaryMap<K, PropertyMap>,
+    key: &str,
+    predicate: impl Fn(&PropertyValue) -> bool,
+) -> Vec<K> {
+    map.iter()
+        .filter(|(_, properties)| properties.get(key).is_some_and(&predicate))
+        .map(|(id, _)| id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Graph, GraphInterface};
+
+    #[test]
+    fn test_properties() {
+        let mut graph: Graph<(), ()> = Graph::new();
+        let nodes = graph.add_nodes(&[(); 3]);
+        let edge = graph.add_edge(nodes[0], nodes[1], ());
+
+        let mut properties = Properties::new();
+        assert_eq!(properties.set_node(nodes[0], "label", "Person"), None);
+        properties.set_node(nodes[1], "label", "Person");
+        properties.set_node(nodes[2], "label", "City");
+        properties.set_node(nodes[0], "active", true);
+        assert_eq!(
+            properties.set_node(nodes[0], "label", "Admin"),
+            Some("Person".into())
+        );
+        properties.set_edge(edge, "weight", 0.5);
+
+        assert_eq!(properties.node_properties(nodes[0]).unwrap().len(), 2);
+        assert_eq!(
+            properties
+                .node(nodes[0], "active")
+                .and_then(PropertyValue::as_bool),
+            Some(true)
+        );
+        assert_eq!(
+            properties.nodes_with("label", &"Person".into()),
+            vec![nodes[1]]
+        );
+        assert_eq!(
+            properties.edges_where("weight", |weight| weight.as_number() < Some(1.0)),
+            vec![edge]
+        );
+        assert_eq!(
+            properties.remove_node_property(nodes[2], "label"),
+            Some("City".into())
+        );
+        assert!(properties.node(nodes[2], "label").is_none());
+
+        // Also removes the edge.
+        graph.remove_node(nodes[1]).unwrap();
+        properties.retain_existing(&graph);
+        assert!(properties.nodes_with("label", &"Person".into()).is_empty());
+        assert!(properties.edge_properties(edge).is_none());
+        assert_eq!(properties.node_properties(nodes[0]).unwrap().len(), 2);
+    }
+}
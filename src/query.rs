@@ -0,0 +1,224 @@
+//! # A small composable query builder over categories and node data.
+//!
+//! [CategorizedGraph::query] starts a [Query], which is built up from steps and only evaluated by [Query::collect] (or [Query::count]).
+//! The evaluation is planned so that the category hash map and the adjacency lists are used wherever possible:
+//! a query that starts with [Query::in_category] only ever looks at the category's members, and
+//! [Query::neighbors]/[Query::successors] only follow the edges of the current nodes. Only a query that starts with [Query::filter] scans all nodes.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//!
+//! let mut graph: CategorizedGraph<u32, ()> = CategorizedGraph::new();
+//! let nodes = graph.add_nodes(&[1, 2, 3, 4]);
+//! graph.add_edges(&[(nodes[0], nodes[2]), (nodes[1], nodes[3])]);
+//! graph.add_to_category("A", vec![nodes[0], nodes[1]]);
+//!
+//! let result = graph
+//!     .query()
+//!     .in_category("A")
+//!     .filter(|node| node.data % 2 == 1)
+//!     .neighbors()
+//!     .collect();
+//!
+//! assert_eq!(result, vec![nodes[2]]);
+//! ```
+
+use std::hash::Hash;
+
+use crate::hash::HashSet;
+use crate::*;
+
+type Predicate<'a, N> = Box<dyn Fn(&Node<N>) -> bool + 'a>;
+
+enum Step<'a, N> {
+    InCategory(Option<NodeID>),
+    Filter(Predicate<'a, N>),
+    Neighbors,
+    Successors,
+}
+
+/// A lazily evaluated query over the nodes of a [CategorizedGraph], see the [module documentation](self).
+///
+/// Every step narrows down or replaces the current set of nodes. The result never contains duplicates and keeps the order in which nodes were first found.
+pub struct Query<'a, N, E, K> {
+    graph: &'a CategorizedGraph<N, E, K>,
+    steps: Vec<Step<'a, N>>,
+}
+
+impl<N, E, K: Hash + Eq> CategorizedGraph<N, E, K> {
+    /// Starts a [Query] over all nodes of the graph.
+    pub fn query(&self) -> Query<'_, N, E, K> {
+        Query {
+            graph: self,
+            steps: Vec::new(),
+        }
+    }
+}
+
+impl<'a, N, E, K: Hash + Eq> Query<'a, N, E, K> {
+    /// Keeps only the members of a category. A category that doesn't exist matches no nodes.
+    pub fn in_category<Q: CategoryKey<K> + ?Sized>(mut self, category: &Q) -> Self {
+        let id = self.graph.category_id_by_name(category).copied();
+        self.steps.push(Step::InCategory(id));
+        self
+    }
+
+    /// Keeps only the members of the category with the given ID.
+    pub fn in_category_id(mut self, category: NodeID) -> Self {
+        self.steps.push(Step::InCategory(Some(category)));
+        self
+    }
+
+    /// Keeps only the nodes for which `predicate` returns true.
+    pub fn filter(mut self, predicate: impl Fn(&Node<N>) -> bool + 'a) -> Self {
+        self.steps.push(Step::Filter(Box::new(predicate)));
+        self
+    }
+
+    /// Replaces the current nodes with the nodes connected to them by an edge in either direction. Category nodes are skipped.
+    pub fn neighbors(mut self) -> Self {
+        self.steps.push(Step::Neighbors);
+        self
+    }
+
+    /// Replaces the current nodes with the targets of their outgoing edges. Category nodes are skipped.
+    pub fn successors(mut self) -> Self {
+        self.steps.push(Step::Successors);
+        self
+    }
+
+    /// Evaluates the query and returns the matching nodes.
+    pub fn collect(self) -> Vec<NodeID> {
+        let graph = self.graph;
+        // `None` stands for "all nodes", which is only materialized when a step can't avoid it.
+        let mut current: Option<Vec<NodeID>> = None;
+
+        for step in self.steps {
+            current = Some(match (step, current) {
+                (Step::InCategory(category), current) => {
+                    let members = category
+                        .map(|id| graph.nodes_by_category_id(id))
+                        .unwrap_or_default();
+                    match current {
+                        None => dedup(members),
+                        Some(mut nodes) => {
+                            let members: HashSet<NodeID> = members.into_iter().collect();
+                            nodes.retain(|node| members.contains(node));
+                            nodes
+                        }
+                    }
+                }
+                (Step::Filter(predicate), None) => graph
+                    .nodes
+                    .values()
+                    .filter(|node| predicate(node))
+                    .map(|node| node.id)
+                    .collect(),
+                (Step::Filter(predicate), Some(mut nodes)) => {
+                    nodes.retain(|id| graph.node(*id).is_ok_and(&predicate));
+                    nodes
+                }
+                (step, current) => {
+                    let outgoing_only = matches!(step, Step::Successors);
+                    let nodes = current.unwrap_or_else(|| graph.nodes().collect());
+                    expand(graph, &nodes, outgoing_only)
+                }
+            });
+        }
+
+        current.unwrap_or_else(|| graph.nodes().collect())
+    }
+
+    /// Evaluates the query and returns the number of matching nodes.
+    pub fn count(self) -> usize {
+        self.collect().len()
+    }
+}
+
+fn dedup(nodes: Vec<NodeID>) -> Vec<NodeID> {
+    let mut seen = HashSet::with_capacity_and_hasher(nodes.len(), Default::default());
+    nodes
+        .into_iter()
+        .filter(|node| seen.insert(*node))
+        .collect()
+}
+
+fn expand<N, E, K>(
+    graph: &CategorizedGraph<N, E, K>,
+    nodes: &[NodeID],
+    outgoing_only: bool,
+) -> Vec<NodeID> {
+    let categories: HashSet<NodeID> = graph.categories.values().copied().collect();
+    let mut seen = HashSet::default();
+    let mut result = Vec::new();
+    for id in nodes {
+        let Ok(node) = graph.node(*id) else {
+            continue;
+        };
+        for edge in node
+            .connections
+            .iter()
+            .filter_map(|edge| graph.edge(*edge).ok())
+        {
+            let other = if edge.from == *id {
+                edge.to
+            } else if outgoing_only {
+                continue;
+            } else {
+                edge.from
+            };
+            if !categories.contains(&other) && seen.insert(other) {
+                result.push(other);
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_steps() {
+        let mut graph: CategorizedGraph<u32, ()> = CategorizedGraph::new();
+        let nodes = graph.add_nodes(&[0, 1, 2, 3, 4]);
+        graph.add_edges(&[
+            (nodes[0], nodes[1]),
+            (nodes[2], nodes[0]),
+            (nodes[1], nodes[3]),
+            (nodes[3], nodes[4]),
+        ]);
+        graph.add_to_category("A", vec![nodes[0], nodes[1], nodes[0]]);
+        graph.add_to_category("B", vec![nodes[1], nodes[4]]);
+
+        assert_eq!(
+            graph.query().in_category("A").collect(),
+            vec![nodes[0], nodes[1]]
+        );
+        assert_eq!(
+            graph.query().in_category("A").in_category("B").collect(),
+            vec![nodes[1]]
+        );
+        assert_eq!(
+            graph.query().in_category("A").successors().collect(),
+            vec![nodes[1], nodes[3]]
+        );
+        assert_eq!(
+            graph
+                .query()
+                .in_category_id(graph.categories["A"])
+                .neighbors()
+                .count(),
+            4
+        );
+        assert_eq!(
+            graph.query().filter(|node| node.data >= 3).collect().len(),
+            2
+        );
+        assert!(graph.query().in_category("Missing").collect().is_empty());
+        // Without any steps every node (including category nodes) matches.
+        assert_eq!(graph.query().count(), 7);
+    }
+}
@@ -0,0 +1,448 @@
+//! # Rule-based graph rewriting.
+//!
+//! A [RewriteSystem] holds rules made of a [Pattern] and a rewrite closure. A pattern is a small graph whose nodes and edges
+//! carry predicates on the data; a [Match] maps its nodes to distinct nodes of the graph and its edges to distinct edges between them
+//! (extra edges in the graph don't prevent a match). The rewrite closure gets the graph and the match, and returns the
+//! [GraphCommand]s that replace the matched part; returning no commands declines the match.
+//!
+//! [RewriteSystem::apply_rules_until_fixpoint] repeatedly applies the first rule (in the order they were added) that has a match it
+//! doesn't decline, until no rule applies, e.g. for peephole optimizations of a dataflow graph.
+//!
+//! Matching is a backtracking search that extends a partial match along the pattern's edges, so patterns should be small and
+//! connected: a pattern node without an edge to an earlier pattern node is tried against every node of the graph.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::commands::{GraphCommand, NodeRef};
+//! use fast_graph::rewrite::{Pattern, RewriteSystem};
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! enum Op { Const(i32), Neg }
+//!
+//! // -(-x) => x: remove both negations and connect x to the users of the outer one.
+//! let mut double_negation = Pattern::new();
+//! let x = double_negation.any_node();
+//! let inner = double_negation.node(|op| *op == Op::Neg);
+//! let outer = double_negation.node(|op| *op == Op::Neg);
+//! double_negation.any_edge(x, inner).any_edge(inner, outer);
+//!
+//! let mut rules = RewriteSystem::new();
+//! rules.add_rule(double_negation, |graph: &Graph<Op, ()>, found| {
+//!     let (x, inner, outer) = (found.nodes[0], found.nodes[1], found.nodes[2]);
+//!     let mut commands = vec![GraphCommand::RemoveNode(NodeRef::Existing(inner)), GraphCommand::RemoveNode(NodeRef::Existing(outer))];
+//!     for edge in graph.edge_references().filter(|edge| edge.from == outer) {
+//!         commands.push(GraphCommand::AddEdge { from: NodeRef::Existing(x), to: NodeRef::Existing(edge.to), data: () });
+//!     }
+//!     commands
+//! });
+//!
+//! let mut graph: Graph<Op, ()> = Graph::new();
+//! let nodes = graph.add_nodes(&[Op::Const(1), Op::Neg, Op::Neg, Op::Neg, Op::Neg, Op::Const(0)]);
+//! graph.add_edges(&[(nodes[0], nodes[1]), (nodes[1], nodes[2]), (nodes[2], nodes[3]), (nodes[3], nodes[4]), (nodes[4], nodes[5])]);
+//!
+//! assert_eq!(rules.apply_rules_until_fixpoint(&mut graph).unwrap(), 2);
+//! assert_eq!(graph.node_count(), 2);
+//! assert_eq!(graph.edge_references().next().map(|edge| (edge.from, edge.to)), Some((nodes[0], nodes[5])));
+//! ```
+
+use thiserror::Error;
+
+use crate::commands::{CommandError, GraphCommand};
+use crate::{EdgeID, Graph, NodeID};
+
+type Predicate<T> = Box<dyn Fn(&T) -> bool>;
+
+/// A pattern to search for in a graph, see the [module documentation](self).
+///
+/// Pattern nodes and edges are numbered in the order they're added, and a [Match] lists the matched graph nodes and edges in that order.
+pub struct Pattern<N, E> {
+    nodes: Vec<Predicate<N>>,
+    edges: Vec<(usize, usize, Predicate<E>)>,
+}
+
+impl<N, E> Default for Pattern<N, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N, E> Pattern<N, E> {
+    pub fn new() -> Self {
+        Pattern {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    /// Adds a pattern node that matches nodes whose data satisfies `predicate`, returning its number.
+    pub fn node(&mut self, predicate: impl Fn(&N) -> bool + 'static) -> usize {
+        self.nodes.push(Box::new(predicate));
+        self.nodes.len() - 1
+    }
+
+    /// Adds a pattern node that matches any node, returning its number.
+    pub fn any_node(&mut self) -> usize {
+        self.node(|_| true)
+    }
+
+    /// Adds a pattern edge from pattern node `from` to `to` that matches edges whose data satisfies `predicate`.
+    ///
+    /// # Panics
+    /// If `from` or `to` isn't a pattern node.
+    pub fn edge(
+        &mut self,
+        from: usize,
+        to: usize,
+        predicate: impl Fn(&E) -> bool + 'static,
+    ) -> &mut Self {
+        assert!(
+            from < self.nodes.len() && to < self.nodes.len(),
+            "pattern edge {} -> {} refers to a pattern node that doesn't exist",
+            from,
+            to
+        );
+        self.edges.push((from, to, Box::new(predicate)));
+        self
+    }
+
+    /// Adds a pattern edge from pattern node `from` to `to` that matches any edge.
+    pub fn any_edge(&mut self, from: usize, to: usize) -> &mut Self {
+        self.edge(from, to, |_| true)
+    }
+
+    /// Returns the first match in the graph, if any.
+    pub fn find_first(&self, graph: &Graph<N, E>) -> Option<Match> {
+        let mut found = None;
+        self.for_each_match(graph, |m| {
+            found = Some(m.clone());
+            true
+        });
+        found
+    }
+
+    /// Returns every match in the graph. Symmetric patterns match the same nodes once per symmetry.
+    pub fn find_all(&self, graph: &Graph<N, E>) -> Vec<Match> {
+        let mut found = Vec::new();
+        self.for_each_match(graph, |m| {
+            found.push(m.clone());
+            false
+        });
+        found
+    }
+
+    /// Calls `visit` with every match until it returns true. Returns true if it did.
+    fn for_each_match(&self, graph: &Graph<N, E>, mut visit: impl FnMut(&Match) -> bool) -> bool {
+        if self.nodes.is_empty() {
+            return false;
+        }
+        let mut state = Match {
+            nodes: Vec::with_capacity(self.nodes.len()),
+            edges: Vec::with_capacity(self.edges.len()),
+        };
+        self.extend_nodes(graph, &mut state, &mut visit)
+    }
+
+    fn extend_nodes(
+        &self,
+        graph: &Graph<N, E>,
+        state: &mut Match,
+        visit: &mut impl FnMut(&Match) -> bool,
+    ) -> bool {
+        let k = state.nodes.len();
+        if k == self.nodes.len() {
+            return self.extend_edges(graph, state, visit);
+        }
+
+        // Follow a pattern edge from or to an already matched node if there is one.
+        let anchor = self
+            .edges
+            .iter()
+            .find_map(|(from, to, _)| match (*from, *to) {
+                (from, to) if to == k && from < k => Some((state.nodes[from], true)),
+                (from, to) if from == k && to < k => Some((state.nodes[to], false)),
+                _ => None,
+            });
+        let mut candidates: Vec<NodeID> = match anchor {
+            Some((anchor, outgoing)) => graph.nodes[anchor]
+                .connections
+                .iter()
+                .filter_map(|edge| graph.edges.get(*edge))
+                .filter_map(|edge| match outgoing {
+                    true if edge.from == anchor => Some(edge.to),
+                    false if edge.to == anchor => Some(edge.from),
+                    _ => None,
+                })
+                .collect(),
+            None => graph.nodes.keys().collect(),
+        };
+        candidates.sort();
+        candidates.dedup();
+
+        for candidate in candidates {
+            let Some(node) = graph.nodes.get(candidate) else {
+                continue;
+            };
+            if state.nodes.contains(&candidate) || !(self.nodes[k])(&node.data) {
+                continue;
+            }
+            state.nodes.push(candidate);
+            // Every pattern edge between matched nodes needs at least one graph edge.
+            let connected = self
+                .edges
+                .iter()
+                .filter(|(from, to, _)| (*from).max(*to) == k)
+                .all(|(from, to, predicate)| {
+                    edges_between(graph, state.nodes[*from], state.nodes[*to])
+                        .any(|id| predicate(&graph.edges[id].data))
+                });
+            if connected && self.extend_nodes(graph, state, visit) {
+                return true;
+            }
+            state.nodes.pop();
+        }
+        false
+    }
+
+    fn extend_edges(
+        &self,
+        graph: &Graph<N, E>,
+        state: &mut Match,
+        visit: &mut impl FnMut(&Match) -> bool,
+    ) -> bool {
+        let k = state.edges.len();
+        let Some((from, to, predicate)) = self.edges.get(k) else {
+            return visit(state);
+        };
+        let candidates: Vec<EdgeID> =
+            edges_between(graph, state.nodes[*from], state.nodes[*to]).collect();
+        for id in candidates {
+            if state.edges.contains(&id) || !predicate(&graph.edges[id].data) {
+                continue;
+            }
+            state.edges.push(id);
+            if self.extend_edges(graph, state, visit) {
+                return true;
+            }
+            state.edges.pop();
+        }
+        false
+    }
+}
+
+/// The edges from `from` to `to`, each once.
+fn edges_between<N, E>(
+    graph: &Graph<N, E>,
+    from: NodeID,
+    to: NodeID,
+) -> impl Iterator<Item = EdgeID> + '_ {
+    let connections = graph
+        .nodes
+        .get(from)
+        .map_or(&[][..], |node| &node.connections[..]);
+    connections.iter().enumerate().filter_map(move |(i, id)| {
+        let edge = graph.edges.get(*id)?;
+        // A self-loop is listed twice.
+        (edge.from == from && edge.to == to && !connections[..i].contains(id)).then_some(*id)
+    })
+}
+
+/// Where a [Pattern] matched, see the [module documentation](self).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Match {
+    /// The matched node of every pattern node.
+    pub nodes: Vec<NodeID>,
+    /// The matched edge of every pattern edge.
+    pub edges: Vec<EdgeID>,
+}
+
+/// Returned by [RewriteSystem::apply_rules_until_fixpoint].
+#[derive(Debug, Error)]
+pub enum RewriteError {
+    /// A rewrite returned commands that couldn't be applied. The commands before the failing one have been applied.
+    #[error("Rule {rule} failed: {source}")]
+    Command { rule: usize, source: CommandError },
+    /// The rules were applied as often as the step limit allows and some rule still matches.
+    #[error("No fixpoint reached after {0} steps")]
+    StepLimit(usize),
+}
+
+type Rewrite<N, E> = Box<dyn FnMut(&Graph<N, E>, &Match) -> Vec<GraphCommand<N, E>>>;
+
+/// A list of rewrite rules, see the [module documentation](self).
+pub struct RewriteSystem<N, E> {
+    rules: Vec<(Pattern<N, E>, Rewrite<N, E>)>,
+    step_limit: Option<usize>,
+}
+
+impl<N, E> Default for RewriteSystem<N, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N, E> RewriteSystem<N, E> {
+    pub fn new() -> Self {
+        RewriteSystem {
+            rules: Vec::new(),
+            step_limit: None,
+        }
+    }
+
+    /// Stops [RewriteSystem::apply_rules_until_fixpoint] with [RewriteError::StepLimit] after `steps` rewrites,
+    /// for rules that might keep rewriting each other's results.
+    pub fn with_step_limit(mut self, steps: usize) -> Self {
+        self.step_limit = Some(steps);
+        self
+    }
+
+    /// Adds a rule, returning its number. Rules added earlier are tried first.
+    pub fn add_rule(
+        &mut self,
+        pattern: Pattern<N, E>,
+        rewrite: impl FnMut(&Graph<N, E>, &Match) -> Vec<GraphCommand<N, E>> + 'static,
+    ) -> usize {
+        self.rules.push((pattern, Box::new(rewrite)));
+        self.rules.len() - 1
+    }
+
+    /// Applies the first rule with a match it doesn't decline. Returns the number of the rule, or `None` if no rule applies.
+    pub fn apply_once(&mut self, graph: &mut Graph<N, E>) -> Result<Option<usize>, RewriteError> {
+        let Some((rule, commands)) = self.next_rewrite(graph) else {
+            return Ok(None);
+        };
+        graph
+            .apply_commands(commands)
+            .map_err(|source| RewriteError::Command { rule, source })?;
+        Ok(Some(rule))
+    }
+
+    /// Applies rules until none applies, returning the number of rewrites.
+    pub fn apply_rules_until_fixpoint(
+        &mut self,
+        graph: &mut Graph<N, E>,
+    ) -> Result<usize, RewriteError> {
+        let mut steps = 0;
+        while let Some((rule, commands)) = self.next_rewrite(graph) {
+            if self.step_limit == Some(steps) {
+                return Err(RewriteError::StepLimit(steps));
+            }
+            graph
+                .apply_commands(commands)
+                .map_err(|source| RewriteError::Command { rule, source })?;
+            steps += 1;
+        }
+        Ok(steps)
+    }
+
+    /// Returns the first rule with a match it doesn't decline, and the commands it returned.
+    fn next_rewrite(&mut self, graph: &Graph<N, E>) -> Option<(usize, Vec<GraphCommand<N, E>>)> {
+        for (rule, (pattern, rewrite)) in self.rules.iter_mut().enumerate() {
+            let mut commands = Vec::new();
+            pattern.for_each_match(graph, |found| {
+                commands = rewrite(graph, found);
+                !commands.is_empty()
+            });
+            if !commands.is_empty() {
+                return Some((rule, commands));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::NodeRef;
+    use crate::{GraphInterface, GraphRef};
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Op {
+        Const(i32),
+        Add,
+        Output,
+    }
+
+    #[test]
+    fn test_constant_folding() {
+        // (1 + 2) + (3 + 4) -> output, with the operands as edge data (0 = left, 1 = right).
+        let mut graph: Graph<Op, u8> = Graph::new();
+        let nodes = graph.add_nodes(&[
+            Op::Const(1),
+            Op::Const(2),
+            Op::Add,
+            Op::Const(3),
+            Op::Const(4),
+            Op::Add,
+            Op::Add,
+            Op::Output,
+        ]);
+        graph.add_edges_with_data(&[
+            (nodes[0], nodes[2], 0),
+            (nodes[1], nodes[2], 1),
+            (nodes[3], nodes[5], 0),
+            (nodes[4], nodes[5], 1),
+            (nodes[2], nodes[6], 0),
+            (nodes[5], nodes[6], 1),
+            (nodes[6], nodes[7], 0),
+        ]);
+
+        let mut fold = Pattern::new();
+        let left = fold.node(|op| matches!(op, Op::Const(_)));
+        let right = fold.node(|op| matches!(op, Op::Const(_)));
+        let add = fold.node(|op| *op == Op::Add);
+        fold.edge(left, add, |side| *side == 0)
+            .edge(right, add, |side| *side == 1);
+        assert_eq!(fold.find_all(&graph).len(), 2);
+
+        let mut rules = RewriteSystem::new().with_step_limit(10);
+        rules.add_rule(fold, |graph: &Graph<Op, u8>, found| {
+            let value = |id: NodeID| match graph.nodes[id].data {
+                Op::Const(value) => value,
+                _ => unreachable!("matched constants"),
+            };
+            let sum = value(found.nodes[0]) + value(found.nodes[1]);
+            let mut commands = vec![GraphCommand::AddNode(Op::Const(sum))];
+            for edge in graph
+                .edge_references()
+                .filter(|edge| edge.from == found.nodes[2])
+            {
+                commands.push(GraphCommand::AddEdge {
+                    from: NodeRef::New(0),
+                    to: NodeRef::Existing(edge.to),
+                    data: edge.data,
+                });
+            }
+            commands.extend(
+                found
+                    .nodes
+                    .iter()
+                    .map(|id| GraphCommand::RemoveNode(NodeRef::Existing(*id))),
+            );
+            commands
+        });
+        // A rule that declines every match doesn't count as a step.
+        let mut never = Pattern::new();
+        never.node(|op| *op == Op::Output);
+        rules.add_rule(never, |_, _| Vec::new());
+
+        assert_eq!(rules.apply_rules_until_fixpoint(&mut graph).unwrap(), 3);
+        assert_eq!(graph.node_count(), 2);
+        let result = graph.edge_references().next().unwrap();
+        assert_eq!(graph.nodes[result.from].data, Op::Const(10));
+        assert_eq!(result.to, nodes[7]);
+        assert!(rules.apply_once(&mut graph).unwrap().is_none());
+
+        // A rule that always applies hits the step limit.
+        let mut grow = Pattern::new();
+        grow.any_node();
+        let mut rules = RewriteSystem::new().with_step_limit(3);
+        rules.add_rule(grow, |_, _| vec![GraphCommand::AddNode(Op::Output)]);
+        assert!(matches!(
+            rules.apply_rules_until_fixpoint(&mut graph),
+            Err(RewriteError::StepLimit(3))
+        ));
+        assert_eq!(graph.node_count(), 5);
+    }
+}
@@ -0,0 +1,119 @@
+//! # Random sampling of nodes and edges (requires the `rand` feature).
+//!
+//! [Graph] and [CategorizedGraph](crate::CategorizedGraph) get `sample_nodes`, `sample_edges` and `random_node` methods, which
+//! use [reservoir sampling](https://en.wikipedia.org/wiki/Reservoir_sampling) over the slotmaps: one pass, no extra allocation besides the result.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use rand::{rngs::SmallRng, SeedableRng};
+//!
+//! let mut graph: Graph<u32, ()> = Graph::new();
+//! graph.add_nodes(&(0..100).collect::<Vec<u32>>());
+//!
+//! let mut rng = SmallRng::seed_from_u64(7);
+//! let sample = graph.sample_nodes(&mut rng, 10);
+//! assert_eq!(sample.len(), 10);
+//! assert!(graph.random_node(&mut rng).is_some());
+//! ```
+
+use rand::Rng;
+
+use crate::{EdgeID, Graph, NodeID};
+
+/// Picks `k` items uniformly at random from `iter` in a single pass (Algorithm R). Returns all items if there are at most `k`.
+///
+/// The order of the returned items is unspecified.
+pub fn reservoir_sample<T, R: Rng + ?Sized>(
+    iter: impl IntoIterator<Item = T>,
+    rng: &mut R,
+    k: usize,
+) -> Vec<T> {
+    let iter = iter.into_iter();
+    // `k` may be far larger than the input, so the reservoir is only sized by what the iterator promises.
+    let mut reservoir = Vec::with_capacity(k.min(iter.size_hint().0));
+    if k == 0 {
+        return reservoir;
+    }
+    for (i, item) in iter.enumerate() {
+        if i < k {
+            reservoir.push(item);
+        } else {
+            let j = rng.gen_range(0..=i);
+            if j < k {
+                reservoir[j] = item;
+            }
+        }
+    }
+    reservoir
+}
+
+impl<N, E> Graph<N, E> {
+    /// Returns `k` distinct nodes chosen uniformly at random, or all nodes if there are fewer than `k`.
+    pub fn sample_nodes<R: Rng + ?Sized>(&self, rng: &mut R, k: usize) -> Vec<NodeID> {
+        reservoir_sample(self.nodes.keys(), rng, k)
+    }
+
+    /// Returns `k` distinct edges chosen uniformly at random, or all edges if there are fewer than `k`.
+    pub fn sample_edges<R: Rng + ?Sized>(&self, rng: &mut R, k: usize) -> Vec<EdgeID> {
+        reservoir_sample(self.edges.keys(), rng, k)
+    }
+
+    /// Returns a node chosen uniformly at random, or `None` if the graph is empty.
+    pub fn random_node<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<NodeID> {
+        self.sample_nodes(rng, 1).pop()
+    }
+}
+
+#[cfg(feature = "categories")]
+impl<N, E, K> crate::CategorizedGraph<N, E, K> {
+    /// Returns `k` distinct nodes (including category nodes) chosen uniformly at random, or all nodes if there are fewer than `k`.
+    pub fn sample_nodes<R: Rng + ?Sized>(&self, rng: &mut R, k: usize) -> Vec<NodeID> {
+        reservoir_sample(self.nodes.keys(), rng, k)
+    }
+
+    /// Returns `k` distinct edges (including membership edges) chosen uniformly at random, or all edges if there are fewer than `k`.
+    pub fn sample_edges<R: Rng + ?Sized>(&self, rng: &mut R, k: usize) -> Vec<EdgeID> {
+        reservoir_sample(self.edges.keys(), rng, k)
+    }
+
+    /// Returns a node chosen uniformly at random, or `None` if the graph is empty.
+    pub fn random_node<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<NodeID> {
+        self.sample_nodes(rng, 1).pop()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GraphInterface, GraphRef};
+    use rand::{rngs::SmallRng, SeedableRng};
+
+    #[test]
+    fn test_sampling() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let mut graph: Graph<u32, ()> = Graph::new();
+        assert_eq!(graph.random_node(&mut rng), None);
+
+        let nodes = graph.add_nodes(&(0..50).collect::<Vec<u32>>());
+        graph.add_edges(&[(nodes[0], nodes[1]), (nodes[1], nodes[2])]);
+
+        let mut sample = graph.sample_nodes(&mut rng, 20);
+        sample.sort();
+        sample.dedup();
+        assert_eq!(sample.len(), 20);
+        assert!(sample.iter().all(|node| graph.node(*node).is_ok()));
+
+        assert_eq!(graph.sample_edges(&mut rng, 5).len(), 2);
+        assert!(graph.sample_nodes(&mut rng, 0).is_empty());
+        assert_eq!(graph.sample_nodes(&mut rng, usize::MAX).len(), 50);
+
+        // Every node should be picked now and then.
+        let mut counts = [0; 50];
+        for _ in 0..5000 {
+            let node = graph.random_node(&mut rng).unwrap();
+            counts[graph.node(node).unwrap().data as usize] += 1;
+        }
+        assert!(counts.iter().all(|count| *count > 0));
+    }
+}
@@ -0,0 +1,434 @@
+use serde::de::Error as _;
+#[cfg(feature = "categories")]
+use serde::ser::Error as _;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(feature = "categories")]
+use crate::hash::{HashMap, HashSet};
+use crate::*;
+#[cfg(feature = "categories")]
+use std::hash::Hash;
+
+/* -------------------------------------------------------------------------- */
+/*                                    Graph                                   */
+/* -------------------------------------------------------------------------- */
+
+impl<N: Serialize, E: Serialize> Serialize for Graph<N, E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Graph", 2)?;
+        state.serialize_field("nodes", &self.nodes)?;
+        state.serialize_field("edges", &self.edges)?;
+        state.end()
+    }
+}
+
+/// Deserializes the slotmaps and then runs [Graph::validate], so a payload whose keys don't line up is rejected instead of producing an inconsistent graph.
+/// Dangling edges (see [Graph::collect_dangling_edges]) are accepted, since the graph itself can produce them, so every graph survives a round trip.
+impl<'de, N: Deserialize<'de>, E: Deserialize<'de>> Deserialize<'de> for Graph<N, E> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename = "Graph")]
+        #[serde(bound(deserialize = "N: Deserialize<'de>, E: Deserialize<'de>"))]
+        struct GraphData<N, E> {
+            nodes: SlotMap<NodeID, Node<N>>,
+            edges: SlotMap<EdgeID, Edge<E>>,
+        }
+
+        let data = GraphData::deserialize(deserializer)?;
+        let graph = Graph {
+            // `strict` and the stats are runtime state and aren't part of the format.
+            strict: false,
+            stats: Default::default(),
+            // The insertion order isn't serialized either, the slot order becomes the order of everything added before.
+            #[cfg(feature = "ordered")]
+            order: order::InsertionOrder::from_ids(data.nodes.keys(), data.edges.keys()),
+            nodes: data.nodes,
+            edges: data.edges,
+        };
+        graph
+            .validate_allowing_dangling()
+            .map_err(D::Error::custom)?;
+        Ok(graph)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+/*                              CategorizedGraph                              */
+/* -------------------------------------------------------------------------- */
+
+// The stable format: nodes and edges refer to each other by their index in these lists instead of by slotmap key,
+// and the memberships of a category are listed with it rather than stored as edges. `member_data` holds the data of
+// the membership edges, in the order of `members`; if it's missing (e.g. in a hand-written payload) `E::default()` is used.
+// A category node registered under several keys is listed once, with the keys after the first one in `aliases`.
+
+#[cfg(feature = "categories")]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "DenseEdge")]
+struct DenseEdge<E> {
+    from: usize,
+    to: usize,
+    data: E,
+}
+
+#[cfg(feature = "categories")]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "CategoryEntry")]
+struct CategoryEntry<K, E> {
+    key: K,
+    #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
+    aliases: Vec<K>,
+    node: usize,
+    members: Vec<usize>,
+    #[serde(default = "Vec::new")]
+    member_data: Vec<E>,
+}
+
+#[cfg(feature = "categories")]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "CategorizedGraph")]
+struct CategorizedGraphData<N, E, K> {
+    nodes: Vec<N>,
+    edges: Vec<DenseEdge<E>>,
+    categories: Vec<CategoryEntry<K, E>>,
+    #[serde(default)]
+    category_node_policy: CategoryNodePolicy,
+}
+
+#[cfg(feature = "categories")]
+impl<N: Serialize, E: Serialize, K: Serialize + Hash + Eq> Serialize for CategorizedGraph<N, E, K> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // With the `ordered` feature nodes and edges are written in insertion order, otherwise in slot order.
+        #[cfg(feature = "ordered")]
+        let (node_ids, edge_ids) = (self.nodes_ordered(), self.edges_ordered());
+        #[cfg(not(feature = "ordered"))]
+        let (node_ids, edge_ids): (Vec<NodeID>, Vec<EdgeID>) =
+            (self.nodes.keys().collect(), self.edges.keys().collect());
+
+        let indices: HashMap<NodeID, usize> = node_ids
+            .iter()
+            .copied()
+            .enumerate()
+            .map(|(index, id)| (id, index))
+            .collect();
+        let index = |id: NodeID| {
+            indices.get(&id).copied().ok_or_else(|| {
+                S::Error::custom(format!("edge or category refers to missing node {:?}", id))
+            })
+        };
+
+        let categories = self.all_categories_ordered();
+        let category_nodes: HashSet<NodeID> = categories.iter().map(|(_, id)| *id).collect();
+
+        let mut edges = Vec::with_capacity(self.edges.len());
+        // The (members, member_data) of every category.
+        let mut memberships: HashMap<NodeID, (Vec<usize>, Vec<&E>)> = HashMap::default();
+        for edge in edge_ids.iter().map(|id| &self.edges[*id]) {
+            // Memberships are written with their category.
+            if category_nodes.contains(&edge.from) {
+                let (members, member_data) = memberships.entry(edge.from).or_default();
+                members.push(index(edge.to)?);
+                member_data.push(&edge.data);
+            } else {
+                edges.push(DenseEdge {
+                    from: index(edge.from)?,
+                    to: index(edge.to)?,
+                    data: &edge.data,
+                });
+            }
+        }
+
+        let mut entries: Vec<CategoryEntry<&K, &E>> = Vec::with_capacity(categories.len());
+        // The position of every category node's entry, to add its other keys as aliases.
+        let mut entry_of: HashMap<NodeID, usize> = HashMap::default();
+        for (key, id) in categories {
+            if let Some(entry) = entry_of.get(&id) {
+                entries[*entry].aliases.push(key);
+                continue;
+            }
+            let (members, member_data) = memberships.remove(&id).unwrap_or_default();
+            entry_of.insert(id, entries.len());
+            entries.push(CategoryEntry {
+                key,
+                aliases: Vec::new(),
+                node: index(id)?,
+                members,
+                member_data,
+            });
+        }
+
+        CategorizedGraphData {
+            nodes: node_ids.iter().map(|id| &self.nodes[*id].data).collect(),
+            edges,
+            categories: entries,
+            category_node_policy: self.category_node_policy,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Rebuilds the slotmaps and the category map from the stable format. Out of range indices and duplicate categories are rejected.
+#[cfg(feature = "categories")]
+impl<'de, N, E, K> Deserialize<'de> for CategorizedGraph<N, E, K>
+where
+    N: Deserialize<'de>,
+    E: Deserialize<'de> + Default,
+    K: Deserialize<'de> + Hash + Eq,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = CategorizedGraphData::<N, E, K>::deserialize(deserializer)?;
+
+        let mut graph = CategorizedGraph::new();
+        graph.category_node_policy = data.category_node_policy;
+        let ids: Vec<NodeID> = data
+            .nodes
+            .into_iter()
+            .map(|node| graph.add_node(node))
+            .collect();
+        let id = |index: usize| {
+            ids.get(index).copied().ok_or_else(|| {
+                D::Error::custom(format!(
+                    "index {} is out of range for {} nodes",
+                    index,
+                    ids.len()
+                ))
+            })
+        };
+
+        for edge in data.edges {
+            graph.add_edge(id(edge.from)?, id(edge.to)?, edge.data);
+        }
+
+        for category in data.categories {
+            let node = id(category.node)?;
+            if graph.is_category_node(node) {
+                return Err(D::Error::custom(format!(
+                    "node {} is listed as more than one category",
+                    category.node
+                )));
+            }
+            for key in std::iter::once(category.key).chain(category.aliases) {
+                if graph.categories.contains_key(&key) {
+                    return Err(D::Error::custom("duplicate category key"));
+                }
+                graph.categories.insert(key, node);
+                graph.register_category_node(node);
+            }
+            graph.category_order.push(node);
+            if !category.member_data.is_empty()
+                && category.member_data.len() != category.members.len()
+            {
+                return Err(D::Error::custom(format!(
+                    "{} member_data entries for {} members",
+                    category.member_data.len(),
+                    category.members.len()
+                )));
+            }
+            let mut member_data = category.member_data.into_iter();
+            for member in category.members {
+                graph.add_edge(node, id(member)?, member_data.next().unwrap_or_default());
+            }
+        }
+
+        Ok(graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph_round_trip() {
+        let mut graph: Graph<String, u32> = Graph::new();
+        let a = graph.add_node("A".into());
+        let b = graph.add_node("B".into());
+        let removed = graph.add_node("C".into());
+        let edge = graph.add_edge(a, b, 7);
+        graph.remove_node(removed).unwrap();
+
+        let json = serde_json::to_string(&graph).unwrap();
+        let restored: Graph<String, u32> = serde_json::from_str(&json).unwrap();
+
+        // Keys survive the round trip unchanged.
+        assert_eq!(restored.node(a).unwrap().data, "A");
+        assert_eq!(restored.edge(edge).unwrap().to, b);
+        assert!(restored.node(removed).is_err());
+    }
+
+    #[cfg(feature = "ordered")]
+    #[test]
+    fn test_deserialized_nodes_come_before_new_ones() {
+        let mut graph: Graph<(), ()> = Graph::new();
+        let nodes = graph.add_nodes(&[(); 3]);
+        let edge = graph.add_edge(nodes[0], nodes[1], ());
+        graph.remove_node(nodes[2]).unwrap();
+
+        let json = serde_json::to_string(&graph).unwrap();
+        let mut restored: Graph<(), ()> = serde_json::from_str(&json).unwrap();
+        // Reuses the slot of `nodes[2]`, but is still ordered after the deserialized nodes.
+        let added = restored.add_node(());
+        let added_edge = restored.add_edge(added, nodes[0], ());
+
+        assert_eq!(restored.nodes_ordered(), vec![nodes[0], nodes[1], added]);
+        assert_eq!(restored.edges_ordered(), vec![edge, added_edge]);
+    }
+
+    #[test]
+    fn test_inconsistent_payload_is_rejected() {
+        let mut graph: Graph<(), ()> = Graph::new();
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        graph.add_edge(a, b, ());
+
+        let mut json = serde_json::to_value(&graph).unwrap();
+        // Drop the edge from the connections of `b`, which still exists.
+        json["nodes"][b.to_u64() as u32 as usize]["value"]["connections"] = serde_json::json!([]);
+
+        let error = serde_json::from_value::<Graph<(), ()>>(json).unwrap_err();
+        assert!(
+            error.to_string().contains("missing from the connections"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_dangling_edges_round_trip() {
+        let mut graph: Graph<(), ()> = Graph::new();
+        let [a, b, c] = graph.add_nodes(&[(); 3])[..] else {
+            unreachable!()
+        };
+        let kept = graph.add_edge(a, b, ());
+        graph.remove_node(b).unwrap();
+        // Outside of strict mode this adds an edge to a node that doesn't exist.
+        let dangling = graph.add_edge(c, b, ());
+        let removed = graph.add_edge(a, c, ());
+        graph.remove_edge(removed).unwrap();
+        assert!(graph.validate().is_err());
+
+        let json = serde_json::to_string(&graph).unwrap();
+        let restored: Graph<(), ()> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.edge(dangling).unwrap().to, b);
+        assert!(restored.edge(kept).is_err() && restored.edge(removed).is_err());
+        assert_eq!(restored.node(c).unwrap().connections.len(), 2);
+    }
+
+    #[cfg(feature = "categories")]
+    #[test]
+    fn test_categorized_graph_round_trip() {
+        let mut graph: CategorizedGraph<String, u32> = CategorizedGraph::new();
+        let a = graph.add_node("A".into());
+        let removed = graph.add_node("removed".into());
+        let b = graph.add_node("B".into());
+        graph.add_edge(a, b, 7);
+        graph.add_to_category_or_create_with("Second", vec![b], || "second".into());
+        graph.add_to_category_or_create_with("First", vec![a, b], || "first".into());
+        graph.move_category("First", 0).unwrap();
+        graph.remove_node(removed).unwrap();
+        // Membership edges can carry data too.
+        let first = *graph.category_id_by_name("First").unwrap();
+        let membership = graph.node(first).unwrap().connections[1];
+        graph.edge_mut(membership).unwrap().data = 3;
+
+        let json = serde_json::to_value(&graph).unwrap();
+        assert_eq!(json["nodes"][0], "A");
+        assert_eq!(json["categories"][0]["key"], "First");
+        assert_eq!(json["categories"][0]["members"], serde_json::json!([0, 1]));
+        assert_eq!(
+            json["categories"][0]["member_data"],
+            serde_json::json!([0, 3])
+        );
+        assert_eq!(json["edges"].as_array().unwrap().len(), 1);
+
+        let restored: CategorizedGraph<String, u32> = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&restored).unwrap(), json);
+
+        let first: Vec<&String> = restored.category_data_iter("First").collect();
+        assert_eq!(first, vec!["A", "B"]);
+        assert_eq!(restored.category("Second").unwrap().data, "second");
+        let names: Vec<&String> = restored
+            .all_categories_ordered()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, vec!["First", "Second"]);
+        let first = *restored.category_id_by_name("First").unwrap();
+        let data: Vec<u32> = restored
+            .node(first)
+            .unwrap()
+            .connections
+            .iter()
+            .map(|id| restored.edge(*id).unwrap().data)
+            .collect();
+        assert_eq!(data, vec![0, 3]);
+    }
+
+    #[cfg(feature = "categories")]
+    #[test]
+    fn test_node_in_two_categories_round_trip() {
+        let mut graph: CategorizedGraph<String, u32> = CategorizedGraph::new();
+        let a = graph.add_node("A".into());
+        let b = graph.add_node("B".into());
+        let x = graph.add_to_category("X", vec![a, b]);
+        graph.add_to_category("Y", vec![a]);
+        // A second key for the same category node.
+        graph.insert_category_id_by_name("Z", x);
+
+        let json = serde_json::to_value(&graph).unwrap();
+        assert_eq!(json["categories"].as_array().unwrap().len(), 2);
+        let mut keys = vec![json["categories"][0]["key"].clone()];
+        keys.extend(json["categories"][0]["aliases"].as_array().unwrap().clone());
+        keys.sort_by_key(|key| key.to_string());
+        assert_eq!(keys, vec!["X", "Z"]);
+        let restored: CategorizedGraph<String, u32> = serde_json::from_value(json).unwrap();
+
+        for (name, members) in [
+            ("X", vec!["A", "B"]),
+            ("Z", vec!["A", "B"]),
+            ("Y", vec!["A"]),
+        ] {
+            let data: Vec<&String> = restored.category_data_iter(name).collect();
+            assert_eq!(data, members, "{}", name);
+        }
+        assert_eq!(
+            restored.category_id_by_name("X"),
+            restored.category_id_by_name("Z")
+        );
+        // Both keys are registered, so the node stays a category node after one of them goes.
+        let mut restored = restored;
+        let node = restored.remove_category_id_by_name("X").unwrap();
+        assert!(restored.is_category_node(node));
+        assert_eq!(restored.nodes.len(), 4);
+    }
+
+    #[cfg(feature = "categories")]
+    #[test]
+    fn test_categorized_graph_without_member_data() {
+        let json = serde_json::json!({
+            "nodes": ["A", "Category"],
+            "edges": [],
+            "categories": [{ "key": "Category", "node": 1, "members": [0] }],
+        });
+        let graph = serde_json::from_value::<CategorizedGraph<String, u32>>(json.clone()).unwrap();
+        assert_eq!(graph.edges.values().next().unwrap().data, 0);
+
+        let mut mismatched = json;
+        mismatched["categories"][0]["member_data"] = serde_json::json!([1, 2]);
+        let error =
+            serde_json::from_value::<CategorizedGraph<String, u32>>(mismatched).unwrap_err();
+        assert!(error.to_string().contains("member_data"), "{}", error);
+    }
+
+    #[cfg(feature = "categories")]
+    #[test]
+    fn test_categorized_graph_out_of_range_is_rejected() {
+        let json = serde_json::json!({
+            "nodes": ["A"],
+            "edges": [],
+            "categories": [{ "key": "Category", "node": 0, "members": [3] }],
+        });
+        let error = serde_json::from_value::<CategorizedGraph<String, ()>>(json).unwrap_err();
+        assert!(error.to_string().contains("out of range"), "{}", error);
+    }
+}
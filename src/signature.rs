@@ -0,0 +1,334 @@
+//! # Structural hashes of graphs.
+//!
+//! [structural_hash] reduces a graph to a `u64` that only depends on its structure and data, not on [NodeID]s, [EdgeID](crate::EdgeID)s,
+//! slot order or the order in which nodes and edges were added, so it's a cheap check whether two runs of a pipeline produced the
+//! same graph. It uses Weisfeiler–Lehman refinement:
+//!
+//! 1. Every node starts with the hash of its data as its label.
+//! 2. In every iteration, the new label of a node is the hash of its label together with the sorted (edge data, label) pairs
+//!    of its outgoing edges and, separately, of its incoming edges.
+//! 3. This stops after the first iteration that doesn't split any group of equally labeled nodes. The graph's hash covers the
+//!    sorted labels after every iteration.
+//!
+//! Graphs that are isomorphic (with equal data) always have the same hash. Different graphs almost always have different
+//! hashes, but like every Weisfeiler–Lehman test it can't tell apart some regular graphs, e.g. a 6-cycle and two triangles.
+//! Compare with [structural_diff](crate::compare::structural_diff) if a match has to be confirmed.
+//!
+//! [wl_features] returns the labels of a fixed number of iterations as histograms instead, for the Weisfeiler–Lehman subtree
+//! kernel: [WlFeatures::similarity] compares two graphs by how many labels they share, which approximates how similar their
+//! structures are (e.g. as input features for machine learning).
+//!
+//! The data is hashed with the standard library's [DefaultHasher] (with fixed keys, unlike the maps in [hash](crate::hash)),
+//! so a hash is stable across runs of the same build, but shouldn't be stored across Rust versions.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//!
+//! let mut a: Graph<&str, u32> = Graph::new();
+//! let [x, y] = a.add_nodes(&["x", "y"])[..] else { unreachable!() };
+//! a.add_edge(x, y, 1);
+//!
+//! // The same graph, built in a different order.
+//! let mut b: Graph<&str, u32> = Graph::new();
+//! let [y, x] = b.add_nodes(&["y", "x"])[..] else { unreachable!() };
+//! b.add_edge(x, y, 1);
+//! assert_eq!(a.structural_hash(), b.structural_hash());
+//!
+//! b.add_edge(y, x, 1);
+//! assert_ne!(a.structural_hash(), b.structural_hash());
+//!
+//! // Still similar: both have the nodes "x" and "y" and an edge from "x" to "y".
+//! let similarity = a.wl_features(2).similarity(&b.wl_features(2));
+//! assert!(similarity > 0.3 && similarity < 1.0);
+//! ```
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::hash::{HashMap, HashSet};
+use crate::{outgoing_edges, Graph, GraphRef, NodeID};
+
+fn hash_one<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The state of Weisfeiler–Lehman refinement: the current label of every node and the neighborhoods of the nodes, by position.
+struct Refinement {
+    labels: Vec<u64>,
+    /// The (hash of the edge data, position of the target) of every outgoing edge.
+    outgoing: Vec<Vec<(u64, usize)>>,
+    /// The (hash of the edge data, position of the source) of every incoming edge.
+    incoming: Vec<Vec<(u64, usize)>>,
+}
+
+impl Refinement {
+    fn new<G>(graph: &G) -> Self
+    where
+        G: GraphRef,
+        G::NodeData: Hash,
+        G::EdgeData: Hash,
+    {
+        let nodes: Vec<NodeID> = graph.nodes().collect();
+        let index: HashMap<NodeID, usize> =
+            nodes.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        let mut labels = Vec::with_capacity(nodes.len());
+        let mut outgoing = vec![Vec::new(); nodes.len()];
+        let mut incoming = vec![Vec::new(); nodes.len()];
+        for (i, id) in nodes.iter().enumerate() {
+            let Ok(node) = graph.node(*id) else {
+                labels.push(0);
+                continue;
+            };
+            labels.push(hash_one(&node.data));
+            // Every edge is recorded from its source.
+            for edge in outgoing_edges(graph, *id) {
+                let Some(target) = index.get(&edge.to).copied() else {
+                    continue;
+                };
+                let data = hash_one(&edge.data);
+                outgoing[i].push((data, target));
+                incoming[target].push((data, i));
+            }
+        }
+        Refinement {
+            labels,
+            outgoing,
+            incoming,
+        }
+    }
+
+    fn edge_count(&self) -> usize {
+        self.outgoing.iter().map(Vec::len).sum()
+    }
+
+    /// The number of different labels.
+    fn distinct(&self) -> usize {
+        self.labels.iter().collect::<HashSet<_>>().len()
+    }
+
+    fn sorted_labels(&self) -> Vec<u64> {
+        let mut labels = self.labels.clone();
+        labels.sort_unstable();
+        labels
+    }
+
+    /// Runs one iteration, see the [module documentation](self).
+    fn refine(&mut self) {
+        let neighborhood = |edges: &[(u64, usize)]| {
+            let mut labels: Vec<(u64, u64)> = edges
+                .iter()
+                .map(|(data, other)| (*data, self.labels[*other]))
+                .collect();
+            labels.sort_unstable();
+            labels
+        };
+        let labels = (0..self.labels.len())
+            .map(|i| {
+                hash_one(&(
+                    self.labels[i],
+                    neighborhood(&self.outgoing[i]),
+                    neighborhood(&self.incoming[i]),
+                ))
+            })
+            .collect();
+        self.labels = labels;
+    }
+}
+
+/// Returns a hash of the structure and data of the graph that doesn't depend on IDs or insertion order, see the [module documentation](self).
+///
+/// Runs at most one iteration per node, each in O(nodes + edges · log(degree)).
+pub fn structural_hash<G>(graph: &G) -> u64
+where
+    G: GraphRef,
+    G::NodeData: Hash,
+    G::EdgeData: Hash,
+{
+    let mut refinement = Refinement::new(graph);
+    let mut hasher = DefaultHasher::new();
+    refinement.labels.len().hash(&mut hasher);
+    refinement.edge_count().hash(&mut hasher);
+
+    let mut distinct = refinement.distinct();
+    refinement.sorted_labels().hash(&mut hasher);
+    for _ in 0..refinement.labels.len() {
+        refinement.refine();
+        refinement.sorted_labels().hash(&mut hasher);
+        let refined = refinement.distinct();
+        if refined == distinct {
+            break;
+        }
+        distinct = refined;
+    }
+    hasher.finish()
+}
+
+/// Label histograms from Weisfeiler–Lehman refinement, returned by [wl_features].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WlFeatures {
+    /// For the initial labels and then after every iteration: how many nodes have each label.
+    pub histograms: Vec<HashMap<u64, usize>>,
+}
+
+impl WlFeatures {
+    /// The Weisfeiler–Lehman subtree kernel: the sum over all iterations and labels of the product of the counts in both graphs.
+    ///
+    /// Only histograms of the same iteration are compared, so both should have been extracted with the same number of iterations.
+    pub fn kernel(&self, other: &WlFeatures) -> u64 {
+        self.histograms
+            .iter()
+            .zip(&other.histograms)
+            .map(|(a, b)| {
+                a.iter()
+                    .filter_map(|(label, count)| b.get(label).map(|other| (count * other) as u64))
+                    .sum::<u64>()
+            })
+            .sum()
+    }
+
+    /// The [kernel](WlFeatures::kernel) normalized to `0.0..=1.0`, where `1.0` means the histograms are equal
+    /// (up to a constant factor). Returns `0.0` if either graph is empty.
+    pub fn similarity(&self, other: &WlFeatures) -> f64 {
+        let norm = (self.kernel(self) as f64 * other.kernel(other) as f64).sqrt();
+        if norm == 0.0 {
+            return 0.0;
+        }
+        self.kernel(other) as f64 / norm
+    }
+}
+
+/// Returns the label histograms of the initial labels and of `iterations` refinement iterations, see the [module documentation](self).
+///
+/// Unlike [structural_hash], this always runs exactly `iterations` iterations, so that the features of different graphs line up.
+pub fn wl_features<G>(graph: &G, iterations: usize) -> WlFeatures
+where
+    G: GraphRef,
+    G::NodeData: Hash,
+    G::EdgeData: Hash,
+{
+    let mut refinement = Refinement::new(graph);
+    let mut histograms = Vec::with_capacity(iterations + 1);
+    for iteration in 0..=iterations {
+        if iteration > 0 {
+            refinement.refine();
+        }
+        let mut histogram = HashMap::default();
+        for label in &refinement.labels {
+            *histogram.entry(*label).or_default() += 1;
+        }
+        histograms.push(histogram);
+    }
+    WlFeatures { histograms }
+}
+
+impl<N: Hash, E: Hash> Graph<N, E> {
+    /// Returns the [structural_hash] of the graph.
+    pub fn structural_hash(&self) -> u64 {
+        structural_hash(self)
+    }
+
+    /// Returns the [wl_features] of the graph.
+    pub fn wl_features(&self, iterations: usize) -> WlFeatures {
+        wl_features(self, iterations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GraphInterface;
+
+    #[test]
+    fn test_structural_hash() {
+        // A path 0 → 1 → 2 with a self-loop on 2, with a removed node in between.
+        let mut a: Graph<u32, u32> = Graph::new();
+        let nodes = a.add_nodes(&[0, 1, 2]);
+        let temp = a.add_node(9);
+        a.add_edges_with_data(&[
+            (nodes[0], nodes[1], 1),
+            (nodes[1], nodes[2], 1),
+            (nodes[2], nodes[2], 2),
+        ]);
+        a.remove_node(temp).unwrap();
+
+        let mut b: Graph<u32, u32> = Graph::new();
+        let nodes = b.add_nodes(&[2, 1, 0]);
+        b.add_edges_with_data(&[
+            (nodes[0], nodes[0], 2),
+            (nodes[2], nodes[1], 1),
+            (nodes[1], nodes[0], 1),
+        ]);
+        assert_eq!(a.structural_hash(), b.structural_hash());
+        assert_eq!(a.structural_hash(), a.clone().structural_hash());
+        assert_ne!(
+            a.structural_hash(),
+            Graph::<u32, u32>::new().structural_hash()
+        );
+
+        // Different edge data or directions are told apart.
+        let mut data = b.clone();
+        data.edges.values_mut().next().unwrap().data = 3;
+        assert_ne!(a.structural_hash(), data.structural_hash());
+
+        let mut reversed: Graph<u32, u32> = Graph::new();
+        let nodes = reversed.add_nodes(&[0, 1, 2]);
+        reversed.add_edges_with_data(&[
+            (nodes[1], nodes[0], 1),
+            (nodes[1], nodes[2], 1),
+            (nodes[2], nodes[2], 2),
+        ]);
+        assert_ne!(a.structural_hash(), reversed.structural_hash());
+
+        // The same chain with a marked node near its start or near its end.
+        let chain = |marked: usize| {
+            let mut graph: Graph<u32, ()> = Graph::new();
+            let nodes = graph.add_nodes(&[0; 5]);
+            graph.add_edges(&[
+                (nodes[0], nodes[1]),
+                (nodes[1], nodes[2]),
+                (nodes[2], nodes[3]),
+                (nodes[3], nodes[4]),
+            ]);
+            graph.node_mut(nodes[marked]).unwrap().data = 1;
+            graph
+        };
+        assert_ne!(chain(1).structural_hash(), chain(3).structural_hash());
+        assert_eq!(chain(1).structural_hash(), chain(1).structural_hash());
+    }
+
+    #[test]
+    fn test_wl_features() {
+        let star = |leaves: usize, data: u32| {
+            let mut graph: Graph<u32, ()> = Graph::new();
+            let center = graph.add_node(data);
+            for _ in 0..leaves {
+                let leaf = graph.add_node(0);
+                graph.add_edge(center, leaf, ());
+            }
+            graph
+        };
+        let features = star(4, 1).wl_features(2);
+        assert_eq!(features.histograms.len(), 3);
+        // The center and the leaves.
+        let mut counts: Vec<usize> = features.histograms[0].values().copied().collect();
+        counts.sort();
+        assert_eq!(counts, vec![1, 4]);
+        assert_eq!(features.kernel(&features), 3 * (1 + 16));
+        assert_eq!(features.similarity(&features), 1.0);
+
+        // The same initial labels. The centers differ from iteration 1 on, the leaves only from iteration 2 on.
+        let bigger = star(5, 1).wl_features(2);
+        let similarity = features.similarity(&bigger);
+        assert!(similarity > 0.3 && similarity < 1.0);
+        // With different center data, only the initial labels of the leaves are shared.
+        let other = star(4, 2).wl_features(2);
+        assert!(features.similarity(&other) < similarity);
+        assert_eq!(
+            features.similarity(&Graph::<u32, ()>::new().wl_features(2)),
+            0.0
+        );
+    }
+}
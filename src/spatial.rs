@@ -0,0 +1,394 @@
+//! # A graph whose nodes have coordinates.
+//!
+//! [SpatialGraph] stores a 2D, 3D (or any `D`-dimensional) position for every node and builds distance-weighted edges from them:
+//! [SpatialGraph::connect_within_radius] uses a uniform grid with cells the size of the radius, and [SpatialGraph::connect_k_nearest] uses a k-d tree.
+//! That's the usual way of turning sample points into a roadmap or navmesh graph.
+//!
+//! The edge data is the euclidean distance between the two nodes, so the graph can be searched directly with
+//! [BestFirst](crate::algorithms::BestFirst) (Dijkstra/A*).
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//!
+//! let mut graph: SpatialGraph<&str> = SpatialGraph::new();
+//! let a = graph.add_node("A", [0.0, 0.0]);
+//! let b = graph.add_node("B", [3.0, 4.0]);
+//! let c = graph.add_node("C", [10.0, 0.0]);
+//!
+//! // A and B are 5 apart, C is too far from both.
+//! let edges = graph.connect_within_radius(6.0).unwrap();
+//! assert_eq!(edges.len(), 2);
+//! assert_eq!(graph.graph().edge(edges[0]).unwrap().data, 5.0);
+//!
+//! assert_eq!(graph.k_nearest([9.0, 1.0], 1), vec![c]);
+//! ```
+
+use core::fmt;
+use std::fmt::Formatter;
+
+use slotmap::SecondaryMap;
+use thiserror::Error;
+
+use crate::hash::HashMap;
+use crate::*;
+
+/// A position in `D`-dimensional space.
+pub type Point<const D: usize> = [f64; D];
+
+/// Returned by [SpatialGraph::connect_within_radius] when the radius isn't a positive, finite number.
+#[derive(Clone, Copy, Debug, PartialEq, Error)]
+#[error("Radius {0} is not a positive, finite number")]
+pub struct InvalidRadius(pub f64);
+
+/* -------------------------------------------------------------------------- */
+/*                                SpatialGraph                                */
+/* -------------------------------------------------------------------------- */
+
+/// A [Graph] with a position for every node and edges weighted by the distance between their endpoints, see the [module documentation](self).
+///
+/// Nodes added through [SpatialGraph::graph_mut] have no position until [SpatialGraph::set_position] is called, and are ignored by the proximity helpers.
+pub struct SpatialGraph<N, const D: usize = 2> {
+    graph: Graph<N, f64>,
+    positions: SecondaryMap<NodeID, Point<D>>,
+}
+
+impl<N, const D: usize> SpatialGraph<N, D> {
+    pub fn new() -> Self {
+        SpatialGraph {
+            graph: Graph::new(),
+            positions: SecondaryMap::new(),
+        }
+    }
+
+    /// Returns the underlying graph.
+    pub fn graph(&self) -> &Graph<N, f64> {
+        &self.graph
+    }
+
+    /// Returns the underlying graph mutably, e.g. to add edges that aren't based on distance.
+    pub fn graph_mut(&mut self) -> &mut Graph<N, f64> {
+        &mut self.graph
+    }
+
+    pub fn into_graph(self) -> Graph<N, f64> {
+        self.graph
+    }
+
+    pub fn add_node(&mut self, data: N, position: Point<D>) -> NodeID {
+        let id = self.graph.add_node(data);
+        self.positions.insert(id, position);
+        id
+    }
+
+    /// Removes a node, its position and its edges.
+    pub fn remove_node(&mut self, id: NodeID) -> Result<(), GraphError> {
+        self.graph.remove_node(id)?;
+        self.positions.remove(id);
+        Ok(())
+    }
+
+    /// Sets the position of a node. Doesn't update the weights of its existing edges.
+    pub fn set_position(&mut self, id: NodeID, position: Point<D>) -> Result<(), GraphError> {
+        self.graph.node(id)?;
+        self.positions.insert(id, position);
+        Ok(())
+    }
+
+    pub fn position(&self, id: NodeID) -> Option<Point<D>> {
+        self.positions.get(id).copied()
+    }
+
+    /// Returns the euclidean distance between two nodes, if both have a position.
+    pub fn distance(&self, a: NodeID, b: NodeID) -> Option<f64> {
+        Some(distance_squared(&self.position(a)?, &self.position(b)?).sqrt())
+    }
+
+    /// Adds an edge from `a` to `b` weighted by their distance. Returns `None` if either node has no position.
+    pub fn connect(&mut self, a: NodeID, b: NodeID) -> Option<EdgeID> {
+        let distance = self.distance(a, b)?;
+        Some(self.graph.add_edge(a, b, distance))
+    }
+
+    /// Connects every pair of nodes that are at most `radius` apart, with an edge in each direction. Returns the added edges.
+    ///
+    /// Uses a uniform grid with cells of size `radius`, so only the nodes in neighbouring cells are compared.
+    /// Fails with [InvalidRadius] if `radius` is zero, negative, infinite or NaN.
+    pub fn connect_within_radius(&mut self, radius: f64) -> Result<Vec<EdgeID>, InvalidRadius> {
+        if !(radius > 0.0 && radius.is_finite()) {
+            return Err(InvalidRadius(radius));
+        }
+        let points = self.points();
+        let cell_of = |point: &Point<D>| point.map(|x| (x / radius).floor() as i64);
+
+        let mut grid: HashMap<[i64; D], Vec<usize>> = HashMap::default();
+        for (index, (_, point)) in points.iter().enumerate() {
+            grid.entry(cell_of(point)).or_default().push(index);
+        }
+
+        let mut edges = Vec::new();
+        for (index, (id, point)) in points.iter().enumerate() {
+            let cell = cell_of(point);
+            // All 3^D combinations of -1, 0 and 1. Far-out points (or a tiny radius) saturate the cell coordinates,
+            // and the cells past `i64::MIN`/`i64::MAX` don't exist.
+            'offsets: for mut offset in 0..3usize.pow(D as u32) {
+                let mut neighbour = cell;
+                for coordinate in neighbour.iter_mut() {
+                    let Some(moved) = coordinate.checked_add((offset % 3) as i64 - 1) else {
+                        continue 'offsets;
+                    };
+                    *coordinate = moved;
+                    offset /= 3;
+                }
+                let Some(candidates) = grid.get(&neighbour) else {
+                    continue;
+                };
+                // Every pair is only handled once, by its lower index.
+                for other in candidates.iter().filter(|other| **other > index) {
+                    let (other_id, other_point) = points[*other];
+                    let distance = distance_squared(point, &other_point).sqrt();
+                    if distance <= radius {
+                        edges.push(self.graph.add_edge(*id, other_id, distance));
+                        edges.push(self.graph.add_edge(other_id, *id, distance));
+                    }
+                }
+            }
+        }
+        Ok(edges)
+    }
+
+    /// Adds an edge from every node to each of its `k` nearest other nodes. Returns the added edges.
+    ///
+    /// The resulting graph isn't symmetric: a node can be among the `k` nearest of another node without the reverse being true.
+    pub fn connect_k_nearest(&mut self, k: usize) -> Vec<EdgeID> {
+        let points = self.points();
+        let tree = KdTree::new(&points);
+        let k = k.min(points.len());
+        let mut edges = Vec::with_capacity(points.len() * k);
+        for (index, (id, point)) in points.iter().enumerate() {
+            for (distance_squared, other) in tree.nearest(point, k, Some(index)) {
+                edges.push(
+                    self.graph
+                        .add_edge(*id, points[other].0, distance_squared.sqrt()),
+                );
+            }
+        }
+        edges
+    }
+
+    /// Returns the `k` nodes closest to `point`, closest first.
+    pub fn k_nearest(&self, point: Point<D>, k: usize) -> Vec<NodeID> {
+        let points = self.points();
+        KdTree::new(&points)
+            .nearest(&point, k, None)
+            .into_iter()
+            .map(|(_, index)| points[index].0)
+            .collect()
+    }
+
+    /// Returns the positions of all nodes, e.g. to render the graph with [render_svg](crate::svg::render_svg).
+    pub fn positions(&self) -> &SecondaryMap<NodeID, Point<D>> {
+        &self.positions
+    }
+
+    /// Returns the nodes (that still exist) and their positions.
+    fn points(&self) -> Vec<(NodeID, Point<D>)> {
+        self.positions
+            .iter()
+            .filter(|(id, _)| self.graph.node(*id).is_ok())
+            .map(|(id, point)| (id, *point))
+            .collect()
+    }
+}
+
+impl<N: fmt::Debug + Clone, const D: usize> fmt::Debug for SpatialGraph<N, D> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpatialGraph")
+            .field("graph", &self.graph)
+            .field("positions", &self.positions)
+            .finish()
+    }
+}
+
+impl<N, const D: usize> Default for SpatialGraph<N, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn distance_squared<const D: usize>(a: &Point<D>, b: &Point<D>) -> f64 {
+    a.iter().zip(b).map(|(a, b)| (a - b) * (a - b)).sum()
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                   KdTree                                   */
+/* -------------------------------------------------------------------------- */
+
+/// A k-d tree over indices into a slice of points, stored implicitly: the median of every range is its root.
+struct KdTree<'a, const D: usize> {
+    points: &'a [(NodeID, Point<D>)],
+    order: Vec<usize>,
+}
+
+impl<'a, const D: usize> KdTree<'a, D> {
+    fn new(points: &'a [(NodeID, Point<D>)]) -> Self {
+        let mut order: Vec<usize> = (0..points.len()).collect();
+        Self::build(points, &mut order, 0);
+        KdTree { points, order }
+    }
+
+    fn build(points: &[(NodeID, Point<D>)], order: &mut [usize], depth: usize) {
+        if order.len() <= 1 {
+            return;
+        }
+        let axis = depth % D;
+        let mid = order.len() / 2;
+        order.select_nth_unstable_by(mid, |a, b| {
+            points[*a].1[axis].total_cmp(&points[*b].1[axis])
+        });
+        let (left, right) = order.split_at_mut(mid);
+        Self::build(points, left, depth + 1);
+        Self::build(points, &mut right[1..], depth + 1);
+    }
+
+    /// Returns the squared distances and indices of the `k` points nearest to `target` (except `skip`), closest first.
+    fn nearest(&self, target: &Point<D>, k: usize, skip: Option<usize>) -> Vec<(f64, usize)> {
+        let k = k.min(self.order.len());
+        let mut best = Vec::with_capacity(k + 1);
+        if k > 0 {
+            self.search(0, self.order.len(), 0, target, k, skip, &mut best);
+        }
+        best
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn search(
+        &self,
+        lo: usize,
+        hi: usize,
+        depth: usize,
+        target: &Point<D>,
+        k: usize,
+        skip: Option<usize>,
+        best: &mut Vec<(f64, usize)>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let index = self.order[mid];
+        let point = &self.points[index].1;
+
+        if skip != Some(index) {
+            let distance = distance_squared(point, target);
+            if best.len() < k || distance < best[best.len() - 1].0 {
+                let position = best.partition_point(|(other, _)| *other <= distance);
+                best.insert(position, (distance, index));
+                best.truncate(k);
+            }
+        }
+
+        let axis = depth % D;
+        let diff = target[axis] - point[axis];
+        let (near, far) = if diff < 0.0 {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+        self.search(near.0, near.1, depth + 1, target, k, skip, best);
+        // The other side can only contain closer points if the splitting plane is closer than the current k-th best.
+        if best.len() < k || diff * diff < best[best.len() - 1].0 {
+            self.search(far.0, far.1, depth + 1, target, k, skip, best);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_k_nearest_matches_brute_force() {
+        let mut graph: SpatialGraph<usize, 3> = SpatialGraph::new();
+        let mut seed = 12345u64;
+        let mut random = move || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as f64 / (1u64 << 31) as f64
+        };
+        let nodes: Vec<NodeID> = (0..200)
+            .map(|i| graph.add_node(i, [random(), random(), random()]))
+            .collect();
+
+        for target in [[0.5, 0.5, 0.5], [0.0, 1.0, 0.2], [2.0, -1.0, 0.0]] {
+            let mut expected = nodes.clone();
+            expected.sort_by(|a, b| {
+                let a = distance_squared(&graph.position(*a).unwrap(), &target);
+                let b = distance_squared(&graph.position(*b).unwrap(), &target);
+                a.total_cmp(&b)
+            });
+            expected.truncate(7);
+            assert_eq!(graph.k_nearest(target, 7), expected);
+        }
+
+        let edges = graph.connect_k_nearest(3);
+        assert_eq!(edges.len(), 600);
+        assert!(edges.iter().all(|edge| {
+            let edge = graph.graph().edge(*edge).unwrap();
+            edge.from != edge.to && edge.data == graph.distance(edge.from, edge.to).unwrap()
+        }));
+    }
+
+    #[test]
+    fn test_connect_within_radius() {
+        let mut graph: SpatialGraph<()> = SpatialGraph::new();
+        let nodes: Vec<NodeID> = (0..10)
+            .map(|i| graph.add_node((), [i as f64 * 0.75, (i % 2) as f64]))
+            .collect();
+
+        let edges = graph.connect_within_radius(1.3).unwrap();
+        let mut brute_force = 0;
+        for a in &nodes {
+            for b in &nodes {
+                if a != b && graph.distance(*a, *b).unwrap() <= 1.3 {
+                    brute_force += 1;
+                }
+            }
+        }
+        assert_eq!(edges.len(), brute_force);
+
+        for radius in [0.0, -1.0, f64::INFINITY] {
+            assert_eq!(
+                graph.connect_within_radius(radius),
+                Err(InvalidRadius(radius))
+            );
+        }
+        assert!(graph.connect_within_radius(f64::NAN).is_err());
+
+        graph.remove_node(nodes[0]).unwrap();
+        assert_eq!(graph.position(nodes[0]), None);
+        assert_eq!(graph.k_nearest([0.0, 0.0], 1), vec![nodes[1]]);
+    }
+
+    #[test]
+    fn test_extreme_radius_and_k() {
+        let mut graph: SpatialGraph<()> = SpatialGraph::new();
+        let a = graph.add_node((), [1.0, -1.0]);
+        let b = graph.add_node((), [1.0, -1.0]);
+        graph.add_node((), [2.0, 0.0]);
+
+        // Every coordinate saturates to `i64::MIN` or `i64::MAX`, only the coincident points are connected.
+        let edges = graph
+            .connect_within_radius(f64::MIN_POSITIVE / 4.0)
+            .unwrap();
+        assert_eq!(edges.len(), 2);
+        assert!(edges.iter().all(|edge| {
+            let edge = graph.graph().edge(*edge).unwrap();
+            [a, b].contains(&edge.from) && [a, b].contains(&edge.to)
+        }));
+
+        assert_eq!(graph.k_nearest([0.0, 0.0], usize::MAX).len(), 3);
+        assert_eq!(graph.connect_k_nearest(usize::MAX).len(), 6);
+    }
+}
@@ -1,3 +1,6 @@
+// Hand-expanded derive output, kept close to what `#[derive(specta::Type)]` generates.
+#![allow(clippy::let_and_return, clippy::needless_borrow)]
+
 use crate::*;
 
 /* -------------------------------------------------------------------------- */
@@ -209,3 +212,112 @@ const _: () = {
     #[automatically_derived]
     impl specta::Flatten for EdgeID {}
 };
+
+/* -------------------------------------------------------------------------- */
+/*                          Graph & CategorizedGraph                          */
+/* -------------------------------------------------------------------------- */
+
+// `SlotMap` doesn't implement `specta::Type`, so the graphs describe their serde representation through these mirror types instead.
+// A serialized slotmap is a list of slots where occupied slots have a `value`.
+
+#[cfg(feature = "specta")]
+#[derive(specta::Type)]
+#[specta(rename = "Slot")]
+#[allow(dead_code)]
+struct SlotType<T> {
+    value: Option<T>,
+    version: u32,
+}
+
+#[cfg(feature = "specta")]
+#[derive(specta::Type)]
+#[specta(rename = "Graph")]
+#[allow(dead_code)]
+struct GraphType<N, E> {
+    nodes: Vec<SlotType<Node<N>>>,
+    edges: Vec<SlotType<Edge<E>>>,
+}
+
+// `CategorizedGraph` is serialized in a stable format where nodes are referred to by their index, see `serde_impls`.
+
+#[cfg(all(feature = "specta", feature = "categories"))]
+#[derive(specta::Type)]
+#[specta(rename = "CategorizedGraph")]
+#[allow(dead_code)]
+struct CategorizedGraphType<N, E> {
+    nodes: Vec<N>,
+    edges: Vec<DenseEdgeType<E>>,
+    categories: Vec<CategoryEntryType<E>>,
+    category_node_policy: crate::CategoryNodePolicy,
+}
+
+#[cfg(all(feature = "specta", feature = "categories"))]
+#[derive(specta::Type)]
+#[specta(rename = "DenseEdge")]
+#[allow(dead_code)]
+struct DenseEdgeType<E> {
+    from: usize,
+    to: usize,
+    data: E,
+}
+
+#[cfg(all(feature = "specta", feature = "categories"))]
+#[derive(specta::Type)]
+#[specta(rename = "CategoryEntry")]
+#[allow(dead_code)]
+struct CategoryEntryType<E> {
+    key: String,
+    #[specta(optional)]
+    aliases: Vec<String>,
+    node: usize,
+    members: Vec<usize>,
+    member_data: Vec<E>,
+}
+
+#[cfg(feature = "specta")]
+macro_rules! impl_specta_type_via {
+    ($ty:ident => $via:ident) => {
+        #[automatically_derived]
+        impl<N: specta::Type + 'static, E: specta::Type + 'static> specta::Type for $ty<N, E> {
+            fn inline(
+                type_map: &mut specta::TypeMap,
+                generics: &[specta::DataType],
+            ) -> specta::DataType {
+                <$via<N, E> as specta::Type>::inline(type_map, generics)
+            }
+            fn definition(type_map: &mut specta::TypeMap) -> specta::DataType {
+                <$via<N, E> as specta::Type>::definition(type_map)
+            }
+            fn reference(
+                type_map: &mut specta::TypeMap,
+                generics: &[specta::DataType],
+            ) -> specta::reference::Reference {
+                <$via<N, E> as specta::Type>::reference(type_map, generics)
+            }
+        }
+        #[automatically_derived]
+        impl<N: specta::Type + 'static, E: specta::Type + 'static> specta::NamedType for $ty<N, E> {
+            const SID: specta::SpectaID = <$via<N, E> as specta::NamedType>::SID;
+            const IMPL_LOCATION: specta::ImplLocation =
+                <$via<N, E> as specta::NamedType>::IMPL_LOCATION;
+            fn named_data_type(
+                type_map: &mut specta::TypeMap,
+                generics: &[specta::DataType],
+            ) -> specta::NamedDataType {
+                <$via<N, E> as specta::NamedType>::named_data_type(type_map, generics)
+            }
+            fn definition_named_data_type(type_map: &mut specta::TypeMap) -> specta::NamedDataType {
+                <$via<N, E> as specta::NamedType>::definition_named_data_type(type_map)
+            }
+        }
+        #[automatically_derived]
+        impl<N: specta::Type + 'static, E: specta::Type + 'static> specta::Flatten for $ty<N, E> {}
+    };
+}
+
+#[cfg(feature = "specta")]
+impl_specta_type_via!(Graph => GraphType);
+
+// Only the default `String` key is exported, since TypeScript map keys have to be strings.
+#[cfg(all(feature = "specta", feature = "categories"))]
+impl_specta_type_via!(CategorizedGraph => CategorizedGraphType);
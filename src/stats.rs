@@ -0,0 +1,127 @@
+//! # Mutation counters.
+//!
+//! Every [Graph] counts the nodes and edges added to and removed from it, and remembers the most nodes and edges it has held
+//! at once. The counters are updated in the mutation methods of [Graph] (adding and removing nodes and edges, and the bulk
+//! clean-ups like [Graph::dedup_edges] and [Graph::collect_dangling_edges]), at the cost of a few additions, so a long-running
+//! service can monitor the churn of a graph with [Graph::stats] without wrapping every call.
+//!
+//! Edges removed together with their node count as removed edges. Rebuilding the storage ([Graph::compact],
+//! [Graph::optimize_layout]) isn't churn and only counts the dangling edges it drops. Changes made directly to the public
+//! slotmaps aren't counted.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//!
+//! let mut graph: Graph<(), ()> = Graph::new();
+//! let nodes = graph.add_nodes(&[(); 3]);
+//! graph.add_edges(&[(nodes[0], nodes[1]), (nodes[1], nodes[2])]);
+//! let before = graph.stats();
+//!
+//! graph.remove_node(nodes[1]).unwrap();
+//! let delta = graph.stats().since(&before);
+//! assert_eq!((delta.nodes_removed, delta.edges_removed), (1, 2));
+//! assert_eq!(graph.stats().max_edges, 2);
+//! ```
+
+use crate::Graph;
+
+/// The mutation counters of a [Graph], see the [module documentation](self).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GraphStats {
+    pub nodes_added: u64,
+    pub nodes_removed: u64,
+    pub edges_added: u64,
+    pub edges_removed: u64,
+    /// The most nodes the graph has held at once.
+    pub max_nodes: usize,
+    /// The most edges the graph has held at once.
+    pub max_edges: usize,
+}
+
+impl GraphStats {
+    /// Returns the counts of the mutations since `earlier`, a previous result of [Graph::stats]. The high-water marks are kept.
+    pub fn since(&self, earlier: &GraphStats) -> GraphStats {
+        GraphStats {
+            nodes_added: self.nodes_added.saturating_sub(earlier.nodes_added),
+            nodes_removed: self.nodes_removed.saturating_sub(earlier.nodes_removed),
+            edges_added: self.edges_added.saturating_sub(earlier.edges_added),
+            edges_removed: self.edges_removed.saturating_sub(earlier.edges_removed),
+            max_nodes: self.max_nodes,
+            max_edges: self.max_edges,
+        }
+    }
+
+    pub(crate) fn add_node(&mut self, nodes: usize) {
+        self.nodes_added += 1;
+        self.max_nodes = self.max_nodes.max(nodes);
+    }
+
+    pub(crate) fn add_edge(&mut self, edges: usize) {
+        self.edges_added += 1;
+        self.max_edges = self.max_edges.max(edges);
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    /// Returns the mutation counters of the graph, see the [module documentation](self).
+    pub fn stats(&self) -> GraphStats {
+        self.stats
+    }
+
+    /// Resets the counters, starting the high-water marks at the current size.
+    pub fn reset_stats(&mut self) {
+        self.stats = GraphStats {
+            max_nodes: self.nodes.len(),
+            max_edges: self.edges.len(),
+            ..GraphStats::default()
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GraphInterface;
+
+    #[test]
+    fn test_graph_stats() {
+        let mut graph: Graph<u32, u32> = Graph::new();
+        let nodes = graph.add_nodes(&[0, 1, 2]);
+        let edges = graph.add_edges_with_data(&[
+            (nodes[0], nodes[1], 0),
+            (nodes[1], nodes[1], 1),
+            (nodes[0], nodes[1], 2),
+            (nodes[2], nodes[0], 3),
+        ]);
+        graph.remove_edge(edges[3]).unwrap();
+        assert!(graph.remove_edge(edges[3]).is_err());
+        assert_eq!(graph.dedup_edges(|_, _| {}), 1);
+        // The self-loop is listed twice, but removed once.
+        graph.remove_node(nodes[1]).unwrap();
+        assert_eq!(
+            graph.stats(),
+            GraphStats {
+                nodes_added: 3,
+                nodes_removed: 1,
+                edges_added: 4,
+                edges_removed: 4,
+                max_nodes: 3,
+                max_edges: 4,
+            }
+        );
+
+        // A dangling edge is counted when it's collected.
+        let before = graph.stats();
+        graph.add_edge(nodes[0], nodes[1], 4);
+        assert_eq!(graph.collect_dangling_edges().len(), 1);
+        graph.compact();
+        let delta = graph.stats().since(&before);
+        assert_eq!((delta.edges_added, delta.edges_removed), (1, 1));
+        assert_eq!(graph.clone().stats(), graph.stats());
+
+        graph.reset_stats();
+        assert_eq!(graph.stats().max_nodes, 2);
+        assert_eq!(graph.stats().edges_added, 0);
+    }
+}
@@ -0,0 +1,149 @@
+//! # Async traversal streams (requires the `async` feature).
+//!
+//! [dfs_stream] and [bfs_stream] wrap a traversal in a [Stream], so a very large traversal can be consumed from an async task without
+//! blocking the executor: after every `yield_every` nodes the stream returns [Poll::Pending] once (waking itself right away), which gives
+//! other tasks on the same thread a chance to run, like `tokio::task::yield_now` does.
+//!
+//! The streams only depend on [futures-core](https://docs.rs/futures-core), so they work with any executor.
+//!
+//! # Example
+//! ```
+//! use std::future::poll_fn;
+//! use std::pin::Pin;
+//!
+//! use fast_graph::*;
+//! use fast_graph::stream::bfs_stream;
+//! use futures_core::Stream;
+//!
+//! async fn count_reachable(graph: &Graph<(), ()>, start: NodeID) -> usize {
+//!     let mut stream = bfs_stream(graph, start, 1024);
+//!     let mut count = 0;
+//!     // With the `futures` crate this is `while let Some(node) = stream.next().await`.
+//!     while let Some(_node) = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await {
+//!         count += 1;
+//!     }
+//!     count
+//! }
+//! ```
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+
+use crate::algorithms::{BreadthFirst, DepthFirstSearch};
+use crate::{GraphRef, NodeID};
+
+/// A [Stream] over the items of an iterator that yields to the executor every `yield_every` items, see the [module documentation](self).
+pub struct TraversalStream<I> {
+    iter: I,
+    yield_every: usize,
+    since_yield: usize,
+}
+
+impl<I: Iterator> TraversalStream<I> {
+    /// Wraps any traversal (or other iterator). A `yield_every` of 0 is treated as 1.
+    pub fn new(iter: I, yield_every: usize) -> Self {
+        TraversalStream {
+            iter,
+            yield_every: yield_every.max(1),
+            since_yield: 0,
+        }
+    }
+}
+
+impl<I: Iterator + Unpin> Stream for TraversalStream<I> {
+    type Item = I::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.since_yield == self.yield_every {
+            self.since_yield = 0;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        self.since_yield += 1;
+        Poll::Ready(self.iter.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Returns a stream of the nodes reachable from `start` in depth first order (like [DepthFirstSearch]), yielding every `yield_every` nodes.
+pub fn dfs_stream<G: GraphRef>(
+    graph: &G,
+    start: NodeID,
+    yield_every: usize,
+) -> TraversalStream<DepthFirstSearch<'_, G>> {
+    TraversalStream::new(DepthFirstSearch::new(graph, start), yield_every)
+}
+
+/// Returns a stream of the nodes reachable from `start` over outgoing edges in breadth first order (the order of
+/// [bfs_layers](crate::algorithms::bfs_layers), flattened), yielding every `yield_every` nodes.
+pub fn bfs_stream<G: GraphRef>(
+    graph: &G,
+    start: NodeID,
+    yield_every: usize,
+) -> TraversalStream<BreadthFirst<'_, G>> {
+    TraversalStream::new(BreadthFirst::new(graph, start), yield_every)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::bfs_layers;
+    use crate::{Graph, GraphInterface};
+    use std::sync::Arc;
+    use std::task::{Wake, Waker};
+
+    struct CountingWaker(std::sync::atomic::AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.wake_by_ref();
+        }
+
+        fn wake_by_ref(self: &Arc<Self>) {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+
+    /// Polls the stream to completion, returning the items and how many times it returned `Pending`.
+    fn drain<S: Stream + Unpin>(mut stream: S) -> (Vec<S::Item>, usize) {
+        let waker = Arc::new(CountingWaker(Default::default()));
+        let task_waker = Waker::from(waker.clone());
+        let mut cx = Context::from_waker(&task_waker);
+        let (mut items, mut pending) = (Vec::new(), 0);
+        loop {
+            match Pin::new(&mut stream).poll_next(&mut cx) {
+                Poll::Ready(Some(item)) => items.push(item),
+                Poll::Ready(None) => break,
+                Poll::Pending => pending += 1,
+            }
+        }
+        // Every `Pending` woke the task.
+        assert_eq!(waker.0.load(std::sync::atomic::Ordering::Relaxed), pending);
+        (items, pending)
+    }
+
+    #[test]
+    fn test_streams_yield_cooperatively() {
+        let mut graph: Graph<(), ()> = Graph::new();
+        let nodes = graph.add_nodes(&[(); 10]);
+        for i in 1..10 {
+            graph.add_edge(nodes[(i - 1) / 2], nodes[i], ());
+        }
+
+        let (bfs, pending) = drain(bfs_stream(&graph, nodes[0], 3));
+        assert_eq!(bfs, bfs_layers(&graph, nodes[0]).concat());
+        // 10 nodes and the final `None` in batches of 3.
+        assert_eq!(pending, 3);
+
+        let (dfs, _) = drain(dfs_stream(&graph, nodes[0], 1));
+        assert_eq!(
+            dfs,
+            DepthFirstSearch::new(&graph, nodes[0]).collect::<Vec<_>>()
+        );
+    }
+}
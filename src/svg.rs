@@ -0,0 +1,285 @@
+//! # Rendering laid-out graphs as SVG.
+//!
+//! [render_svg] draws the nodes of a graph as circles at the given 2D positions (e.g. [SpatialGraph::positions](crate::SpatialGraph::positions))
+//! and its edges as arrows between them, returning an SVG document as a string. The drawing is scaled to fit the positions, with
+//! [SvgStyle] controlling sizes and colors, plus hooks for per-node/per-edge colors and node labels. There are no dependencies involved,
+//! the result can be written to a `.svg` file or embedded into HTML.
+//!
+//! Nodes without a position are left out, together with their edges.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::svg::{render_svg, SvgStyle};
+//!
+//! let mut graph: SpatialGraph<&str> = SpatialGraph::new();
+//! let a = graph.add_node("A", [0.0, 0.0]);
+//! let b = graph.add_node("B", [100.0, 50.0]);
+//! graph.connect(a, b);
+//!
+//! let style = SvgStyle::default()
+//!     .node_label(|_, data: &&str| data.to_string())
+//!     .node_fill(|id, _| if id == a { "tomato".into() } else { "steelblue".into() });
+//! let svg = render_svg(graph.graph(), graph.positions(), &style);
+//! assert!(svg.starts_with("<svg"));
+//! assert!(svg.contains(r#"fill="tomato""#) && svg.contains(">B</text>"));
+//! ```
+
+use std::fmt::Write;
+
+use slotmap::SecondaryMap;
+
+use crate::spatial::Point;
+use crate::{outgoing_edges, EdgeID, GraphRef, NodeID};
+
+type Hook<'a, I, D> = Box<dyn Fn(I, &D) -> String + 'a>;
+
+/// Sizes, colors and styling hooks for [render_svg].
+pub struct SvgStyle<'a, N, E> {
+    pub node_radius: f64,
+    /// The space around the drawing.
+    pub margin: f64,
+    pub stroke_width: f64,
+    /// The default node fill color, used if there's no [SvgStyle::node_fill] hook.
+    pub node_color: String,
+    /// The default edge color, used if there's no [SvgStyle::edge_stroke] hook. Also the color of the arrow heads.
+    pub edge_color: String,
+    pub font_size: f64,
+    /// Whether to draw arrow heads at the targets of edges.
+    pub arrows: bool,
+    node_label: Option<Hook<'a, NodeID, N>>,
+    node_fill: Option<Hook<'a, NodeID, N>>,
+    edge_stroke: Option<Hook<'a, EdgeID, E>>,
+}
+
+impl<N, E> Default for SvgStyle<'_, N, E> {
+    fn default() -> Self {
+        SvgStyle {
+            node_radius: 10.0,
+            margin: 20.0,
+            stroke_width: 1.5,
+            node_color: "#4682b4".to_string(),
+            edge_color: "#555555".to_string(),
+            font_size: 12.0,
+            arrows: true,
+            node_label: None,
+            node_fill: None,
+            edge_stroke: None,
+        }
+    }
+}
+
+impl<'a, N, E> SvgStyle<'a, N, E> {
+    /// Draws a label (escaped for XML) in the center of every node.
+    pub fn node_label(mut self, label: impl Fn(NodeID, &N) -> String + 'a) -> Self {
+        self.node_label = Some(Box::new(label));
+        self
+    }
+
+    /// Chooses the fill color of every node, e.g. by category.
+    pub fn node_fill(mut self, fill: impl Fn(NodeID, &N) -> String + 'a) -> Self {
+        self.node_fill = Some(Box::new(fill));
+        self
+    }
+
+    /// Chooses the color of every edge, e.g. by weight.
+    pub fn edge_stroke(mut self, stroke: impl Fn(EdgeID, &E) -> String + 'a) -> Self {
+        self.edge_stroke = Some(Box::new(stroke));
+        self
+    }
+}
+
+/// Renders `graph` as an SVG document with the nodes at `positions`, see the [module documentation](self).
+pub fn render_svg<G: GraphRef>(
+    graph: &G,
+    positions: &SecondaryMap<NodeID, Point<2>>,
+    style: &SvgStyle<'_, G::NodeData, G::EdgeData>,
+) -> String {
+    let placed: Vec<(NodeID, Point<2>)> = graph
+        .nodes()
+        .filter_map(|id| Some((id, *positions.get(id)?)))
+        .collect();
+
+    // Translate the bounding box of the positions so that it starts at the margin.
+    let (mut min, mut max) = ([f64::INFINITY; 2], [f64::NEG_INFINITY; 2]);
+    for (_, point) in &placed {
+        for axis in 0..2 {
+            min[axis] = min[axis].min(point[axis]);
+            max[axis] = max[axis].max(point[axis]);
+        }
+    }
+    if placed.is_empty() {
+        (min, max) = ([0.0; 2], [0.0; 2]);
+    }
+    let offset = style.margin + style.node_radius;
+    let place = |point: Point<2>| [point[0] - min[0] + offset, point[1] - min[1] + offset];
+    let (width, height) = (
+        max[0] - min[0] + 2.0 * offset,
+        max[1] - min[1] + 2.0 * offset,
+    );
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{:.2}" height="{:.2}" viewBox="0 0 {:.2} {:.2}">"#,
+        width, height, width, height
+    );
+    if style.arrows {
+        let _ = writeln!(
+            svg,
+            r#"<defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="6" markerHeight="6" orient="auto"><path d="M 0 0 L 10 5 L 0 10 z" fill="{}"/></marker></defs>"#,
+            escape(&style.edge_color)
+        );
+    }
+    let marker = if style.arrows {
+        r#" marker-end="url(#arrow)""#
+    } else {
+        ""
+    };
+
+    let _ = writeln!(
+        svg,
+        r#"<g class="edges" fill="none" stroke-width="{:.2}">"#,
+        style.stroke_width
+    );
+    for (id, _) in &placed {
+        // Every edge is drawn once, from its source.
+        for edge in outgoing_edges(graph, *id) {
+            let edge_id = edge.id;
+            let Some(to) = positions
+                .get(edge.to)
+                .filter(|_| graph.node(edge.to).is_ok())
+            else {
+                continue;
+            };
+            let stroke = match &style.edge_stroke {
+                Some(hook) => hook(edge_id, &edge.data),
+                None => style.edge_color.clone(),
+            };
+            let [x1, y1] = place(positions[*id]);
+            let [x2, y2] = place(*to);
+            if edge.to == *id {
+                // Self-loops get a small loop above the node.
+                let r = style.node_radius;
+                let _ = writeln!(
+                    svg,
+                    r#"<circle cx="{:.2}" cy="{:.2}" r="{:.2}" stroke="{}"/>"#,
+                    x1,
+                    y1 - r,
+                    r * 0.75,
+                    escape(&stroke)
+                );
+                continue;
+            }
+            let length = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt();
+            if length <= 2.0 * style.node_radius {
+                // The circles overlap, there's no room for a line.
+                continue;
+            }
+            // Shorten the line so that it ends at the border of the target circle.
+            let (dx, dy) = ((x2 - x1) / length, (y2 - y1) / length);
+            let _ = writeln!(
+                svg,
+                r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" stroke="{}"{}/>"#,
+                x1 + dx * style.node_radius,
+                y1 + dy * style.node_radius,
+                x2 - dx * style.node_radius,
+                y2 - dy * style.node_radius,
+                escape(&stroke),
+                marker
+            );
+        }
+    }
+    svg.push_str("</g>\n");
+
+    let _ = writeln!(
+        svg,
+        r#"<g class="nodes" font-size="{:.2}" text-anchor="middle" dominant-baseline="central">"#,
+        style.font_size
+    );
+    for (id, point) in &placed {
+        let Ok(node) = graph.node(*id) else {
+            continue;
+        };
+        let [x, y] = place(*point);
+        let fill = match &style.node_fill {
+            Some(hook) => hook(*id, &node.data),
+            None => style.node_color.clone(),
+        };
+        let _ = writeln!(
+            svg,
+            r#"<circle cx="{:.2}" cy="{:.2}" r="{:.2}" fill="{}"/>"#,
+            x,
+            y,
+            style.node_radius,
+            escape(&fill)
+        );
+        if let Some(label) = &style.node_label {
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.2}" y="{:.2}">{}</text>"#,
+                x,
+                y,
+                escape(&label(*id, &node.data))
+            );
+        }
+    }
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
+
+/// Escapes text for use in XML content and attribute values.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Graph, GraphInterface};
+
+    #[test]
+    fn test_render_svg() {
+        let mut graph: Graph<&str, u32> = Graph::new();
+        let [a, b, c] = graph.add_nodes(&["a<b", "b", "no position"])[..] else {
+            unreachable!()
+        };
+        graph.add_edge(a, b, 1);
+        graph.add_edge(b, c, 2);
+        graph.add_edge(b, b, 3);
+
+        let mut positions = SecondaryMap::new();
+        positions.insert(a, [-50.0, 0.0]);
+        positions.insert(b, [50.0, 0.0]);
+
+        let style = SvgStyle {
+            arrows: false,
+            ..SvgStyle::default()
+        }
+        .node_label(|_, data: &&str| data.to_string())
+        .edge_stroke(|_, weight: &u32| format!("#00000{}", weight));
+        let svg = render_svg(&graph, &positions, &style);
+
+        // 100 wide plus margin and radius on both sides.
+        assert!(svg.contains(r#"width="160.00" height="60.00""#));
+        assert_eq!(svg.matches("<line").count(), 1);
+        assert!(svg.contains(
+            r##"<line x1="40.00" y1="30.00" x2="120.00" y2="30.00" stroke="#000001"/>"##
+        ));
+        // The self-loop (once, although it's listed twice in the connections), but nothing for the node without a position.
+        assert_eq!(svg.matches(r##"stroke="#000003""##).count(), 1);
+        assert!(!svg.contains("#000002") && !svg.contains("no position"));
+        assert!(svg.contains(">a&lt;b</text>") && !svg.contains("marker"));
+    }
+}
@@ -0,0 +1,203 @@
+//! # A bitset index over category memberships.
+//!
+//! When nodes belong to many categories, walking category nodes' edges to check a membership gets slow.
+//! [TagIndex] assigns every category node a bit position and stores one bitset per node, so checking a membership is O(1)
+//! and "in all of these categories" / "in any of these categories" queries are a few word-wise AND/OR operations per node.
+//!
+//! The index is a snapshot: it's built with [TagIndex::from_graph] and has to be kept up to date with [TagIndex::tag] and [TagIndex::untag]
+//! (or rebuilt) when memberships change.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::tags::TagIndex;
+//!
+//! let mut graph: CategorizedGraph<u32, ()> = CategorizedGraph::new();
+//! let nodes = graph.add_nodes(&[1, 2, 3]);
+//! let red = graph.add_to_category("Red", vec![nodes[0], nodes[1]]);
+//! let big = graph.add_to_category("Big", vec![nodes[1], nodes[2]]);
+//!
+//! let index = TagIndex::from_graph(&graph);
+//! assert!(index.has_tag(nodes[0], red));
+//! assert_eq!(index.nodes_with_all(&[red, big]), vec![nodes[1]]);
+//! assert_eq!(index.nodes_with_any(&[red, big]).len(), 3);
+//! ```
+
+use crate::hash::HashMap;
+use crate::*;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Maps category nodes to bit positions and stores the tags (category memberships) of every node as a bitset.
+#[derive(Clone, Debug, Default)]
+pub struct TagIndex {
+    bits: HashMap<NodeID, usize>,
+    categories: Vec<NodeID>,
+    tags: HashMap<NodeID, Vec<u64>>,
+}
+
+impl TagIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds the index from all categories of a graph and their current members.
+    pub fn from_graph<N, E, C, K, G: Categorized<N, E, C, K>>(graph: &G) -> Self {
+        let mut index = TagIndex::new();
+        for (_, category) in graph.all_categories() {
+            index.add_category(category);
+            for node in graph.nodes_by_category_id(category) {
+                index.tag(node, category);
+            }
+        }
+        index
+    }
+
+    /// Returns the bit position of a category, assigning the next free one if the category isn't indexed yet.
+    pub fn add_category(&mut self, category: NodeID) -> usize {
+        if let Some(bit) = self.bits.get(&category) {
+            return *bit;
+        }
+        let bit = self.categories.len();
+        self.categories.push(category);
+        self.bits.insert(category, bit);
+        bit
+    }
+
+    /// Returns the bit position of a category, if it's indexed.
+    pub fn bit(&self, category: NodeID) -> Option<usize> {
+        self.bits.get(&category).copied()
+    }
+
+    /// Returns the number of indexed categories.
+    pub fn category_count(&self) -> usize {
+        self.categories.len()
+    }
+
+    /// Marks `node` as a member of `category`, indexing the category first if needed.
+    pub fn tag(&mut self, node: NodeID, category: NodeID) {
+        let bit = self.add_category(category);
+        let words = self.tags.entry(node).or_default();
+        if words.len() <= bit / WORD_BITS {
+            words.resize(bit / WORD_BITS + 1, 0);
+        }
+        words[bit / WORD_BITS] |= 1 << (bit % WORD_BITS);
+    }
+
+    /// Removes `category` from the tags of `node`. Returns true if the node had the tag.
+    pub fn untag(&mut self, node: NodeID, category: NodeID) -> bool {
+        let (Some(bit), Some(words)) = (self.bit(category), self.tags.get_mut(&node)) else {
+            return false;
+        };
+        match words.get_mut(bit / WORD_BITS) {
+            Some(word) if *word & (1 << (bit % WORD_BITS)) != 0 => {
+                *word &= !(1 << (bit % WORD_BITS));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if `node` is tagged with `category`. This is O(1).
+    pub fn has_tag(&self, node: NodeID, category: NodeID) -> bool {
+        match (self.bit(category), self.tags.get(&node)) {
+            (Some(bit), Some(words)) => words
+                .get(bit / WORD_BITS)
+                .is_some_and(|word| word & (1 << (bit % WORD_BITS)) != 0),
+            _ => false,
+        }
+    }
+
+    /// Returns the categories `node` is tagged with, in bit order.
+    pub fn tags_of(&self, node: NodeID) -> Vec<NodeID> {
+        let Some(words) = self.tags.get(&node) else {
+            return Vec::new();
+        };
+        (0..self.categories.len())
+            .filter(|bit| {
+                words
+                    .get(bit / WORD_BITS)
+                    .is_some_and(|word| word & (1 << (bit % WORD_BITS)) != 0)
+            })
+            .map(|bit| self.categories[bit])
+            .collect()
+    }
+
+    /// Returns the nodes tagged with every one of `categories`. Returns no nodes if one of the categories isn't indexed.
+    pub fn nodes_with_all(&self, categories: &[NodeID]) -> Vec<NodeID> {
+        let Some(mask) = self.mask(categories, true) else {
+            return Vec::new();
+        };
+        self.tags
+            .iter()
+            .filter(|(_, words)| {
+                mask.iter()
+                    .enumerate()
+                    .all(|(i, m)| words.get(i).copied().unwrap_or(0) & m == *m)
+            })
+            .map(|(node, _)| *node)
+            .collect()
+    }
+
+    /// Returns the nodes tagged with at least one of `categories`. Categories that aren't indexed are ignored.
+    pub fn nodes_with_any(&self, categories: &[NodeID]) -> Vec<NodeID> {
+        let Some(mask) = self.mask(categories, false) else {
+            return Vec::new();
+        };
+        self.tags
+            .iter()
+            .filter(|(_, words)| words.iter().zip(&mask).any(|(word, m)| word & m != 0))
+            .map(|(node, _)| *node)
+            .collect()
+    }
+
+    /// Builds a bitset of the given categories. Returns `None` if a category isn't indexed and `strict` is set.
+    fn mask(&self, categories: &[NodeID], strict: bool) -> Option<Vec<u64>> {
+        let mut mask = vec![0u64; self.categories.len().div_ceil(WORD_BITS)];
+        for category in categories {
+            match self.bit(*category) {
+                Some(bit) => mask[bit / WORD_BITS] |= 1 << (bit % WORD_BITS),
+                None if strict => return None,
+                None => {}
+            }
+        }
+        Some(mask)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tags_across_word_boundaries() {
+        let mut graph: CategorizedGraph<u32, (), u32> = CategorizedGraph::new();
+        let node = graph.add_node(0);
+        let other = graph.add_node(1);
+        let categories: Vec<NodeID> = (0..100)
+            .map(|i| graph.add_to_category(&i, vec![node]))
+            .collect();
+        graph.add_to_category(&99, vec![other]);
+
+        let mut index = TagIndex::from_graph(&graph);
+        assert_eq!(index.category_count(), 100);
+        assert_eq!(index.tags_of(node).len(), 100);
+        assert!(index.has_tag(other, categories[99]));
+        assert!(!index.has_tag(other, categories[0]));
+
+        let mut both = index.nodes_with_all(&[categories[99]]);
+        both.sort();
+        let mut expected = vec![node, other];
+        expected.sort();
+        assert_eq!(both, expected);
+        assert_eq!(
+            index.nodes_with_all(&[categories[0], categories[99]]),
+            vec![node]
+        );
+
+        assert!(index.untag(node, categories[0]));
+        assert!(!index.untag(node, categories[0]));
+        assert!(index.nodes_with_any(&[categories[0]]).is_empty());
+        assert!(index.nodes_with_all(&[NodeID::default()]).is_empty());
+    }
+}
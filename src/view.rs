@@ -0,0 +1,281 @@
+//! # Read-only views of a graph.
+//!
+//! [SimpleView] presents a multigraph as a simple graph: all edges with the same `from` and `to` (parallel edges) are merged into one,
+//! combining their data with a closure. Algorithms that assume at most one edge per direction between two nodes can then run
+//! on the view, without deduplicating the original graph like [Graph::dedup_edges] does.
+//!
+//! Nothing is merged up front: [SimpleView::new] only registers the nodes, and the outgoing edges of a node are merged the first
+//! time they are needed (when the node, one of its edges or one of its neighbors is accessed), then kept for later calls.
+//! So an algorithm that only visits part of the graph only pays for that part.
+//!
+//! The view keeps the [NodeID]s of the graph (node data is borrowed, so its type is `&N`). A merged edge takes the [EdgeID] of the first
+//! edge of its group (in the connection order of the source), and [SimpleView::original_edges] maps it back to all of them.
+//!
+//! # Example
+//! ```
+//! use fast_graph::*;
+//! use fast_graph::view::SimpleView;
+//!
+//! let mut graph: Graph<&str, u32> = Graph::new();
+//! let [a, b] = graph.add_nodes(&["a", "b"])[..] else { unreachable!() };
+//! graph.add_edges_with_data(&[(a, b, 2), (a, b, 3), (b, a, 1)]);
+//!
+//! // Parallel capacities add up.
+//! let view = SimpleView::new(&graph, |total, capacity| *total += capacity);
+//! let edges: Vec<&Edge<u32>> = view.edge_references().collect();
+//! assert_eq!(edges.len(), 2);
+//! assert_eq!((edges[0].from, edges[0].to, edges[0].data), (a, b, 5));
+//! assert_eq!(view.original_edges(edges[0].id).len(), 2);
+//! assert_eq!(view.node(a).unwrap().data, &"a");
+//! ```
+
+use std::sync::OnceLock;
+
+use slotmap::SecondaryMap;
+
+use crate::hash::HashMap;
+use crate::*;
+
+/* -------------------------------------------------------------------------- */
+/*                                 SimpleView                                 */
+/* -------------------------------------------------------------------------- */
+
+/// A view of a graph with its parallel edges merged, see the [module documentation](self).
+///
+/// The view implements [GraphRef], so it can be passed to any algorithm.
+pub struct SimpleView<'a, G: GraphRef, F> {
+    graph: &'a G,
+    merge: F,
+    cache: SecondaryMap<NodeID, Cached<&'a G::NodeData, G::EdgeData>>,
+}
+
+/// What the view has built for a node so far.
+struct Cached<N, E> {
+    node: OnceLock<Node<N>>,
+    outgoing: OnceLock<Merged<E>>,
+}
+
+/// The merged outgoing edges of a node, each with the edges of the graph merged into it.
+struct Merged<E> {
+    edges: Vec<(Edge<E>, Vec<EdgeID>)>,
+    /// The position of every merged edge in `edges`.
+    positions: HashMap<EdgeID, usize>,
+}
+
+impl<'a, G: GraphRef, F> SimpleView<'a, G, F>
+where
+    G::EdgeData: Clone,
+    F: Fn(&mut G::EdgeData, &G::EdgeData),
+{
+    /// Creates the view in O(nodes). The first edge of every group of parallel edges is cloned, and the data of every other
+    /// edge of the group is passed to `merge` together with the merged data so far, e.g. to sum weights.
+    ///
+    /// Self-loops are kept (parallel self-loops are merged too). Connections to removed edges and edges to nodes that don't exist are skipped.
+    pub fn new(graph: &'a G, merge: F) -> Self {
+        let mut cache = SecondaryMap::with_capacity(graph.node_count());
+        for id in graph.nodes() {
+            if graph.contains_node(id) {
+                cache.insert(
+                    id,
+                    Cached {
+                        node: OnceLock::new(),
+                        outgoing: OnceLock::new(),
+                    },
+                );
+            }
+        }
+        SimpleView {
+            graph,
+            merge,
+            cache,
+        }
+    }
+
+    /// Returns the graph the view was built from.
+    pub fn graph(&self) -> &'a G {
+        self.graph
+    }
+
+    /// Returns the edges of the graph that were merged into an edge of the view, in the connection order of their source.
+    pub fn original_edges(&self, id: EdgeID) -> &[EdgeID] {
+        self.merged_edge(id)
+            .map_or(&[], |(_, original)| original.as_slice())
+    }
+
+    /// Returns a reference to every edge of the view. This merges the edges of every node.
+    pub fn edge_references(&self) -> impl Iterator<Item = &Edge<G::EdgeData>> + Clone {
+        let edges: Vec<&Edge<G::EdgeData>> = self
+            .cache
+            .keys()
+            .filter_map(|from| self.outgoing(from))
+            .flat_map(|merged| merged.edges.iter().map(|(edge, _)| edge))
+            .collect();
+        edges.into_iter()
+    }
+
+    /// Returns the merged outgoing edges of `from`, merging them on first use. `None` if the node isn't in the view.
+    fn outgoing(&self, from: NodeID) -> Option<&Merged<G::EdgeData>> {
+        let cached = self.cache.get(from)?;
+        Some(cached.outgoing.get_or_init(|| {
+            let mut merged = Merged {
+                edges: Vec::new(),
+                positions: HashMap::default(),
+            };
+            let mut by_target: HashMap<NodeID, usize> = HashMap::default();
+            for edge in outgoing_edges(self.graph, from) {
+                if !self.cache.contains_key(edge.to) {
+                    continue;
+                }
+                match by_target.get(&edge.to) {
+                    Some(i) => {
+                        let (view_edge, original) = &mut merged.edges[*i];
+                        (self.merge)(&mut view_edge.data, &edge.data);
+                        original.push(edge.id);
+                    }
+                    None => {
+                        by_target.insert(edge.to, merged.edges.len());
+                        merged.positions.insert(edge.id, merged.edges.len());
+                        merged.edges.push((
+                            Edge::new(edge.id, from, edge.to, edge.data.clone()),
+                            vec![edge.id],
+                        ));
+                    }
+                }
+            }
+            merged
+        }))
+    }
+
+    /// Returns the merged edge with the given ID and the edges merged into it.
+    fn merged_edge(&self, id: EdgeID) -> Option<&(Edge<G::EdgeData>, Vec<EdgeID>)> {
+        let merged = self.outgoing(self.graph.edge(id).ok()?.from)?;
+        merged.positions.get(&id).map(|i| &merged.edges[*i])
+    }
+}
+
+impl<'a, G: GraphRef, F> GraphRef for SimpleView<'a, G, F>
+where
+    G::EdgeData: Clone,
+    F: Fn(&mut G::EdgeData, &G::EdgeData),
+{
+    type NodeData = &'a G::NodeData;
+    type EdgeData = G::EdgeData;
+
+    fn nodes(&self) -> impl Iterator<Item = NodeID> {
+        self.cache.keys()
+    }
+
+    fn node_count(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Merges the outgoing edges of the node and of its neighbors with edges to it, on first use.
+    fn node(&self, id: NodeID) -> Result<&Node<&'a G::NodeData>, GraphError> {
+        let cached = self
+            .cache
+            .get(id)
+            .ok_or_else(|| GraphError::node_not_found(id))?;
+        Ok(cached.node.get_or_init(|| {
+            let original = self
+                .graph
+                .node(id)
+                .expect("the view only has nodes of the graph");
+            let mut node = Node::new(id, &original.data);
+            // A merged edge is listed where its first edge is, by both endpoints (so a self-loop is listed twice, like in a graph).
+            for edge_id in &original.connections {
+                if let Ok(edge) = self.graph.edge(*edge_id) {
+                    let merged = self.outgoing(edge.from);
+                    if merged.is_some_and(|merged| merged.positions.contains_key(edge_id)) {
+                        node.connections.push(*edge_id);
+                    }
+                }
+            }
+            node
+        }))
+    }
+
+    fn edge(&self, id: EdgeID) -> Result<&Edge<G::EdgeData>, GraphError> {
+        self.merged_edge(id)
+            .map(|(edge, _)| edge)
+            .ok_or_else(|| GraphError::edge_not_found(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::toposort;
+
+    #[test]
+    fn test_simple_view() {
+        let mut graph: Graph<u32, u32> = Graph::new();
+        let nodes = graph.add_nodes(&[0, 1, 2]);
+        let edges = graph.add_edges_with_data(&[
+            (nodes[0], nodes[1], 1),
+            (nodes[0], nodes[1], 10),
+            (nodes[1], nodes[2], 4),
+            (nodes[0], nodes[1], 100),
+            (nodes[2], nodes[2], 7),
+            (nodes[2], nodes[2], 8),
+        ]);
+        let removed = graph.add_edge(nodes[1], nodes[0], 0);
+        graph.remove_edge(removed).unwrap();
+
+        let view = SimpleView::new(&graph, |max, weight| *max = (*max).max(*weight));
+        assert_eq!(view.node_count(), 3);
+        let merged: Vec<(NodeID, NodeID, u32, usize)> = view
+            .edge_references()
+            .map(|edge| {
+                (
+                    edge.from,
+                    edge.to,
+                    edge.data,
+                    view.original_edges(edge.id).len(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            merged,
+            vec![
+                (nodes[0], nodes[1], 100, 3),
+                (nodes[1], nodes[2], 4, 1),
+                (nodes[2], nodes[2], 8, 2)
+            ]
+        );
+        let first = view.node(nodes[0]).unwrap().connections[0];
+        assert_eq!(view.original_edges(first), &[edges[0], edges[1], edges[3]]);
+        // A self-loop is listed twice, like in a graph.
+        assert_eq!(view.node(nodes[2]).unwrap().connections.len(), 3);
+
+        // The graph itself is untouched, and algorithms run on the view.
+        assert_eq!(graph.edges.len(), 6);
+        graph.remove_edge(edges[4]).unwrap();
+        graph.remove_edge(edges[5]).unwrap();
+        let view = SimpleView::new(&graph, |_, _| {});
+        assert_eq!(toposort(&view).unwrap(), nodes);
+    }
+
+    #[test]
+    fn test_simple_view_merges_on_demand() {
+        let mut graph: Graph<u32, u32> = Graph::new();
+        let nodes = graph.add_nodes(&[0, 1, 2, 3]);
+        let edges = graph.add_edges_with_data(&[
+            (nodes[0], nodes[1], 1),
+            (nodes[0], nodes[1], 2),
+            (nodes[2], nodes[3], 3),
+        ]);
+        let view = SimpleView::new(&graph, |total, weight| *total += weight);
+        let merged = |id: NodeID| view.cache[id].outgoing.get().is_some();
+        assert!(!merged(nodes[0]));
+
+        // The target of an edge has to know which of its incoming edges are merged, so only their sources are merged.
+        let node = view.node(nodes[1]).unwrap();
+        assert_eq!(&node.connections[..], &[edges[0]]);
+        assert!(merged(nodes[0]));
+        assert!(!merged(nodes[2]) && !merged(nodes[3]));
+
+        assert_eq!(view.edge(edges[0]).unwrap().data, 3);
+        assert!(view.edge(edges[1]).is_err());
+        assert!(!merged(nodes[2]));
+    }
+}
@@ -0,0 +1,145 @@
+//! Runs every algorithm through a function that is generic over [GraphInterface], so none of them can end up only accepting [Graph].
+
+use fast_graph::algorithms::*;
+use fast_graph::*;
+
+/// Adds a weighted diamond with a shortcut, 0 → 1 → 2 → 3 plus 0 → 2 and 1 → 3, and an isolated node.
+fn build<G: GraphInterface<NodeData = u32, EdgeData = u32>>(graph: &mut G) -> Vec<NodeID> {
+    let nodes = graph.add_nodes(&[0, 1, 2, 3, 4]);
+    graph.add_edges_with_data(&[
+        (nodes[0], nodes[1], 1),
+        (nodes[0], nodes[2], 4),
+        (nodes[1], nodes[2], 1),
+        (nodes[2], nodes[3], 1),
+        (nodes[1], nodes[3], 5),
+    ]);
+    nodes
+}
+
+fn check_algorithms<G: GraphInterface<NodeData = u32, EdgeData = u32>>(mut graph: G) {
+    let nodes = build(&mut graph);
+    let (start, end, isolated) = (nodes[0], nodes[3], nodes[4]);
+
+    // Traversals.
+    assert_eq!(graph.iter_depth_first(start).count(), 4);
+    assert_eq!(
+        graph
+            .iter_depth_first_filtered(start, |id| id != nodes[1])
+            .count(),
+        3
+    );
+    assert_eq!(DepthFirstSearch::new(&graph, isolated).count(), 1);
+    assert_eq!(graph.connected_components().len(), 2);
+    let mut map = graph.visit_map();
+    let mut search = DepthFirstSearch::new(&graph, start).with_visit_map(map);
+    assert_eq!(search.by_ref().count(), 4);
+    map = search.into_visit_map();
+    assert!(map.is_visited(end));
+    assert!(!map.is_visited(isolated));
+
+    let all: Vec<(usize, NodeID)> = graph.iter_depth_first_all().collect();
+    assert_eq!(all.len(), 5);
+    let search_of = |id: NodeID| all.iter().find(|(_, node)| *node == id).unwrap().0;
+    assert_eq!(search_of(start), search_of(end));
+    assert_ne!(search_of(start), search_of(isolated));
+
+    let post_order: Vec<NodeID> = graph.iter_depth_first_post_order(start).collect();
+    assert_eq!(post_order.len(), 4);
+    assert_eq!(post_order[0], end);
+    assert_eq!(post_order[3], start);
+    let reverse: Vec<NodeID> = graph.reverse_post_order(start).collect();
+    assert_eq!(reverse, nodes[..4]);
+
+    let kinds: Vec<EdgeKind> = graph
+        .iter_depth_first_edges(start)
+        .map(|(_, kind)| kind)
+        .collect();
+    assert_eq!(kinds.len(), 5);
+    assert_eq!(
+        kinds.iter().filter(|kind| **kind == EdgeKind::Tree).count(),
+        3
+    );
+    assert!(!kinds.contains(&EdgeKind::Back));
+    assert_eq!(DepthFirstEdges::all(&graph).count(), 5);
+
+    let breadth_first: Vec<NodeID> = BreadthFirst::new(&graph, start).collect();
+    assert_eq!(breadth_first[0], start);
+    assert_eq!(breadth_first.len(), 4);
+    assert_eq!(bfs_distances(&graph, start)[&end], 2);
+    assert_eq!(bfs_layers(&graph, start).len(), 3);
+    for order in [TraversalOrder::BreadthFirst, TraversalOrder::DepthFirst] {
+        let store: std::collections::VecDeque<Vec<NodeID>> = Default::default();
+        let bounded = BoundedTraversal::new(&graph, start, order, 2, store);
+        assert_eq!(bounded.count(), 4);
+    }
+
+    // Shortest paths.
+    let dijkstra: Vec<(NodeID, u32)> = BestFirst::with_cost(
+        &graph,
+        start,
+        0,
+        |cost, edge| cost + edge.data,
+        |_, cost| *cost,
+    )
+    .collect();
+    assert_eq!(dijkstra.last(), Some(&(end, 3)));
+    let hierarchy = ContractionHierarchy::build(&graph, |edge: &Edge<u32>| edge.data);
+    assert_eq!(hierarchy.distance(start, end), Some(3));
+    assert_eq!(hierarchy.shortest_path(start, end).unwrap().nodes.len(), 4);
+    assert_eq!(hierarchy.distance(end, start), None);
+
+    // Structure.
+    let index = ReachabilityIndex::build(&graph);
+    assert!(index.can_reach(start, end));
+    assert!(!index.can_reach(end, start));
+    assert!(!index.can_reach(start, isolated));
+
+    let order = toposort(&graph).unwrap();
+    let position = |id: NodeID| order.iter().position(|other| *other == id).unwrap();
+    assert_eq!(order.len(), 5);
+    assert!(position(nodes[0]) < position(nodes[1]));
+    assert!(position(nodes[1]) < position(nodes[2]));
+    assert!(position(nodes[2]) < position(nodes[3]));
+    let by_data = toposort_by_key(&graph, |_, data| std::cmp::Reverse(*data)).unwrap();
+    assert_eq!(by_data[0], isolated);
+
+    assert_eq!(triangle_count(&graph, TriangleMethod::Exact), 2.0);
+    let clusters = single_linkage(&graph, |_, edge| edge.data).clusters(2);
+    assert_eq!(clusters[&start], clusters[&end]);
+    assert_ne!(clusters[&start], clusters[&isolated]);
+
+    let order = reverse_cuthill_mckee(&graph);
+    assert_eq!(order.len(), 5);
+    assert!(bandwidth(&graph, &order) <= 2);
+    assert_eq!(degree_order(&graph)[0], isolated);
+}
+
+#[test]
+fn test_algorithms_on_graph() {
+    check_algorithms(Graph::new());
+}
+
+#[cfg(feature = "categories")]
+#[test]
+fn test_algorithms_on_categorized_graph() {
+    check_algorithms(CategorizedGraph::<u32, u32>::new());
+}
+
+#[test]
+fn test_algorithms_on_persistent_graph() {
+    check_algorithms(PersistentGraph::new());
+}
+
+#[cfg(feature = "categories")]
+#[test]
+fn test_algorithms_see_category_nodes() {
+    let mut graph: CategorizedGraph<u32, u32> = CategorizedGraph::new();
+    let nodes = build(&mut graph);
+    let category = graph.add_to_category("Numbers", vec![nodes[0], nodes[4]]);
+
+    // A category node is an ordinary node with edges to its members.
+    assert_eq!(toposort(&graph).unwrap()[0], category);
+    assert_eq!(bfs_distances(&graph, category)[&nodes[3]], 3);
+    assert_eq!(graph.iter_depth_first(category).count(), 6);
+    assert!(ReachabilityIndex::build(&graph).can_reach(category, nodes[3]));
+}
@@ -0,0 +1,531 @@
+#![cfg(feature = "categories")]
+
+use fast_graph::*;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+enum NodeData {
+    Number(u32),
+    Category(&'static str),
+    #[default]
+    None,
+}
+
+fn graph_with_category() -> (CategorizedGraph<NodeData, ()>, Vec<NodeID>, NodeID) {
+    let mut graph: CategorizedGraph<NodeData, ()> = CategorizedGraph::new();
+    let nodes = graph.add_nodes(&[
+        NodeData::Number(1),
+        NodeData::Number(2),
+        NodeData::Number(3),
+    ]);
+    let category = graph.add_to_category("Numbers", nodes.clone());
+    (graph, nodes, category)
+}
+
+#[test]
+fn test_delete_category_keep_members() {
+    let (mut graph, nodes, category) = graph_with_category();
+
+    let members = graph
+        .delete_category("Numbers", DeletePolicy::KeepMembers)
+        .unwrap();
+
+    assert_eq!(members, nodes);
+    assert!(!graph.category_exists("Numbers"));
+    assert!(graph.node(category).is_err());
+    assert_eq!(graph.node_count(), 3);
+    assert_eq!(graph.edges.len(), 0);
+    for node in nodes {
+        assert!(graph.node(node).unwrap().connections.is_empty());
+    }
+}
+
+#[test]
+fn test_delete_category_cascade() {
+    let (mut graph, nodes, _) = graph_with_category();
+    let other = graph.add_to_category("Other", vec![nodes[0]]);
+
+    graph
+        .delete_category("Numbers", DeletePolicy::Cascade)
+        .unwrap();
+
+    assert_eq!(graph.node_count(), 1);
+    assert!(graph.node(other).unwrap().connections.is_empty());
+    assert!(graph.nodes_by_category("Other").is_empty());
+    assert!(matches!(
+        graph.delete_category("Numbers", DeletePolicy::Cascade),
+        Err(CategorizedGraphError::CategoryNotFound(_))
+    ));
+}
+
+#[test]
+fn test_rename_category() {
+    let (mut graph, nodes, category) = graph_with_category();
+    graph.add_to_category("Taken", vec![]);
+
+    assert_eq!(
+        graph.rename_category("Numbers", "Digits").unwrap(),
+        category
+    );
+    assert!(!graph.category_exists("Numbers"));
+    assert_eq!(graph.nodes_by_category("Digits"), nodes);
+
+    assert!(matches!(
+        graph.rename_category("Digits", "Taken"),
+        Err(CategorizedGraphError::CategoryAlreadyExists(_))
+    ));
+    assert!(matches!(
+        graph.rename_category("Numbers", "Anything"),
+        Err(CategorizedGraphError::CategoryNotFound(_))
+    ));
+    assert_eq!(graph.category("Digits").unwrap().data, NodeData::None);
+}
+
+#[test]
+fn test_create_category_registers_data_node() {
+    let mut graph: CategorizedGraph<NodeData, ()> = CategorizedGraph::new();
+    let nodes = graph.add_nodes(&[NodeData::Number(1), NodeData::Number(2)]);
+
+    let category = graph
+        .create_category("Pair", nodes.clone(), NodeData::Category("Pair"))
+        .unwrap();
+
+    // Exactly one node was added for the category, and it's the registered one.
+    assert_eq!(graph.node_count(), 3);
+    assert_eq!(graph.category_id_by_name("Pair"), Some(&category));
+    assert_eq!(
+        graph.category("Pair").unwrap().data,
+        NodeData::Category("Pair")
+    );
+    assert_eq!(graph.nodes_by_category("Pair"), nodes);
+    assert_eq!(graph.nodes_by_category_id(category), nodes);
+
+    assert!(graph
+        .create_category("Pair", vec![], NodeData::Category("Pair"))
+        .is_err());
+    assert_eq!(graph.node_count(), 3);
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+enum Parity {
+    Even,
+    Odd,
+}
+
+#[test]
+fn test_enum_category_keys() {
+    let mut graph: CategorizedGraph<u32, (), Parity> = CategorizedGraph::new();
+    let nodes = graph.add_nodes(&[1, 2, 3, 4]);
+
+    let odd = graph
+        .create_category(&Parity::Odd, vec![nodes[0], nodes[2]], 0)
+        .unwrap();
+    let even = graph.add_to_category(&Parity::Even, vec![nodes[1], nodes[3]]);
+
+    assert_eq!(graph.category_id_by_name(&Parity::Odd), Some(&odd));
+    assert_eq!(
+        graph.nodes_by_category(&Parity::Even),
+        vec![nodes[1], nodes[3]]
+    );
+    assert!(graph.create_category(&Parity::Even, vec![], 0).is_err());
+
+    graph
+        .delete_category(&Parity::Even, DeletePolicy::KeepMembers)
+        .unwrap();
+    assert!(!graph.category_exists(&Parity::Even));
+    assert!(graph.node(even).is_err());
+}
+
+#[test]
+fn test_integer_category_keys() {
+    let mut graph: CategorizedGraph<u32, (), u64> = CategorizedGraph::new();
+    let node = graph.add_node(1);
+    let category = graph.add_to_category(&7, vec![node]);
+
+    assert_eq!(graph.rename_category(&7, &8).unwrap(), category);
+    assert_eq!(graph.nodes_by_categories(vec![&7, &8]), vec![node]);
+    assert_eq!(graph.all_categories(), vec![(&8, category)]);
+}
+
+#[test]
+fn test_iter_depth_first_in_category() {
+    let mut graph: CategorizedGraph<u32, ()> = CategorizedGraph::new();
+    let nodes = graph.add_nodes(&[0, 1, 2, 3]);
+    graph.add_edges(&[
+        (nodes[0], nodes[1]),
+        (nodes[1], nodes[2]),
+        (nodes[0], nodes[3]),
+    ]);
+    graph.add_to_category("Group", vec![nodes[0], nodes[1], nodes[3]]);
+
+    let mut visited: Vec<NodeID> = graph
+        .iter_depth_first_in_category(nodes[0], "Group")
+        .collect();
+    visited.sort();
+    let mut expected = vec![nodes[0], nodes[1], nodes[3]];
+    expected.sort();
+    assert_eq!(visited, expected);
+
+    assert_eq!(
+        graph
+            .iter_depth_first_in_category(nodes[2], "Group")
+            .count(),
+        0
+    );
+    assert_eq!(
+        graph
+            .iter_depth_first_in_category(nodes[0], "Missing")
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn test_subgraph_of_category() {
+    let mut graph: CategorizedGraph<u32, &str> = CategorizedGraph::new();
+    let nodes = graph.add_nodes(&[0, 1, 2]);
+    graph.add_edge(nodes[0], nodes[1], "inside");
+    graph.add_edge(nodes[1], nodes[2], "outgoing");
+    graph.add_edge(nodes[1], nodes[1], "loop");
+    graph.add_to_category("Group", vec![nodes[0], nodes[1]]);
+
+    let (subgraph, mapping) = graph.subgraph_of_category("Group").unwrap();
+    assert_eq!(subgraph.node_count(), 2);
+    assert_eq!(subgraph.edges.len(), 2);
+    assert!(!mapping.contains_key(&nodes[2]));
+
+    let inside = subgraph
+        .edges
+        .values()
+        .find(|edge| edge.data == "inside")
+        .unwrap();
+    assert_eq!(inside.from, mapping[&nodes[0]]);
+    assert_eq!(inside.to, mapping[&nodes[1]]);
+    assert_eq!(subgraph.node(mapping[&nodes[1]]).unwrap().data, 1);
+
+    assert!(graph.subgraph_of_category("Missing").is_err());
+}
+
+#[test]
+fn test_subgraph_of_category_after_lazy_removal() {
+    let mut graph: CategorizedGraph<u32, &str> = CategorizedGraph::new();
+    graph.deletion_mode = DeletionMode::Lazy;
+    let nodes = graph.add_nodes(&[0, 1, 2]);
+    let removed = graph.add_edge(nodes[0], nodes[1], "removed");
+    graph.add_edge(nodes[1], nodes[0], "kept");
+    graph.add_to_category("Group", vec![nodes[0], nodes[1]]);
+    graph.remove_edge(removed).unwrap();
+    graph.remove_node(nodes[2]).unwrap();
+
+    let (subgraph, mapping) = graph.subgraph_of_category("Group").unwrap();
+    assert_eq!(subgraph.node_count(), 2);
+    assert_eq!(subgraph.edges.len(), 1);
+    let kept = subgraph.edges.values().next().unwrap();
+    assert_eq!(
+        (kept.from, kept.to),
+        (mapping[&nodes[1]], mapping[&nodes[0]])
+    );
+}
+
+#[test]
+fn test_all_categories_ordered() {
+    let mut graph: CategorizedGraph<u32, ()> = CategorizedGraph::new();
+    let names = ["Zeta", "Alpha", "Mu", "Beta"];
+    let ids: Vec<NodeID> = names
+        .iter()
+        .map(|name| graph.add_to_category(*name, vec![]))
+        .collect();
+
+    let ordered = |graph: &CategorizedGraph<u32, ()>| -> Vec<String> {
+        graph
+            .all_categories_ordered()
+            .into_iter()
+            .map(|(name, _)| name.clone())
+            .collect()
+    };
+    assert_eq!(ordered(&graph), names);
+
+    graph.rename_category("Alpha", "Omega").unwrap();
+    assert_eq!(ordered(&graph), ["Zeta", "Omega", "Mu", "Beta"]);
+
+    graph.move_category("Beta", 0).unwrap();
+    assert_eq!(ordered(&graph), ["Beta", "Zeta", "Omega", "Mu"]);
+
+    graph
+        .delete_category("Zeta", DeletePolicy::KeepMembers)
+        .unwrap();
+    assert_eq!(ordered(&graph), ["Beta", "Omega", "Mu"]);
+    assert!(!graph.category_order.contains(&ids[0]));
+    assert!(graph.move_category("Zeta", 0).is_err());
+}
+
+#[test]
+fn test_merge_categories() {
+    let (mut graph, nodes, _) = graph_with_category();
+    let other = graph
+        .create_category(
+            "Other",
+            vec![nodes[1], nodes[2]],
+            NodeData::Category("Other"),
+        )
+        .unwrap();
+
+    let merged = graph
+        .merge_categories("Numbers", "Other", "Merged", |a, b| match a {
+            NodeData::None => b.clone(),
+            _ => a.clone(),
+        })
+        .unwrap();
+
+    assert!(!graph.category_exists("Numbers"));
+    assert!(!graph.category_exists("Other"));
+    assert!(graph.node(other).is_err());
+    assert_eq!(graph.category_id_by_name("Merged"), Some(&merged));
+    assert_eq!(
+        graph.node(merged).unwrap().data,
+        NodeData::Category("Other")
+    );
+
+    let mut members = graph.nodes_by_category("Merged");
+    members.sort();
+    let mut expected = nodes.clone();
+    expected.sort();
+    assert_eq!(members, expected);
+
+    assert!(graph
+        .merge_categories("Merged", "Missing", "Merged", |a, _| a.clone())
+        .is_err());
+}
+
+#[test]
+fn test_category_events() {
+    use std::sync::{Arc, Mutex};
+
+    let mut graph: CategorizedGraph<u32, ()> = CategorizedGraph::new();
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&events);
+    graph.on_category_event(move |event| recorded.lock().unwrap().push(event.clone()));
+
+    let nodes = graph.add_nodes(&[1, 2]);
+    let category = graph.add_to_category("Group", vec![nodes[0]]);
+    graph.add_to_category("Group", vec![nodes[1]]);
+    graph.rename_category("Group", "Renamed").unwrap();
+    graph.remove_node(nodes[0]).unwrap();
+    graph
+        .delete_category("Renamed", DeletePolicy::KeepMembers)
+        .unwrap();
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![
+            CategoryEvent::Created {
+                category,
+                key: "Group".to_string()
+            },
+            CategoryEvent::MembersAdded {
+                category,
+                nodes: vec![nodes[0]]
+            },
+            CategoryEvent::MembersAdded {
+                category,
+                nodes: vec![nodes[1]]
+            },
+            CategoryEvent::Renamed {
+                category,
+                key: "Renamed".to_string()
+            },
+            CategoryEvent::MembersRemoved {
+                category,
+                nodes: vec![nodes[0]]
+            },
+            CategoryEvent::Deleted { category },
+        ]
+    );
+}
+
+#[test]
+fn test_category_nodes_iter() {
+    let (graph, nodes, _) = graph_with_category();
+
+    let members: Vec<NodeID> = graph
+        .category_nodes_iter("Numbers")
+        .map(|node| node.id)
+        .collect();
+    assert_eq!(members, nodes);
+
+    let data: Vec<&NodeData> = graph.category_data_iter("Numbers").collect();
+    assert_eq!(
+        data,
+        vec![
+            &NodeData::Number(1),
+            &NodeData::Number(2),
+            &NodeData::Number(3)
+        ]
+    );
+    assert_eq!(graph.category_nodes_iter("Missing").count(), 0);
+}
+
+#[test]
+fn test_add_to_category_or_create_with() {
+    // No `Default` needed for the node data.
+    #[derive(Clone, Debug, PartialEq)]
+    struct Label(&'static str);
+
+    let mut graph: CategorizedGraph<Label, ()> = CategorizedGraph::new();
+    let node = graph.add_node(Label("node"));
+
+    let category = graph.add_to_category_or_create_with("Group", vec![node], || Label("group"));
+    assert_eq!(graph.node(category).unwrap().data, Label("group"));
+
+    let again = graph.add_to_category_or_create_with("Group", vec![], || unreachable!());
+    assert_eq!(again, category);
+
+    let created = graph
+        .create_category("Other", vec![node], Label("other"))
+        .unwrap();
+    assert_eq!(graph.category("Other").unwrap().id, created);
+}
+
+#[test]
+fn test_category_node_policy() {
+    let (mut graph, nodes, category) = graph_with_category();
+
+    graph.category_node_policy = CategoryNodePolicy::Refuse;
+    assert!(matches!(
+        graph.remove_node(category),
+        Err(GraphError::IsCategoryNode(id)) if id == category
+    ));
+    assert!(graph.node(category).is_ok());
+    // Ordinary nodes can still be removed.
+    graph.remove_node(nodes[0]).unwrap();
+
+    graph.category_node_policy = CategoryNodePolicy::Unregister;
+    graph.remove_node(category).unwrap();
+    assert!(!graph.category_exists("Numbers"));
+    assert!(graph.all_categories_ordered().is_empty());
+}
+
+#[test]
+fn test_category_node_policy_after_rename() {
+    let (mut graph, nodes, category) = graph_with_category();
+    graph.category_node_policy = CategoryNodePolicy::Refuse;
+
+    graph.rename_category("Numbers", "Renamed").unwrap();
+    assert!(graph.remove_node(category).is_err());
+
+    graph.category_node_policy = CategoryNodePolicy::Unregister;
+    graph.remove_node(category).unwrap();
+    assert!(graph.categories.is_empty());
+    assert!(graph.all_categories_ordered().is_empty());
+    assert_eq!(graph.node_count(), nodes.len());
+}
+
+#[test]
+fn test_failed_delete_keeps_category() {
+    let (mut graph, _, category) = graph_with_category();
+    graph.category_node_policy = CategoryNodePolicy::Refuse;
+
+    // The node is gone but the category is still registered, so removing it fails.
+    graph.nodes.remove(category);
+    assert!(graph
+        .delete_category("Numbers", DeletePolicy::KeepMembers)
+        .is_err());
+    assert_eq!(graph.category_id_by_name("Numbers"), Some(&category));
+    assert_eq!(graph.all_categories_ordered().len(), 1);
+
+    // delete_category ignores the policy.
+    let (mut graph, nodes, _) = graph_with_category();
+    graph.category_node_policy = CategoryNodePolicy::Refuse;
+    assert_eq!(
+        graph
+            .delete_category("Numbers", DeletePolicy::KeepMembers)
+            .unwrap(),
+        nodes
+    );
+    assert!(!graph.category_exists("Numbers"));
+}
+
+#[test]
+fn test_lazy_deletion_mode() {
+    let (mut graph, nodes, category) = graph_with_category();
+    graph.deletion_mode = DeletionMode::Lazy;
+
+    graph.remove_node(nodes[0]).unwrap();
+    // The category node keeps a tombstone for the removed membership edge.
+    assert_eq!(graph.node(category).unwrap().connections.len(), 3);
+    assert_eq!(
+        graph.nodes_by_category_id(category),
+        vec![nodes[1], nodes[2]]
+    );
+
+    assert_eq!(graph.vacuum(), 1);
+    assert_eq!(graph.node(category).unwrap().connections.len(), 2);
+    assert_eq!(
+        graph.nodes_by_category_id(category),
+        vec![nodes[1], nodes[2]]
+    );
+}
+
+#[test]
+fn test_categorized_graph_equality() {
+    let (mut a, _, _) = graph_with_category();
+    let (mut b, nodes, _) = graph_with_category();
+    assert_graph_eq!(a, b);
+
+    // Same structure, but a different category key.
+    a.create_category("Other", vec![], NodeData::None).unwrap();
+    b.create_category("Renamed", vec![], NodeData::None)
+        .unwrap();
+    assert_ne!(a, b);
+
+    b.delete_category("Renamed", DeletePolicy::KeepMembers)
+        .unwrap();
+    b.create_category("Other", vec![nodes[0]], NodeData::None)
+        .unwrap();
+    assert_ne!(a, b);
+}
+
+#[test]
+fn test_categorized_graph_equality_ignores_order() {
+    let mut a: CategorizedGraph<NodeData, ()> = CategorizedGraph::new();
+    let nodes = a.add_nodes(&[NodeData::Number(1), NodeData::Number(1)]);
+    a.create_category("First", vec![nodes[0]], NodeData::None)
+        .unwrap();
+    a.create_category("Second", vec![nodes[1]], NodeData::None)
+        .unwrap();
+    a.create_category("Empty", vec![], NodeData::None).unwrap();
+
+    // The categories are created in a different order, and only their keys tell them apart.
+    let mut b: CategorizedGraph<NodeData, ()> = CategorizedGraph::new();
+    b.create_category("Empty", vec![], NodeData::None).unwrap();
+    let nodes = b.add_nodes(&[NodeData::Number(1), NodeData::Number(1)]);
+    b.create_category("Second", vec![nodes[0]], NodeData::None)
+        .unwrap();
+    b.create_category("First", vec![nodes[1]], NodeData::None)
+        .unwrap();
+    assert_graph_eq!(a, b);
+
+    b.add_to_category("First", vec![nodes[0]]);
+    assert_ne!(a, b);
+}
+
+#[test]
+fn test_clone_categorized_graph() {
+    let (mut graph, nodes, category) = graph_with_category();
+    graph.on_category_event(|_| {});
+
+    let clone = graph.clone();
+    assert_graph_eq!(clone, graph);
+    assert_eq!(clone.nodes_by_category_id(category), nodes);
+
+    graph.remove_node(nodes[0]).unwrap();
+    let (copy, mapping) = graph.clone_with_mapping();
+    assert_graph_eq!(copy, graph);
+    assert_eq!(
+        copy.category("Numbers").unwrap().id,
+        mapping.nodes[&category]
+    );
+    assert_eq!(
+        copy.nodes_by_category("Numbers"),
+        vec![mapping.nodes[&nodes[1]], mapping.nodes[&nodes[2]]]
+    );
+}
@@ -0,0 +1,346 @@
+use fast_graph::algorithms::IterDepthFirst;
+use fast_graph::*;
+
+#[test]
+fn test_compact_after_churn() {
+    let mut graph: Graph<u32, &str> = Graph::new();
+    let nodes = graph.add_nodes(&(0..10).collect::<Vec<u32>>());
+    for window in nodes.windows(2) {
+        graph.add_edge(window[0], window[1], "next");
+    }
+    let self_loop = graph.add_edge(nodes[9], nodes[9], "loop");
+    for node in nodes.iter().step_by(2).take(4) {
+        graph.remove_node(*node).unwrap();
+    }
+    graph.remove_edge(self_loop).unwrap();
+
+    let mapping = graph.compact();
+    assert_eq!(mapping.len(), 6);
+    assert_eq!(graph.node_count(), 6);
+    assert!(graph.validate().is_ok());
+
+    // Only the edges between surviving neighbours (7 -> 8 -> 9) are left.
+    assert_eq!(graph.edges.len(), 2);
+    assert!(graph
+        .edges
+        .values()
+        .any(|edge| edge.from == mapping[&nodes[8]] && edge.to == mapping[&nodes[9]]));
+    assert_eq!(graph.node(mapping[&nodes[9]]).unwrap().connections.len(), 1);
+    assert_eq!(graph.node(mapping[&nodes[8]]).unwrap().connections.len(), 2);
+
+    // Data stays with its node, and the new IDs are densely packed.
+    for (old, new) in &mapping {
+        assert_eq!(
+            graph.node(*new).unwrap().data,
+            nodes.iter().position(|id| id == old).unwrap() as u32
+        );
+        assert!((new.to_u64() as u32 as usize) <= graph.node_count());
+    }
+}
+
+#[test]
+fn test_vacuum_removes_stale_connections() {
+    let mut graph: Graph<u32, ()> = Graph::new();
+    let nodes = graph.add_nodes(&[0, 1, 2, 3]);
+    let edges = graph.add_edges(&[
+        (nodes[0], nodes[1]),
+        (nodes[1], nodes[2]),
+        (nodes[2], nodes[3]),
+    ]);
+
+    graph.remove_edge(edges[0]).unwrap();
+    graph.remove_node(nodes[3]).unwrap();
+    // Removals leave stale connections behind, which traversals skip.
+    assert_eq!(graph.node(nodes[1]).unwrap().connections.len(), 2);
+    assert_eq!(graph.iter_depth_first(nodes[0]).count(), 1);
+    assert_eq!(graph.iter_depth_first(nodes[1]).count(), 2);
+
+    // nodes[0] and nodes[1] lose edges[0], nodes[2] loses the edge to nodes[3].
+    assert_eq!(graph.vacuum(), 3);
+    assert_eq!(graph.node(nodes[1]).unwrap().connections.len(), 1);
+    assert_eq!(graph.vacuum(), 0);
+}
+
+#[test]
+fn test_errors_carry_ids_and_operation() {
+    let mut graph: Graph<u32, ()> = Graph::new();
+    let node = graph.add_node(1);
+    let edge = graph.add_edge(node, node, ());
+    graph.remove_node(node).unwrap();
+
+    assert!(matches!(
+        graph.node(node),
+        Err(GraphError::NodeNotFound { id, during: GraphOperation::Lookup }) if id == node
+    ));
+    let error = graph.remove_edge(edge).unwrap_err();
+    assert!(matches!(
+        error,
+        GraphError::EdgeNotFound { id, during: GraphOperation::RemoveEdge(_) } if id == edge
+    ));
+    assert_eq!(
+        error.to_string(),
+        format!("Edge {:?} not found while removing edge {:?}", edge, edge)
+    );
+}
+
+#[test]
+fn test_try_add_edge_rejects_dangling_edges() {
+    let mut graph: Graph<u32, ()> = Graph::new();
+    let [a, b] = graph.add_nodes(&[1, 2])[..] else {
+        unreachable!()
+    };
+    graph.remove_node(b).unwrap();
+
+    assert!(graph.try_add_edge(a, a, ()).is_ok());
+    assert!(matches!(
+        graph.try_add_edge(a, b, ()),
+        Err(GraphError::NodeNotFound { id, during: GraphOperation::AddEdge { .. } }) if id == b
+    ));
+    assert_eq!(graph.edges.len(), 1);
+
+    // Without strict mode `add_edge` still adds the dangling edge.
+    graph.add_edge(b, a, ());
+    assert_eq!(graph.edges.len(), 2);
+    assert!(graph.validate().is_err());
+}
+
+#[test]
+#[should_panic(expected = "while adding an edge")]
+fn test_strict_add_edge_panics_on_dangling_edge() {
+    let mut graph: Graph<u32, ()> = Graph::new();
+    graph.strict = true;
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    graph.add_edge(a, b, ());
+    graph.remove_node(b).unwrap();
+    graph.add_edge(a, b, ());
+}
+
+#[test]
+fn test_dedup_parallel_edges() {
+    let mut graph: Graph<(), u32> = Graph::new();
+    let nodes = graph.add_nodes(&[(); 3]);
+    let ab = graph.add_edge(nodes[0], nodes[1], 1);
+    let ab2 = graph.add_edge(nodes[0], nodes[1], 2);
+    let ba = graph.add_edge(nodes[1], nodes[0], 4);
+    let loop1 = graph.add_edge(nodes[2], nodes[2], 8);
+    let loop2 = graph.add_edge(nodes[2], nodes[2], 16);
+    let ab3 = graph.add_edge(nodes[0], nodes[1], 32);
+
+    assert_eq!(
+        graph.parallel_edge_groups(),
+        vec![vec![ab, ab2, ab3], vec![loop1, loop2]]
+    );
+    assert_eq!(graph.dedup_edges(|kept, other| *kept += other), 3);
+
+    assert!(graph.parallel_edge_groups().is_empty());
+    assert_eq!(graph.edge(ab).unwrap().data, 35);
+    assert_eq!(graph.edge(ba).unwrap().data, 4);
+    assert_eq!(graph.edge(loop1).unwrap().data, 24);
+    assert_eq!(graph.node(nodes[0]).unwrap().connections.len(), 2);
+    assert_eq!(graph.node(nodes[2]).unwrap().connections.len(), 2);
+    graph.validate().unwrap();
+}
+
+#[test]
+fn test_collect_dangling_edges() {
+    let mut graph: Graph<(), ()> = Graph::new();
+    let nodes = graph.add_nodes(&[(); 3]);
+    let kept = graph.add_edge(nodes[0], nodes[1], ());
+    let to_removed = graph.add_edge(nodes[0], nodes[2], ());
+    // Remove the node without going through `remove_node`, like a node whose connections were out of date.
+    graph.nodes.remove(nodes[2]);
+    let never_existed = graph.add_edge(nodes[1], NodeID::default(), ());
+
+    let mut dangling = graph.collect_dangling_edges();
+    dangling.sort();
+    let mut expected = vec![to_removed, never_existed];
+    expected.sort();
+    assert_eq!(dangling, expected);
+    assert!(graph.collect_dangling_edges().is_empty());
+    assert!(graph.edge(kept).is_ok());
+    assert_eq!(graph.node(nodes[0]).unwrap().connections.len(), 1);
+    graph.validate().unwrap();
+}
+
+#[test]
+fn test_reverse_and_redirect_edge() {
+    let mut graph: Graph<(), &str> = Graph::new();
+    let nodes = graph.add_nodes(&[(); 3]);
+    let edge = graph.add_edge(nodes[0], nodes[1], "kept");
+
+    graph.reverse_edge(edge).unwrap();
+    let reversed = graph.edge(edge).unwrap();
+    assert_eq!((reversed.from, reversed.to), (nodes[1], nodes[0]));
+
+    graph.redirect_edge(edge, nodes[2], nodes[2]).unwrap();
+    assert!(graph.node(nodes[0]).unwrap().connections.is_empty());
+    assert!(graph.node(nodes[1]).unwrap().connections.is_empty());
+    assert_eq!(
+        graph.node(nodes[2]).unwrap().connections.as_slice(),
+        &[edge, edge]
+    );
+    graph.validate().unwrap();
+
+    graph.redirect_edge(edge, nodes[2], nodes[0]).unwrap();
+    assert_eq!(graph.node(nodes[2]).unwrap().connections.len(), 1);
+    assert_eq!(graph.edge(edge).unwrap().data, "kept");
+    graph.validate().unwrap();
+
+    graph.remove_node(nodes[1]).unwrap();
+    let error = graph.redirect_edge(edge, nodes[1], nodes[0]).unwrap_err();
+    assert!(error.to_string().contains("while redirecting edge"));
+    assert_eq!(graph.edge(edge).unwrap().from, nodes[2]);
+}
+
+#[test]
+fn test_try_from_u64_checks_ids() {
+    let mut graph: Graph<(), ()> = Graph::new();
+    let nodes = graph.add_nodes(&[(); 2]);
+    let edge = graph.add_edge(nodes[0], nodes[1], ());
+
+    assert_eq!(
+        NodeID::try_from_u64(&graph, nodes[1].to_u64()).unwrap(),
+        nodes[1]
+    );
+    assert_eq!(EdgeID::try_from_u64(&graph, edge.to_u64()).unwrap(), edge);
+
+    // Versions are odd, so an even version never round-trips.
+    let even = (2u64 << 32) | 1;
+    assert!(matches!(
+        NodeID::try_from_u64(&graph, even),
+        Err(GraphError::InvalidId(id)) if id == even
+    ));
+
+    graph.remove_node(nodes[0]).unwrap();
+    assert!(matches!(
+        NodeID::try_from_u64(&graph, nodes[0].to_u64()),
+        Err(GraphError::NodeNotFound { id, .. }) if id == nodes[0]
+    ));
+    assert!(EdgeID::try_from_u64(&graph, edge.to_u64()).is_err());
+}
+
+#[test]
+fn test_edge_triples_and_references() {
+    let mut graph: Graph<(), u32> = Graph::new();
+    let nodes = graph.add_nodes(&[(); 3]);
+    let edges = graph.add_edges_with_data(&[(nodes[0], nodes[1], 1), (nodes[1], nodes[2], 2)]);
+    let removed = graph.add_edge(nodes[2], nodes[0], 3);
+    graph.remove_edge(removed).unwrap();
+
+    assert_eq!(
+        graph.edge_triples().collect::<Vec<_>>(),
+        vec![(nodes[0], nodes[1], &1), (nodes[1], nodes[2], &2)]
+    );
+    assert_eq!(
+        graph
+            .edge_references()
+            .map(|edge| edge.id)
+            .collect::<Vec<_>>(),
+        edges
+    );
+}
+
+#[test]
+fn test_clone_keeps_ids_and_clone_with_mapping_compacts() {
+    let mut graph: Graph<u32, &str> = Graph::new();
+    let nodes = graph.add_nodes(&[0, 1, 2, 3]);
+    let edges = graph.add_edges_with_data(&[(nodes[0], nodes[3], "a"), (nodes[3], nodes[1], "b")]);
+    graph.remove_node(nodes[2]).unwrap();
+
+    let mut clone = graph.clone();
+    for id in [nodes[0], nodes[1], nodes[3]] {
+        assert_eq!(clone.node(id).unwrap().data, graph.node(id).unwrap().data);
+    }
+    assert_eq!(clone.edge(edges[1]).unwrap().from, nodes[3]);
+    // IDs stay valid in the clone when the clone changes.
+    let added = clone.add_node(4);
+    assert!(clone.node(nodes[3]).is_ok());
+    assert!(graph.node(added).is_err());
+
+    let (copy, mapping) = graph.clone_with_mapping();
+    assert_graph_eq!(copy, graph);
+    assert_eq!(mapping.nodes.len(), 3);
+    assert!(!mapping.nodes.contains_key(&nodes[2]));
+    let edge = copy.edge(mapping.edges[&edges[0]]).unwrap();
+    assert_eq!(
+        (edge.from, edge.to, edge.data),
+        (mapping.nodes[&nodes[0]], mapping.nodes[&nodes[3]], "a")
+    );
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Relation {
+    Parent,
+    Sibling,
+}
+
+#[test]
+fn test_neighbors_where() {
+    let mut graph: Graph<&str, Relation> = Graph::new();
+    let [child, mother, father, sister] =
+        graph.add_nodes(&["child", "mother", "father", "sister"])[..]
+    else {
+        unreachable!()
+    };
+    graph.add_edges_with_data(&[
+        (child, mother, Relation::Parent),
+        (child, sister, Relation::Sibling),
+        (child, father, Relation::Parent),
+        (mother, child, Relation::Parent),
+        (child, child, Relation::Sibling),
+    ]);
+    let removed = graph.add_edge(child, sister, Relation::Parent);
+    graph.remove_edge(removed).unwrap();
+
+    let parents: Vec<NodeID> = graph
+        .neighbors_where(child, |relation| *relation == Relation::Parent)
+        .collect();
+    assert_eq!(parents, vec![mother, father]);
+    let siblings: Vec<NodeID> = graph
+        .neighbors_where(child, |relation| *relation == Relation::Sibling)
+        .collect();
+    assert_eq!(siblings, vec![sister, child]);
+    assert_eq!(graph.edges_where(child, |_| true).count(), 4);
+    assert!(graph
+        .edges_where(child, |_| true)
+        .all(|edge| edge.from == child));
+    graph.remove_node(mother).unwrap();
+    assert_eq!(graph.neighbors_where(mother, |_| true).count(), 0);
+}
+
+#[test]
+fn test_rows() {
+    let mut graph: Graph<&str, u32> = Graph::new();
+    let [a, b, c] = graph.add_nodes(&["a", "b", "c"])[..] else {
+        unreachable!()
+    };
+    let edges = graph.add_edges_with_data(&[(a, b, 1), (a, a, 2), (c, a, 3), (a, c, 4)]);
+    let removed = graph.add_edge(b, c, 5);
+    graph.remove_edge(removed).unwrap();
+
+    let rows: Vec<(NodeID, &str, Vec<_>)> = graph
+        .rows()
+        .map(|(id, data, edges)| {
+            (
+                id,
+                *data,
+                edges.map(|(edge, to, data)| (edge, to, *data)).collect(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        rows,
+        vec![
+            (
+                a,
+                "a",
+                vec![(edges[0], b, 1), (edges[1], a, 2), (edges[3], c, 4)]
+            ),
+            (b, "b", vec![]),
+            (c, "c", vec![(edges[2], a, 3)]),
+        ]
+    );
+    let edge_count: usize = graph.rows().map(|(_, _, edges)| edges.count()).sum();
+    assert_eq!(edge_count, graph.edges.len());
+}